@@ -0,0 +1,227 @@
+//! Snapshots the page of an entry into a single self-contained HTML file, with
+//! its stylesheets and images inlined, the way monolith does.
+
+use crate::{profile, text, CLIENT};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures::future::join_all;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    env,
+    error::Error,
+    fs,
+    hash::{Hash, Hasher},
+    path::{self, Path, PathBuf},
+    sync::Mutex,
+};
+use url::Url;
+
+const ARCHIVE_DIR: &str = "archive";
+
+lazy_static! {
+    static ref ARCHIVED: Mutex<HashSet<String>> = Mutex::new(file_names(&archive_dir()));
+    /// The snapshots saved before they were kept in the profile, not moved yet.
+    static ref LEGACY: Mutex<HashSet<String>> = Mutex::new(file_names(&legacy_archive_dir()));
+    static ref STYLESHEET: Regex =
+        Regex::new(r#"(?is)<link\b[^>]*?\brel\s*=\s*["']?stylesheet["']?[^>]*>"#).unwrap();
+    static ref HREF: Regex = Regex::new(r#"(?i)\bhref\s*=\s*["']([^"']+)["']"#).unwrap();
+    static ref IMG_SRC: Regex =
+        Regex::new(r#"(?i)(<img\b[^>]*?\bsrc\s*=\s*["'])([^"']+)(["'])"#).unwrap();
+}
+
+/// In the profile, with the rest of its state. Absolute, as the snapshots are
+/// opened through `file://` links.
+pub fn archive_dir() -> PathBuf {
+    let dir = profile::path(ARCHIVE_DIR);
+
+    path::absolute(&dir).unwrap_or(dir)
+}
+
+/// Where snapshots were saved before they were kept in the profile.
+fn legacy_archive_dir() -> PathBuf {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .unwrap_or_else(env::temp_dir)
+        .join("gluon_news")
+        .join("archive")
+}
+
+/// Entry IDs are often URLs, so the file is named after a hash of the ID,
+/// one that stays the same between builds, unlike that of `DefaultHasher`.
+fn file_name(entry_id: &str) -> String {
+    let hash: String = Sha256::digest(entry_id.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+
+    format!("{hash}.html")
+}
+
+/// The name snapshots were saved under before, which only builds with the
+/// same `DefaultHasher` can work out again.
+fn legacy_file_name(entry_id: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    entry_id.hash(&mut hasher);
+
+    format!("{:016x}.html", hasher.finish())
+}
+
+/// Moves the snapshot of the entry saved before into the profile, if there is
+/// one, returning whether there was.
+fn move_legacy(entry_id: &str) -> bool {
+    let name = legacy_file_name(entry_id);
+    if !LEGACY.lock().unwrap().remove(&name) {
+        return false;
+    }
+    let legacy = legacy_archive_dir().join(name);
+    let path = archive_path(entry_id);
+    let moved = fs::create_dir_all(archive_dir()).and_then(|_| {
+        fs::rename(&legacy, &path).or_else(|_| {
+            fs::copy(&legacy, &path)?;
+            fs::remove_file(&legacy)
+        })
+    });
+    if let Err(e) = moved {
+        tracing::warn!("could not move the archived page {}: {e}", legacy.display());
+        return false;
+    }
+
+    ARCHIVED.lock().unwrap().insert(file_name(entry_id));
+    true
+}
+
+fn file_names(dir: &Path) -> HashSet<String> {
+    fs::read_dir(dir)
+        .map(|read_dir| {
+            read_dir
+                .filter_map(|e| e.ok())
+                .filter_map(|e| e.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn archive_path(entry_id: &str) -> PathBuf {
+    archive_dir().join(file_name(entry_id))
+}
+
+pub fn is_archived(entry_id: &str) -> bool {
+    let archived = ARCHIVED.lock().unwrap().contains(&file_name(entry_id));
+
+    archived || move_legacy(entry_id)
+}
+
+/// The Wayback Machine page of a link, which redirects to its latest snapshot.
+pub fn archive_url(original: &str) -> String {
+    format!("https://web.archive.org/web/{original}")
+}
+
+/// Whether the page at the link is gone, going by a 404 or 410 in answer to a
+/// HEAD request. Other client errors, such as 403 or 429, are how many sites
+/// answer bots or too many requests, so they are not taken to mean gone.
+pub async fn is_missing(link: &str) -> bool {
+    match CLIENT
+        .head(link)
+        .header("User-Agent", "gluon_news")
+        .send()
+        .await
+    {
+        Ok(response) => {
+            [reqwest::StatusCode::NOT_FOUND, reqwest::StatusCode::GONE].contains(&response.status())
+        }
+        Err(_) => false,
+    }
+}
+
+async fn fetch_bytes(url: &Url) -> Option<(Vec<u8>, Option<String>)> {
+    let response = CLIENT
+        .get(url.clone())
+        .header("User-Agent", "gluon_news")
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?;
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+
+    Some((response.bytes().await.ok()?.to_vec(), content_type))
+}
+
+/// Fetches every resource once, keyed by the URL as written in the page.
+async fn fetch_all(base: &Url, urls: Vec<String>) -> HashMap<String, (Vec<u8>, Option<String>)> {
+    let fetches = urls.into_iter().map(|raw| async move {
+        let url = base.join(&raw.replace("&amp;", "&")).ok()?;
+        fetch_bytes(&url).await.map(|resource| (raw, resource))
+    });
+
+    join_all(fetches).await.into_iter().flatten().collect()
+}
+
+/// The stylesheet and image URLs of the page, as written in it.
+fn resource_urls(html: &str) -> (Vec<String>, Vec<String>) {
+    let stylesheets = STYLESHEET
+        .find_iter(html)
+        .filter_map(|m| HREF.captures(m.as_str()).map(|c| c[1].to_owned()))
+        .collect();
+    let images = IMG_SRC
+        .captures_iter(html)
+        .map(|c| c[2].to_owned())
+        .filter(|src| !src.starts_with("data:"))
+        .collect();
+
+    (stylesheets, images)
+}
+
+/// Saves a snapshot of the page at `link` and returns its path. Scripts are
+/// left out, so the snapshot is static. Resources referenced from inside the
+/// stylesheets, such as fonts, are not inlined.
+pub async fn archive(entry_id: &str, link: &str) -> Result<PathBuf, Box<dyn Error + Send + Sync>> {
+    let base = Url::parse(link)?;
+    let html = CLIENT
+        .get(base.clone())
+        .header("User-Agent", "gluon_news")
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let html = text::sanitize_html(&html);
+    let (stylesheet_urls, image_urls) = resource_urls(&html);
+    let stylesheets = fetch_all(&base, stylesheet_urls).await;
+    let images = fetch_all(&base, image_urls).await;
+
+    let html = STYLESHEET.replace_all(&html, |caps: &Captures| {
+        let css = HREF
+            .captures(&caps[0])
+            .and_then(|c| stylesheets.get(&c[1]))
+            .map(|(bytes, _)| String::from_utf8_lossy(bytes).replace("</style", "<\\/style"));
+        match css {
+            Some(css) => format!("<style>{css}</style>"),
+            None => caps[0].to_owned(),
+        }
+    });
+    let html = IMG_SRC.replace_all(&html, |caps: &Captures| match images.get(&caps[2]) {
+        Some((bytes, content_type)) => format!(
+            "{}data:{};base64,{}{}",
+            &caps[1],
+            content_type.as_deref().unwrap_or("image/png"),
+            STANDARD.encode(bytes),
+            &caps[3]
+        ),
+        None => caps[0].to_owned(),
+    });
+
+    let path = archive_path(entry_id);
+    tokio::fs::create_dir_all(archive_dir()).await?;
+    tokio::fs::write(&path, html.as_bytes()).await?;
+    ARCHIVED.lock().unwrap().insert(file_name(entry_id));
+
+    Ok(path)
+}
@@ -0,0 +1,17 @@
+use crate::{feed_cache, state};
+use std::{collections::HashSet, error::Error};
+
+const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+pub fn load() -> HashSet<String> {
+    feed_cache::load(|cache| cache.bookmarks.clone())
+}
+
+pub fn save(bookmarks: &HashSet<String>) -> Result<(), Box<dyn Error>> {
+    feed_cache::update(|cache| cache.bookmarks = bookmarks.clone())
+}
+
+/// The file the bookmarks were kept in before the feed cache.
+pub(crate) fn load_legacy() -> HashSet<String> {
+    state::load(BOOKMARKS_FILE)
+}
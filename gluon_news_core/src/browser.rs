@@ -0,0 +1,68 @@
+//! Opens links in the browser set in `browser` rather than the default one.
+
+use std::{
+    env, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+const URL_PLACEHOLDER: &str = "{url}";
+
+/// The words of a browser command. Double quotes group words, for paths with
+/// spaces such as "C:\Program Files\Mozilla Firefox\firefox.exe".
+fn words(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+
+    for c in command.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+/// Starts the browser command on the URL. The URL replaces every `{url}` in
+/// the arguments, such as in "open -a Safari {url}", or is the last argument
+/// when there is none.
+pub fn open(browser: &str, url: &str) -> io::Result<()> {
+    let mut words = words(browser).into_iter();
+    let program = words
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "browser is empty"))?;
+    let mut args: Vec<String> = words.map(|w| w.replace(URL_PLACEHOLDER, url)).collect();
+
+    if !browser.contains(URL_PLACEHOLDER) {
+        args.push(url.to_owned());
+    }
+    Command::new(program).args(args).spawn()?;
+
+    Ok(())
+}
+
+/// The program the browser command starts, given as a path or looked up on
+/// the PATH.
+pub fn find_program(browser: &str) -> Option<PathBuf> {
+    let program = PathBuf::from(words(browser).into_iter().next()?);
+
+    if program.components().count() > 1 {
+        return program.is_file().then_some(program);
+    }
+    env::split_paths(&env::var_os("PATH")?)
+        .flat_map(|dir| {
+            let path = dir.join(&program);
+            [path.with_extension(env::consts::EXE_EXTENSION), path]
+        })
+        .find(|path| Path::is_file(path))
+}
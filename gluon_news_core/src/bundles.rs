@@ -0,0 +1,50 @@
+//! Bundles, named groups of feeds shown as a single virtual feed.
+
+use crate::config::Bundle;
+
+/// How deeply bundles can be nested in one another. Members beyond it are
+/// ignored, which also breaks cycles.
+pub const MAX_BUNDLE_DEPTH: usize = 3;
+
+fn find<'a>(name: &str, bundles: &'a [Bundle]) -> Option<&'a Bundle> {
+    bundles.iter().find(|b| b.name == name)
+}
+
+fn collect(bundle: &Bundle, bundles: &[Bundle], depth: usize, feeds: &mut Vec<String>) {
+    for member in &bundle.feeds {
+        match find(member, bundles) {
+            Some(nested) if depth < MAX_BUNDLE_DEPTH => collect(nested, bundles, depth + 1, feeds),
+            Some(_) => {}
+            None if !feeds.contains(member) => feeds.push(member.clone()),
+            None => {}
+        }
+    }
+}
+
+/// The URLs of the feeds in the bundle, including those of the bundles in it,
+/// without duplicates. Members naming a bundle are bundles, the rest feeds.
+pub fn resolve_bundle(bundle: &Bundle, all_bundles: &[Bundle]) -> Vec<String> {
+    let mut feeds = Vec::new();
+    collect(bundle, all_bundles, 1, &mut feeds);
+
+    feeds
+}
+
+/// How deeply bundles are nested in the bundle, counting itself, up to one
+/// past `MAX_BUNDLE_DEPTH`.
+pub fn nesting_depth(bundle: &Bundle, all_bundles: &[Bundle]) -> usize {
+    fn depth(bundle: &Bundle, bundles: &[Bundle], level: usize) -> usize {
+        if level > MAX_BUNDLE_DEPTH {
+            return level;
+        }
+        bundle
+            .feeds
+            .iter()
+            .filter_map(|member| find(member, bundles))
+            .map(|nested| depth(nested, bundles, level + 1))
+            .max()
+            .unwrap_or(level)
+    }
+
+    depth(bundle, all_bundles, 1)
+}
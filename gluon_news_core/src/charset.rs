@@ -0,0 +1,43 @@
+//! Decodes feed bodies to UTF-8, for legacy feeds that give their encoding
+//! only in the XML declaration and not in the `Content-Type` header.
+
+use encoding_rs::{Encoding, UTF_8};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// How far into the body the XML declaration is looked for.
+const DECLARATION_WINDOW: usize = 100;
+
+lazy_static! {
+    static ref XML_ENCODING: Regex =
+        Regex::new(r#"^(\u{FEFF}?\s*<\?xml[^>]*?\bencoding\s*=\s*["'])([^"']+)"#).unwrap();
+}
+
+/// The encoding named by the `charset` parameter of a `Content-Type` header.
+fn header_encoding(content_type: &str) -> Option<&'static Encoding> {
+    content_type
+        .split(';')
+        .skip(1)
+        .filter_map(|param| param.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+        .and_then(|(_, value)| Encoding::for_label(value.trim().trim_matches('"').as_bytes()))
+}
+
+/// Decodes the body with the charset of the `Content-Type` header, or else
+/// the encoding in the XML declaration, or else UTF-8, unless a byte order
+/// mark says otherwise.
+pub fn decode_feed(bytes: &[u8], content_type: Option<&str>) -> String {
+    let window = String::from_utf8_lossy(&bytes[..bytes.len().min(DECLARATION_WINDOW)]);
+    let declared = XML_ENCODING
+        .captures(&window)
+        .and_then(|captures| Encoding::for_label(captures[2].as_bytes()));
+    let encoding = content_type
+        .and_then(header_encoding)
+        .or(declared)
+        .unwrap_or(UTF_8);
+    let (text, _, _) = encoding.decode(bytes);
+
+    // The text is UTF-8 now, so the parser must not decode it again with the
+    // encoding the declaration names.
+    XML_ENCODING.replace(&text, "${1}UTF-8").into_owned()
+}
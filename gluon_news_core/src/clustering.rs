@@ -0,0 +1,159 @@
+//! Groups entries by topic with k-means, over the presence of the terms most
+//! common across all entries.
+
+use crate::nlp;
+use feed_rs::model::Entry;
+use std::collections::{HashMap, HashSet};
+
+pub const DEFAULT_TOPIC_CLUSTER_COUNT: usize = 5;
+/// The terms in the most entries, whose presence are the features.
+const FEATURE_TERMS: usize = 200;
+const KEYWORDS_PER_TOPIC: usize = 3;
+const MAX_ITERATIONS: usize = 50;
+
+/// A cluster of entries, labelled by the terms that characterise it most.
+#[derive(Clone, PartialEq)]
+pub struct Topic {
+    pub keywords: Vec<String>,
+    pub entry_ids: HashSet<String>,
+}
+
+impl Topic {
+    pub fn label(&self) -> String {
+        self.keywords.join(", ")
+    }
+}
+
+fn squared_distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+fn nearest(point: &[f32], centroids: &[Vec<f32>]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .min_by(|a, b| squared_distance(point, a.1).total_cmp(&squared_distance(point, b.1)))
+        .map_or(0, |(i, _)| i)
+}
+
+/// Starts from the first point, then repeatedly adds the point farthest from
+/// the centroids so far, so the clusters are the same from one refresh to
+/// the next.
+fn initial_centroids(points: &[Vec<f32>], k: usize) -> Vec<Vec<f32>> {
+    let mut centroids = vec![points[0].clone()];
+
+    while centroids.len() < k {
+        let farthest = points.iter().max_by(|a, b| {
+            let distance = |p: &Vec<f32>| squared_distance(p, &centroids[nearest(p, &centroids)]);
+            distance(a).total_cmp(&distance(b))
+        });
+        match farthest {
+            Some(point) if !centroids.contains(point) => centroids.push(point.clone()),
+            // Fewer distinct points than clusters.
+            _ => break,
+        }
+    }
+
+    centroids
+}
+
+/// Splits each point into one of `k` clusters, by index into `points`.
+pub fn k_means(points: &[Vec<f32>], k: usize) -> Vec<usize> {
+    if points.is_empty() || k == 0 {
+        return Vec::new();
+    }
+
+    let mut centroids = initial_centroids(points, k);
+    let mut assignments: Vec<usize> = points.iter().map(|p| nearest(p, &centroids)).collect();
+
+    for _ in 0..MAX_ITERATIONS {
+        for (i, centroid) in centroids.iter_mut().enumerate() {
+            let members: Vec<&Vec<f32>> = points
+                .iter()
+                .zip(&assignments)
+                .filter(|(_, &a)| a == i)
+                .map(|(p, _)| p)
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+            for (d, value) in centroid.iter_mut().enumerate() {
+                *value = members.iter().map(|m| m[d]).sum::<f32>() / members.len() as f32;
+            }
+        }
+
+        let next: Vec<usize> = points.iter().map(|p| nearest(p, &centroids)).collect();
+        if next == assignments {
+            break;
+        }
+        assignments = next;
+    }
+
+    assignments
+}
+
+/// The topics of the entries, largest first, leaving out empty clusters.
+pub fn topics(entries: &[(String, Entry)], k: usize) -> Vec<Topic> {
+    let documents: Vec<HashSet<String>> = entries
+        .iter()
+        .map(|e| nlp::terms(&e.1).into_iter().collect())
+        .collect();
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+
+    for terms in &documents {
+        for term in terms {
+            *document_frequency.entry(term).or_default() += 1;
+        }
+    }
+
+    let mut vocabulary: Vec<(&str, usize)> = document_frequency.into_iter().collect();
+    vocabulary.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    vocabulary.truncate(FEATURE_TERMS);
+
+    let points: Vec<Vec<f32>> = documents
+        .iter()
+        .map(|terms| {
+            vocabulary
+                .iter()
+                .map(|(term, _)| if terms.contains(*term) { 1.0 } else { 0.0 })
+                .collect()
+        })
+        .collect();
+    let assignments = k_means(&points, k);
+    let mut topics: Vec<Topic> = (0..k)
+        .filter_map(|cluster| {
+            let members: Vec<usize> = (0..points.len())
+                .filter(|&i| assignments[i] == cluster)
+                .collect();
+            if members.is_empty() {
+                return None;
+            }
+            // How much more common each term is in the cluster than overall, so
+            // terms common to every topic do not label them all.
+            let mut weights: Vec<(&str, f32)> = vocabulary
+                .iter()
+                .enumerate()
+                .map(|(d, (term, df))| {
+                    let in_cluster = members.iter().filter(|&&i| points[i][d] > 0.0).count();
+                    let weight =
+                        in_cluster as f32 / members.len() as f32 - *df as f32 / points.len() as f32;
+                    (*term, weight)
+                })
+                .filter(|(_, weight)| *weight > 0.0)
+                .collect();
+            weights.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+
+            Some(Topic {
+                keywords: weights
+                    .into_iter()
+                    .take(KEYWORDS_PER_TOPIC)
+                    .map(|(term, _)| term.to_owned())
+                    .collect(),
+                entry_ids: members.iter().map(|&i| entries[i].1.id.clone()).collect(),
+            })
+        })
+        .collect();
+    topics.sort_by_key(|t| std::cmp::Reverse(t.entry_ids.len()));
+
+    topics
+}
@@ -0,0 +1,109 @@
+use crate::{compat::EntryExt, pdf, state, text};
+use feed_rs::model::Entry;
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs::{create_dir_all, write},
+    path::PathBuf,
+};
+
+const COLLECTIONS_FILE: &str = "collections.json";
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Opml,
+}
+
+pub fn load() -> HashMap<String, Vec<String>> {
+    state::load(COLLECTIONS_FILE)
+}
+
+pub fn save(collections: &HashMap<String, Vec<String>>) -> Result<(), Box<dyn Error>> {
+    state::save(COLLECTIONS_FILE, collections)
+}
+
+/// Collection names in alphabetical order, with the number of entries in each.
+pub fn sorted(collections: &HashMap<String, Vec<String>>) -> Vec<(String, usize)> {
+    let mut names: Vec<(String, usize)> = collections
+        .iter()
+        .map(|(name, ids)| (name.clone(), ids.len()))
+        .collect();
+    names.sort();
+
+    names
+}
+
+/// Adds the entry to the collection, creating the collection when needed.
+/// Returns false when the entry was already in it.
+pub fn add(collections: &mut HashMap<String, Vec<String>>, name: &str, entry_id: &str) -> bool {
+    let ids = collections.entry(name.to_owned()).or_default();
+
+    if ids.iter().any(|id| id == entry_id) {
+        return false;
+    }
+
+    ids.push(entry_id.to_owned());
+    true
+}
+
+pub(crate) fn to_json(ids: &[String], entries: &[(String, Entry)]) -> serde_json::Result<String> {
+    let items: Vec<serde_json::Value> = ids
+        .iter()
+        .map(|id| match entries.iter().find(|e| &e.1.id == id) {
+            Some((feed_title, entry)) => serde_json::json!({
+                "id": id,
+                "title": entry.best_title(),
+                "link": entry.best_link(),
+                "feed": feed_title,
+                "published": entry.best_date(),
+            }),
+            None => serde_json::json!({ "id": id }),
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&items)
+}
+
+pub(crate) fn to_opml(name: &str, ids: &[String], entries: &[(String, Entry)]) -> String {
+    let mut opml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n<head><title>{}</title></head>\n<body>\n",
+        text::escape_html(name)
+    );
+
+    for id in ids {
+        if let Some((_, entry)) = entries.iter().find(|e| &e.1.id == id) {
+            opml.push_str(&format!(
+                "<outline text=\"{}\" type=\"link\" url=\"{}\"/>\n",
+                text::escape_html(&entry.best_title()),
+                text::escape_html(entry.best_link().unwrap_or_default())
+            ));
+        }
+    }
+    opml.push_str("</body>\n</opml>\n");
+
+    opml
+}
+
+/// Writes the collection to the downloads directory. Entries that are no longer
+/// in any feed are exported by ID only in JSON and left out of OPML.
+pub fn export(
+    name: &str,
+    ids: &[String],
+    entries: &[(String, Entry)],
+    format: ExportFormat,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let (data, extension) = match format {
+        ExportFormat::Json => (to_json(ids, entries)?, "json"),
+        ExportFormat::Opml => (to_opml(name, ids, entries), "opml"),
+    };
+    let path = pdf::downloads_dir().join(pdf::safe_file_name(name, "collection", extension));
+
+    create_dir_all(pdf::downloads_dir())?;
+    write(&path, data)?;
+
+    Ok(path)
+}
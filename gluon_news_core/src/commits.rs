@@ -0,0 +1,30 @@
+//! Details of entries from GitHub and GitLab commit feeds, whose titles are
+//! terse and whose summary is often empty.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref SHA: Regex = Regex::new(r"/commits?/([0-9a-fA-F]{7,40})\b").unwrap();
+    static ref LINE_CHANGES: Regex =
+        Regex::new(r"(?i)(\d+)\s+(?:additions?|insertions?(?:\(\+\))?)\D+?(\d+)\s+deletions?")
+            .unwrap();
+    static ref SHORT_STAT: Regex = Regex::new(r"\+(\d+)\s*/?\s*[-−](\d+)\b").unwrap();
+}
+
+/// The commit hash from a link such as `https://github.com/o/r/commit/<sha>`.
+pub fn sha(link: &str) -> Option<&str> {
+    SHA.captures(link)
+        .and_then(|c| c.get(1))
+        .map(|m| m.as_str())
+}
+
+/// Added and removed line counts, when the summary mentions them as
+/// "N additions, M deletions", "N insertions(+), M deletions(-)" or "+N -M".
+pub fn line_changes(summary: &str) -> Option<(u32, u32)> {
+    let caps = LINE_CHANGES
+        .captures(summary)
+        .or_else(|| SHORT_STAT.captures(summary))?;
+
+    Some((caps[1].parse().ok()?, caps[2].parse().ok()?))
+}
@@ -0,0 +1,127 @@
+//! Helpers over the raw feed_rs types, so changes to its model only need
+//! to be handled here.
+
+use crate::text::humanize_duration;
+use chrono::{DateTime, Duration, Utc};
+use feed_rs::model::{Entry, Feed, Link};
+use url::Url;
+
+/// How much later than its publication an entry must be updated for the
+/// update to be pointed out.
+const UPDATE_BADGE_MIN_GAP_MINUTES: i64 = 60;
+
+pub trait EntryExt {
+    /// The entry title, or "N/A" when the feed has none.
+    fn best_title(&self) -> String;
+    /// The entry summary, or "N/A" when the feed has none.
+    fn best_summary(&self) -> String;
+    /// The full content body, falling back to the summary.
+    fn best_content(&self) -> String;
+    /// The link to the entry itself, leaving out links such as related
+    /// articles when the entry has one.
+    fn best_link(&self) -> Option<&str>;
+    /// The links with the given Atom relation, such as "related" or "via".
+    fn links_with_rel(&self, rel: &str) -> Vec<&Link>;
+    /// When the entry was published, or last updated when that is all the feed gives.
+    fn best_date(&self) -> Option<DateTime<Utc>>;
+    fn domain(&self) -> Option<String>;
+    /// The labels of the entry's categories, falling back to their terms.
+    fn category_names(&self) -> Vec<String>;
+    /// "Updated 3h ago" for an entry updated well after it was published,
+    /// which is often a silent correction.
+    fn freshness_badge(&self, now: DateTime<Utc>) -> Option<String>;
+}
+
+impl EntryExt for Entry {
+    fn best_title(&self) -> String {
+        match &self.title {
+            Some(title) => title.content.clone(),
+            None => String::from("N/A"),
+        }
+    }
+
+    fn best_summary(&self) -> String {
+        match &self.summary {
+            Some(summary) => summary.content.clone(),
+            None => String::from("N/A"),
+        }
+    }
+
+    fn best_content(&self) -> String {
+        match self.content.as_ref().and_then(|c| c.body.clone()) {
+            Some(body) => body,
+            None => self.best_summary(),
+        }
+    }
+
+    fn best_link(&self) -> Option<&str> {
+        self.links
+            .iter()
+            .find(|link| link.rel.as_deref().is_none_or(|rel| rel == "alternate"))
+            .or(self.links.first())
+            .map(|link| link.href.as_str())
+    }
+
+    fn links_with_rel(&self, rel: &str) -> Vec<&Link> {
+        self.links
+            .iter()
+            .filter(|link| link.rel.as_deref() == Some(rel))
+            .collect()
+    }
+
+    fn best_date(&self) -> Option<DateTime<Utc>> {
+        self.published.or(self.updated)
+    }
+
+    fn domain(&self) -> Option<String> {
+        Url::parse(self.best_link()?)
+            .ok()?
+            .host_str()
+            .map(String::from)
+    }
+
+    fn category_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .categories
+            .iter()
+            .map(|c| c.label.as_deref().unwrap_or(&c.term).trim().to_owned())
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        names.dedup();
+
+        names
+    }
+
+    fn freshness_badge(&self, now: DateTime<Utc>) -> Option<String> {
+        let (published, updated) = (self.published?, self.updated?);
+
+        (updated - published > Duration::minutes(UPDATE_BADGE_MIN_GAP_MINUTES))
+            .then(|| format!("Updated {}", humanize_duration(now - updated)))
+    }
+}
+
+/// The link's title, or its host when it has none.
+pub fn link_label(link: &Link) -> String {
+    match &link.title {
+        Some(title) if !title.trim().is_empty() => title.trim().to_owned(),
+        _ => Url::parse(&link.href)
+            .ok()
+            .and_then(|url| url.host_str().map(String::from))
+            .unwrap_or_else(|| link.href.clone()),
+    }
+}
+
+pub trait FeedExt {
+    /// The feed title, or "N/A" when the feed has none.
+    fn best_title(&self) -> String;
+}
+
+impl FeedExt for Feed {
+    fn best_title(&self) -> String {
+        match &self.title {
+            Some(title) => title.content.clone(),
+            None => String::from("N/A"),
+        }
+    }
+}
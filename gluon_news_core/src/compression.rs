@@ -0,0 +1,38 @@
+//! Compresses the text kept in caches on disk with zstd.
+//!
+//! The caches are JSON files rather than SQLite tables, so a cache is
+//! compressed as a whole file instead of column by column. The full article
+//! cache is the one that grows large enough for it to matter.
+
+use std::io;
+
+/// Favours speed, as caches are compressed each time they are saved.
+const LEVEL: i32 = 3;
+/// Every zstd frame starts with these bytes.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Falls back to the uncompressed bytes in the unlikely case zstd fails,
+/// which `decompress_text` reads back as they are.
+pub fn compress_text(s: &str) -> Vec<u8> {
+    zstd::encode_all(s.as_bytes(), LEVEL).unwrap_or_else(|_| s.as_bytes().to_vec())
+}
+
+/// Bytes that are not zstd data are taken to be uncompressed text.
+pub fn decompress_text(b: &[u8]) -> String {
+    match zstd::decode_all(b) {
+        Ok(text) => String::from_utf8_lossy(&text).into_owned(),
+        Err(_) => String::from_utf8_lossy(b).into_owned(),
+    }
+}
+
+/// Like `decompress_text`, for data from outside the app, such as links: zstd
+/// data that would decompress to more than `max_bytes` is an error, rather
+/// than decompressed until memory runs out.
+pub fn decompress_untrusted_text(b: &[u8], max_bytes: usize) -> io::Result<String> {
+    if !b.starts_with(&ZSTD_MAGIC) {
+        return Ok(String::from_utf8_lossy(b).into_owned());
+    }
+    let text = zstd::bulk::decompress(b, max_bytes)?;
+
+    Ok(String::from_utf8_lossy(&text).into_owned())
+}
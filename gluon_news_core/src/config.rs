@@ -0,0 +1,1939 @@
+use crate::{
+    browser, bundles, collections::ExportFormat, local_feeds, migration, profile,
+    reading_goal::Period, sanitizer, state, webfinger,
+};
+use chrono_tz::Tz;
+use cron::Schedule;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use reqwest::header::{HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    env,
+    error::Error,
+    fs::{self, read_to_string},
+    io,
+    path::PathBuf,
+    str::FromStr,
+    sync::Mutex,
+};
+use url::Url;
+
+const SETTINGS_FILE: &str = "settings.json";
+/// The content codings feeds can be sent in, as the client decodes them.
+const DECODED_ENCODINGS: [&str; 3] = ["gzip", "deflate", "br"];
+
+lazy_static! {
+    /// The settings as the app last wrote them, so the watcher can tell its
+    /// own writes from edits made by hand.
+    static ref WRITTEN: Mutex<Option<serde_json::Value>> = Mutex::new(None);
+    static ref ENV_VAR: Regex = Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap();
+    static ref URL_PATTERN: Regex = Regex::new(r#"(?i)https?://[^\s"'<>]+"#).unwrap();
+    static ref OPML_XML_URL: Regex = Regex::new(r#"(?i)\bxmlUrl\s*=\s*["']([^"']+)["']"#).unwrap();
+}
+
+/// The colour theme. `System` follows the OS colour scheme, switching when
+/// it does.
+#[derive(Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeMode {
+    Light,
+    #[default]
+    Dark,
+    System,
+}
+
+impl ThemeMode {
+    pub const ALL: [ThemeMode; 3] = [ThemeMode::Light, ThemeMode::Dark, ThemeMode::System];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ThemeMode::Light => "Light",
+            ThemeMode::Dark => "Dark",
+            ThemeMode::System => "Follow the system",
+        }
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            ThemeMode::Light => "light",
+            ThemeMode::Dark => "dark",
+            ThemeMode::System => "system",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkTarget {
+    #[default]
+    NewTab,
+    SameWindow,
+    ContentPane,
+}
+
+impl LinkTarget {
+    pub fn target(&self) -> &'static str {
+        match self {
+            LinkTarget::NewTab | LinkTarget::ContentPane => "_blank",
+            LinkTarget::SameWindow => "_self",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RankingMode {
+    #[default]
+    Chronological,
+    Ranked,
+}
+
+/// The most verbose messages logged. From `debug` on they are also written to
+/// a daily log file.
+#[derive(Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn level(&self) -> tracing::Level {
+        match self {
+            LogLevel::Error => tracing::Level::ERROR,
+            LogLevel::Warn => tracing::Level::WARN,
+            LogLevel::Info => tracing::Level::INFO,
+            LogLevel::Debug => tracing::Level::DEBUG,
+            LogLevel::Trace => tracing::Level::TRACE,
+        }
+    }
+
+    /// Whether messages are logged to a file as well.
+    pub fn logs_to_file(&self) -> bool {
+        matches!(self, LogLevel::Debug | LogLevel::Trace)
+    }
+}
+
+/// The format asked for from servers that serve a feed in several formats.
+#[derive(Clone, Copy, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    JsonFeed,
+    #[default]
+    Auto,
+}
+
+impl FeedFormat {
+    /// The `Accept` header value for the format.
+    pub fn accept(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "application/rss+xml",
+            FeedFormat::Atom => "application/atom+xml",
+            FeedFormat::JsonFeed => "application/feed+json",
+            FeedFormat::Auto => "*/*",
+        }
+    }
+}
+
+/// How much each part of an entry's score counts in `Ranked` mode.
+#[derive(Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct RankingWeights {
+    pub recency: f64,
+    pub popularity: f64,
+    pub personal: f64,
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            recency: 1.0,
+            popularity: 0.5,
+            personal: 0.5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ToolbarItem {
+    Refresh,
+    Search,
+    DateFilters,
+    ReadingSpeed,
+    Export,
+    QueueDepth,
+    ReadStreak,
+    DataFetched,
+    ReadingTimeFilter,
+    Newsletter,
+    ReadingGoal,
+    TrackersBlocked,
+    FocusTime,
+}
+
+impl ToolbarItem {
+    pub const ALL: [ToolbarItem; 13] = [
+        ToolbarItem::Refresh,
+        ToolbarItem::Search,
+        ToolbarItem::DateFilters,
+        ToolbarItem::ReadingSpeed,
+        ToolbarItem::Export,
+        ToolbarItem::QueueDepth,
+        ToolbarItem::ReadStreak,
+        ToolbarItem::DataFetched,
+        ToolbarItem::ReadingTimeFilter,
+        ToolbarItem::Newsletter,
+        ToolbarItem::ReadingGoal,
+        ToolbarItem::TrackersBlocked,
+        ToolbarItem::FocusTime,
+    ];
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "refresh" => Some(ToolbarItem::Refresh),
+            "search" => Some(ToolbarItem::Search),
+            "date_filters" => Some(ToolbarItem::DateFilters),
+            "reading_speed" => Some(ToolbarItem::ReadingSpeed),
+            "export" => Some(ToolbarItem::Export),
+            "queue_depth" => Some(ToolbarItem::QueueDepth),
+            "read_streak" => Some(ToolbarItem::ReadStreak),
+            "data_fetched" => Some(ToolbarItem::DataFetched),
+            "reading_time_filter" => Some(ToolbarItem::ReadingTimeFilter),
+            "newsletter" => Some(ToolbarItem::Newsletter),
+            "reading_goal" => Some(ToolbarItem::ReadingGoal),
+            "trackers_blocked" => Some(ToolbarItem::TrackersBlocked),
+            "focus_time" => Some(ToolbarItem::FocusTime),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "name", rename_all = "snake_case")]
+pub enum ProcessorConfig {
+    SanitizeHtml,
+    TruncateSummary { max_chars: usize },
+    ExtractReadingTime { wpm: Option<u32> },
+}
+
+/// How to authenticate to a feed that requires it.
+#[derive(Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeedAuth {
+    /// OAuth 2.0 authorization code flow with PKCE, authorized in the browser.
+    Oauth2Pkce {
+        client_id: String,
+        auth_url: String,
+        token_url: String,
+        #[serde(default)]
+        scopes: Vec<String>,
+    },
+}
+
+/// Settings for a single feed, keyed by its URL in `feed_options`.
+#[derive(Deserialize, Serialize)]
+pub struct FeedOptions {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub commit_feed: bool,
+    /// A CSS hex colour for the feed, such as "#3366cc".
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub auth: Option<FeedAuth>,
+    /// Extra HTTP headers sent with the feed's requests, overriding the
+    /// default ones such as `User-Agent`.
+    #[serde(default)]
+    pub custom_headers: Option<HashMap<String, String>>,
+    /// Cookies sent with the feed's requests, by name, for feeds behind a
+    /// session cookie.
+    #[serde(default)]
+    pub cookies: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub preferred_format: Option<FeedFormat>,
+    /// Shown instead of the title the feed gives itself.
+    #[serde(default)]
+    pub title: Option<String>,
+    /// The feed's share of the listed entries relative to the other feeds,
+    /// 1.0 when unset. Feeds are only balanced once one of them has a weight.
+    #[serde(default)]
+    pub weight: Option<f32>,
+}
+
+const DEFAULT_FEED_OPTIONS: FeedOptions = FeedOptions {
+    enabled: true,
+    commit_feed: false,
+    color: None,
+    auth: None,
+    custom_headers: None,
+    cookies: None,
+    preferred_format: None,
+    title: None,
+    weight: None,
+};
+
+impl Default for FeedOptions {
+    fn default() -> Self {
+        DEFAULT_FEED_OPTIONS
+    }
+}
+
+impl FeedOptions {
+    fn expand_env_vars(self) -> Self {
+        Self {
+            custom_headers: self.custom_headers.map(|headers| {
+                headers
+                    .into_iter()
+                    .map(|(name, value)| (name, expand_env_vars(&value)))
+                    .collect()
+            }),
+            cookies: self.cookies.map(|cookies| {
+                cookies
+                    .into_iter()
+                    .map(|(name, value)| (name, expand_env_vars(&value)))
+                    .collect()
+            }),
+            ..self
+        }
+    }
+}
+
+/// A feed with its options and the bundles it is in, for writing settings
+/// from code rather than by hand.
+pub struct FeedConfig {
+    pub url: String,
+    pub options: FeedOptions,
+    pub groups: Vec<String>,
+}
+
+impl FeedConfig {
+    pub fn builder() -> FeedConfigBuilder {
+        FeedConfigBuilder::default()
+    }
+}
+
+/// Builds a `FeedConfig`, such as
+/// `FeedConfig::builder().url("https://...").title("My Feed").group("Tech").build()`.
+/// The options left unset keep their defaults.
+#[derive(Default)]
+pub struct FeedConfigBuilder {
+    url: Option<String>,
+    options: FeedOptions,
+    groups: Vec<String>,
+}
+
+impl FeedConfigBuilder {
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.options.enabled = enabled;
+        self
+    }
+
+    pub fn commit_feed(mut self, commit_feed: bool) -> Self {
+        self.options.commit_feed = commit_feed;
+        self
+    }
+
+    pub fn color(mut self, color: impl Into<String>) -> Self {
+        self.options.color = Some(color.into());
+        self
+    }
+
+    pub fn auth(mut self, auth: FeedAuth) -> Self {
+        self.options.auth = Some(auth);
+        self
+    }
+
+    /// Adds a custom header, replacing the one of the same name.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options
+            .custom_headers
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), value.into());
+        self
+    }
+
+    /// Adds a cookie, replacing the one of the same name.
+    pub fn cookie(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options
+            .cookies
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), value.into());
+        self
+    }
+
+    pub fn preferred_format(mut self, format: FeedFormat) -> Self {
+        self.options.preferred_format = Some(format);
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.options.title = Some(title.into());
+        self
+    }
+
+    pub fn weight(mut self, weight: f32) -> Self {
+        self.options.weight = Some(weight);
+        self
+    }
+
+    /// Adds the feed to a bundle, created when no bundle has the name.
+    pub fn group(mut self, name: impl Into<String>) -> Self {
+        self.groups.push(name.into());
+        self
+    }
+
+    /// Fails when no URL was given.
+    pub fn build(self) -> Result<FeedConfig, Box<dyn Error>> {
+        Ok(FeedConfig {
+            url: self.url.ok_or("a feed needs a URL")?,
+            options: self.options,
+            groups: self.groups,
+        })
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct SmtpConfig {
+    pub host: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl SmtpConfig {
+    fn expand_env_vars(self) -> Self {
+        Self {
+            host: expand_env_vars(&self.host),
+            user: self.user.as_deref().map(expand_env_vars),
+            password: self.password.as_deref().map(expand_env_vars),
+            ..self
+        }
+    }
+}
+
+/// Where entries are sent on each refresh, or when exporting on demand.
+#[derive(Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportTarget {
+    File {
+        path: String,
+        #[serde(default)]
+        format: ExportFormat,
+    },
+    Http {
+        url: String,
+        #[serde(default = "default_export_method")]
+        method: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    Email {
+        smtp_config: SmtpConfig,
+        to: String,
+    },
+}
+
+impl ExportTarget {
+    fn expand_env_vars(self) -> Self {
+        match self {
+            ExportTarget::File { path, format } => ExportTarget::File {
+                path: expand_env_vars(&path),
+                format,
+            },
+            ExportTarget::Http {
+                url,
+                method,
+                headers,
+            } => ExportTarget::Http {
+                url: expand_env_vars(&url),
+                method,
+                headers: headers
+                    .into_iter()
+                    .map(|(name, value)| (name, expand_env_vars(&value)))
+                    .collect(),
+            },
+            ExportTarget::Email { smtp_config, to } => ExportTarget::Email {
+                smtp_config: smtp_config.expand_env_vars(),
+                to: expand_env_vars(&to),
+            },
+        }
+    }
+}
+
+fn default_export_method() -> String {
+    String::from("POST")
+}
+
+#[derive(Deserialize)]
+pub struct TranslateConfig {
+    pub endpoint: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default = "default_target_language")]
+    pub target_language: String,
+}
+
+fn default_target_language() -> String {
+    String::from("en")
+}
+
+#[derive(Deserialize)]
+pub struct NextcloudConfig {
+    pub url: String,
+    pub username: String,
+    pub password: String,
+}
+
+/// The tags, and the attributes by tag, kept in entry HTML. Attributes listed
+/// under "*" are kept on every tag. Scripts, event handlers and `javascript:`
+/// URLs are removed even when allowed.
+#[derive(Deserialize)]
+pub struct SanitizerConfig {
+    #[serde(default = "default_allow_tags")]
+    pub allow_tags: Vec<String>,
+    #[serde(default = "default_allow_attributes")]
+    pub allow_attributes: HashMap<String, Vec<String>>,
+}
+
+impl Default for SanitizerConfig {
+    fn default() -> Self {
+        Self {
+            allow_tags: default_allow_tags(),
+            allow_attributes: default_allow_attributes(),
+        }
+    }
+}
+
+fn default_allow_tags() -> Vec<String> {
+    sanitizer::DEFAULT_ALLOW_TAGS.map(String::from).to_vec()
+}
+
+fn default_allow_attributes() -> HashMap<String, Vec<String>> {
+    sanitizer::DEFAULT_ALLOW_ATTRIBUTES
+        .iter()
+        .map(|(tag, attributes)| {
+            (
+                tag.to_string(),
+                attributes.iter().map(|a| a.to_string()).collect(),
+            )
+        })
+        .collect()
+}
+
+/// How many entries to read each day or week.
+#[derive(Deserialize)]
+pub struct ReadingGoal {
+    pub count: u32,
+    #[serde(default)]
+    pub period: Period,
+}
+
+/// A named group of feeds listed as a single feed, written as
+/// `{ "type": "bundle", "name": ..., "feeds": [...] }`. A member that names
+/// another bundle includes that bundle's feeds.
+#[derive(Deserialize)]
+pub struct Bundle {
+    pub name: String,
+    pub feeds: Vec<String>,
+}
+
+/// Feeds combined into one for good, written as
+/// `{ "type": "merged", "title": ..., "sources": [...] }`. The sources are
+/// fetched like any other feed, and their entries listed under the title.
+#[derive(Clone, Deserialize)]
+pub struct MergedFeed {
+    pub title: String,
+    pub sources: Vec<String>,
+}
+
+/// The curated digest rendered by "Generate newsletter". The feeds are listed
+/// by title, and all feeds are included when none are.
+#[derive(Deserialize)]
+pub struct NewsletterConfig {
+    pub title: String,
+    #[serde(default)]
+    pub intro_text: String,
+    #[serde(default)]
+    pub footer_text: String,
+    #[serde(default)]
+    pub include_feeds: Vec<String>,
+    #[serde(default = "default_newsletter_entries")]
+    pub max_entries: usize,
+}
+
+fn default_newsletter_entries() -> usize {
+    20
+}
+
+/// Where a search feed searches.
+#[derive(Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SearchSource {
+    HackerNews,
+    Reddit {
+        subreddit: String,
+    },
+    /// The computer science categories of arXiv.
+    ArxivCs,
+    /// Repositories with the topic, most recently updated first.
+    #[serde(rename = "github")]
+    GitHub {
+        topic: String,
+    },
+}
+
+impl SearchSource {
+    pub fn name(&self) -> String {
+        match self {
+            SearchSource::HackerNews => String::from("Hacker News"),
+            SearchSource::Reddit { subreddit } => format!("r/{subreddit}"),
+            SearchSource::ArxivCs => String::from("arXiv CS"),
+            SearchSource::GitHub { topic } => format!("GitHub #{topic}"),
+        }
+    }
+}
+
+/// A keyword search listed as a feed of its latest results, written as
+/// `{ "query": ..., "source": { "type": "reddit", "subreddit": ... } }`.
+#[derive(Clone, Deserialize)]
+pub struct SearchFeedConfig {
+    pub query: String,
+    pub source: SearchSource,
+    #[serde(default = "default_search_entries")]
+    pub max_entries: u32,
+}
+
+impl SearchFeedConfig {
+    pub fn title(&self) -> String {
+        format!("\"{}\" on {}", self.query, self.source.name())
+    }
+}
+
+fn default_search_entries() -> u32 {
+    30
+}
+
+/// A WebSocket endpoint new entries are pushed to after each refresh.
+#[derive(Deserialize)]
+pub struct WsConfig {
+    pub url: String,
+}
+
+#[derive(Deserialize)]
+pub struct Settings {
+    /// The schema version the file was written for, see `migration`.
+    #[serde(default)]
+    pub version: Option<u32>,
+    pub feeds: Vec<String>,
+    pub maximized: bool,
+    pub time_zone: String,
+    #[serde(default)]
+    pub link_target: LinkTarget,
+    #[serde(default)]
+    pub interval_seconds: Option<u64>,
+    #[serde(default)]
+    pub max_cache_size_mb: Option<u64>,
+    #[serde(default)]
+    pub show_author_avatars: bool,
+    #[serde(default)]
+    pub retry_base_delay_ms: Option<u64>,
+    #[serde(default)]
+    pub digest_smtp_host: Option<String>,
+    #[serde(default)]
+    pub digest_smtp_port: Option<u16>,
+    #[serde(default)]
+    pub digest_smtp_user: Option<String>,
+    #[serde(default)]
+    pub digest_smtp_password: Option<String>,
+    #[serde(default)]
+    pub digest_to: Option<String>,
+    #[serde(default)]
+    pub digest_schedule: Option<String>,
+    #[serde(default)]
+    pub mute_domains: Vec<String>,
+    /// Entries in these categories, or with these words in their title or
+    /// summary, are hidden like those from muted domains.
+    #[serde(default)]
+    pub mute_categories: Vec<String>,
+    #[serde(default)]
+    pub mute_keywords: Vec<String>,
+    #[serde(default = "default_accept_encoding")]
+    pub accept_encoding: Vec<String>,
+    #[serde(default)]
+    pub processors: Vec<ProcessorConfig>,
+    #[serde(default)]
+    pub tor_socks_proxy: Option<String>,
+    /// URL prefix plain HTTP images are loaded through, with the encoded image
+    /// URL appended, e.g. `https://images.weserv.nl/?url=`.
+    #[serde(default)]
+    pub image_proxy: Option<String>,
+    #[serde(default)]
+    pub tts_rate: Option<f32>,
+    #[serde(default)]
+    pub tts_voice: Option<String>,
+    #[serde(default)]
+    pub expand_short_links: bool,
+    #[serde(default = "default_short_link_domains")]
+    pub short_link_domains: Vec<String>,
+    #[serde(default)]
+    pub toolbar_items: Option<Vec<String>>,
+    #[serde(default)]
+    pub rsvp_wpm: Option<u32>,
+    #[serde(default)]
+    pub notify_new_entries: bool,
+    #[serde(default)]
+    pub notification_digest_seconds: Option<u64>,
+    #[serde(default)]
+    pub fetch_full_content: bool,
+    #[serde(default)]
+    pub nextcloud_sync: Option<NextcloudConfig>,
+    #[serde(default)]
+    pub translate_config: Option<TranslateConfig>,
+    #[serde(default)]
+    pub content_font_family: Option<String>,
+    #[serde(default)]
+    pub content_font_url: Option<String>,
+    #[serde(default)]
+    pub reading_pane_max_width_px: Option<u32>,
+    #[serde(default)]
+    pub reading_line_height: Option<f32>,
+    #[serde(default)]
+    pub reading_paragraph_spacing_em: Option<f32>,
+    #[serde(default)]
+    pub min_poll_interval_seconds: Option<u64>,
+    #[serde(default)]
+    pub max_poll_interval_seconds: Option<u64>,
+    #[serde(default)]
+    pub feed_options: HashMap<String, FeedOptions>,
+    #[serde(default)]
+    pub export_targets: Vec<ExportTarget>,
+    #[serde(default)]
+    pub max_connections_per_host: Option<usize>,
+    #[serde(default)]
+    pub health_check_on_startup: bool,
+    #[serde(default)]
+    pub auto_disable_after_days: Option<u32>,
+    /// Delay between starting one feed request and the next, in milliseconds.
+    #[serde(default)]
+    pub fetch_stagger_ms: Option<u64>,
+    #[serde(default = "default_lazy_images")]
+    pub lazy_images: bool,
+    #[serde(default)]
+    pub archive_on_read: bool,
+    #[serde(default)]
+    pub ranking: RankingMode,
+    #[serde(default)]
+    pub ranking_weights: RankingWeights,
+    #[serde(default)]
+    pub enable_smart_ranking: bool,
+    #[serde(default)]
+    pub catch_up_days: Option<u32>,
+    /// Entries with a lower content score, from 0 to 1, are hidden.
+    #[serde(default)]
+    pub min_content_score: Option<f32>,
+    /// A DNS-over-HTTPS server to resolve host names with, such as
+    /// "https://1.1.1.1/dns-query".
+    #[serde(default)]
+    pub dns_over_https: Option<String>,
+    /// A DNS-over-TLS server to resolve host names with, such as "1.1.1.1" or
+    /// "dns.quad9.net:853".
+    #[serde(default)]
+    pub dns_over_tls: Option<String>,
+    /// Only the newest entries up to this many are listed.
+    #[serde(default)]
+    pub max_total_entries: Option<usize>,
+    /// Looks for a newer release on GitHub at startup.
+    #[serde(default = "default_check_for_updates")]
+    pub check_for_updates: bool,
+    #[serde(default)]
+    pub websocket_push: Option<WsConfig>,
+    /// How many topics entries are grouped into, 5 when unset.
+    #[serde(default)]
+    pub topic_cluster_count: Option<usize>,
+    #[serde(default)]
+    pub newsletter: Option<NewsletterConfig>,
+    /// Entries whose titles are at least this similar, from 0 to 1, are
+    /// folded into one. Only identical titles are when unset.
+    #[serde(default)]
+    pub fuzzy_dedup_threshold: Option<f32>,
+    #[serde(default)]
+    pub bundles: Vec<Bundle>,
+    /// Merged feeds replace their sources in `feeds`.
+    #[serde(default)]
+    pub merged_feeds: Vec<MergedFeed>,
+    #[serde(default)]
+    pub reading_goal: Option<ReadingGoal>,
+    #[serde(default)]
+    pub sanitizer: SanitizerConfig,
+    #[serde(default)]
+    pub theme: ThemeMode,
+    /// Points out entries updated well after they were published.
+    #[serde(default = "default_show_update_badge")]
+    pub show_update_badge: bool,
+    /// Entries past the fold are listed compactly, one line each. The fold
+    /// is after this many entries or at the first entry older than this many
+    /// hours, whichever comes first.
+    #[serde(default)]
+    pub fold_after_n: Option<usize>,
+    #[serde(default)]
+    pub fold_after_hours: Option<u32>,
+    /// Keys for the shortcuts of the main window by action, such as
+    /// `{ "toggle_star": "f" }`.
+    #[serde(default)]
+    pub keybindings: HashMap<String, String>,
+    /// Removes images that are 1×1 pixel or served from these domains, or
+    /// their subdomains, from entry HTML.
+    #[serde(default = "default_block_tracking_pixels")]
+    pub block_tracking_pixels: bool,
+    #[serde(default)]
+    pub tracking_pixel_domains: Vec<String>,
+    /// Scrolls the entry list back to the entry at the top when the app last
+    /// closed.
+    #[serde(default = "default_restore_position_on_startup")]
+    pub restore_position_on_startup: bool,
+    /// Only notifies of new entries in the hours the user usually reads in,
+    /// give or take `notification_buffer_hours`, 1 by default.
+    #[serde(default)]
+    pub smart_notification_window: bool,
+    #[serde(default)]
+    pub notification_buffer_hours: Option<u32>,
+    /// Stores the full article cache compressed with zstd.
+    #[serde(default)]
+    pub compress_cache: bool,
+    /// Entries linking to these domains, or their subdomains, are marked as
+    /// trusted or suspicious.
+    #[serde(default)]
+    pub trusted_domains: Vec<String>,
+    #[serde(default)]
+    pub suspicious_domains: Vec<String>,
+    /// Also checks entry links against Google Safe Browsing when set.
+    #[serde(default)]
+    pub safe_browsing_api_key: Option<String>,
+    /// The browser command links open in instead of the default browser, such
+    /// as "firefox" or "open -a Safari {url}". `{url}` stands for the link, and
+    /// is appended when left out.
+    #[serde(default)]
+    pub browser: Option<String>,
+    #[serde(default)]
+    pub search_feeds: Vec<SearchFeedConfig>,
+    /// Counts the time spent reading each day, while the window has focus
+    /// and input in the last 30 seconds.
+    #[serde(default)]
+    pub track_reading_time: bool,
+    /// Shows how long each entry took to render, along with `debug`.
+    #[serde(default)]
+    pub show_render_timings: bool,
+    /// At `debug` or `trace`, messages are also written to a log file in
+    /// `logs`, with a file per day kept for a week.
+    #[serde(default)]
+    pub log_level: LogLevel,
+    /// Shows internals such as entry scores in the UI.
+    #[serde(default)]
+    pub debug: bool,
+    /// The keys this build does not know, such as those of settings added by
+    /// newer versions, kept so they only warrant a warning.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+fn default_accept_encoding() -> Vec<String> {
+    vec!["gzip".to_owned(), "deflate".to_owned(), "br".to_owned()]
+}
+
+fn default_lazy_images() -> bool {
+    true
+}
+
+fn default_block_tracking_pixels() -> bool {
+    true
+}
+
+fn default_restore_position_on_startup() -> bool {
+    true
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_check_for_updates() -> bool {
+    true
+}
+
+fn default_show_update_badge() -> bool {
+    true
+}
+
+fn default_short_link_domains() -> Vec<String> {
+    [
+        "bit.ly",
+        "buff.ly",
+        "dlvr.it",
+        "goo.gl",
+        "is.gd",
+        "lnkd.in",
+        "ow.ly",
+        "t.co",
+        "tinyurl.com",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: Some(migration::CURRENT_VERSION),
+            feeds: vec!["https://github.com/vascocosta/gluon_news/commits.atom".to_owned()],
+            maximized: true,
+            time_zone: "Europe/London".to_owned(),
+            link_target: LinkTarget::default(),
+            interval_seconds: None,
+            max_cache_size_mb: None,
+            show_author_avatars: false,
+            retry_base_delay_ms: None,
+            digest_smtp_host: None,
+            digest_smtp_port: None,
+            digest_smtp_user: None,
+            digest_smtp_password: None,
+            digest_to: None,
+            digest_schedule: None,
+            mute_domains: Vec::new(),
+            mute_categories: Vec::new(),
+            mute_keywords: Vec::new(),
+            accept_encoding: default_accept_encoding(),
+            processors: Vec::new(),
+            tor_socks_proxy: None,
+            image_proxy: None,
+            tts_rate: None,
+            tts_voice: None,
+            expand_short_links: false,
+            short_link_domains: default_short_link_domains(),
+            toolbar_items: None,
+            rsvp_wpm: None,
+            notify_new_entries: false,
+            notification_digest_seconds: None,
+            fetch_full_content: false,
+            nextcloud_sync: None,
+            translate_config: None,
+            content_font_family: None,
+            content_font_url: None,
+            reading_pane_max_width_px: None,
+            reading_line_height: None,
+            reading_paragraph_spacing_em: None,
+            min_poll_interval_seconds: None,
+            max_poll_interval_seconds: None,
+            feed_options: HashMap::new(),
+            export_targets: Vec::new(),
+            max_connections_per_host: None,
+            health_check_on_startup: false,
+            auto_disable_after_days: None,
+            fetch_stagger_ms: None,
+            lazy_images: default_lazy_images(),
+            archive_on_read: false,
+            ranking: RankingMode::default(),
+            ranking_weights: RankingWeights::default(),
+            enable_smart_ranking: false,
+            catch_up_days: None,
+            min_content_score: None,
+            dns_over_https: None,
+            dns_over_tls: None,
+            max_total_entries: None,
+            check_for_updates: default_check_for_updates(),
+            websocket_push: None,
+            topic_cluster_count: None,
+            newsletter: None,
+            fuzzy_dedup_threshold: None,
+            bundles: Vec::new(),
+            merged_feeds: Vec::new(),
+            reading_goal: None,
+            sanitizer: SanitizerConfig::default(),
+            theme: ThemeMode::default(),
+            show_update_badge: default_show_update_badge(),
+            fold_after_n: None,
+            fold_after_hours: None,
+            keybindings: HashMap::new(),
+            block_tracking_pixels: default_block_tracking_pixels(),
+            tracking_pixel_domains: Vec::new(),
+            restore_position_on_startup: default_restore_position_on_startup(),
+            smart_notification_window: false,
+            notification_buffer_hours: None,
+            compress_cache: false,
+            trusted_domains: Vec::new(),
+            suspicious_domains: Vec::new(),
+            safe_browsing_api_key: None,
+            browser: None,
+            search_feeds: Vec::new(),
+            track_reading_time: false,
+            show_render_timings: false,
+            log_level: LogLevel::default(),
+            debug: false,
+            extra: HashMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// The configured toolbar items in display order, or all of them when unset.
+    /// Unknown item IDs are skipped.
+    pub fn toolbar_items(&self) -> Vec<ToolbarItem> {
+        match &self.toolbar_items {
+            Some(ids) => ids
+                .iter()
+                .filter_map(|id| ToolbarItem::from_id(id))
+                .collect(),
+            None => ToolbarItem::ALL.to_vec(),
+        }
+    }
+
+    pub fn feed_options(&self, url: &str) -> &FeedOptions {
+        static DEFAULT: FeedOptions = DEFAULT_FEED_OPTIONS;
+
+        self.feed_options.get(url).unwrap_or(&DEFAULT)
+    }
+
+    /// Adds the feed, or replaces its options when it is already listed.
+    pub fn add_feed(&mut self, feed: FeedConfig) {
+        if !self.feeds.contains(&feed.url) {
+            self.feeds.push(feed.url.clone());
+        }
+        for name in feed.groups {
+            match self.bundles.iter_mut().find(|b| b.name == name) {
+                Some(bundle) if bundle.feeds.contains(&feed.url) => {}
+                Some(bundle) => bundle.feeds.push(feed.url.clone()),
+                None => self.bundles.push(Bundle {
+                    name,
+                    feeds: vec![feed.url.clone()],
+                }),
+            }
+        }
+        self.feed_options.insert(feed.url, feed.options);
+    }
+
+    /// The configured feeds, including the sources of merged feeds.
+    pub fn all_feeds(&self) -> impl Iterator<Item = &String> {
+        self.feeds
+            .iter()
+            .chain(self.merged_feeds.iter().flat_map(|merged| &merged.sources))
+    }
+
+    /// The setting that routes connections through a proxy or resolver, if
+    /// any. Connections made other than with the HTTP client go around it.
+    pub fn routing_setting(&self) -> Option<&'static str> {
+        if self.tor_socks_proxy.is_some() {
+            Some("tor_socks_proxy")
+        } else if self.dns_over_https.is_some() {
+            Some("dns_over_https")
+        } else if self.dns_over_tls.is_some() {
+            Some("dns_over_tls")
+        } else {
+            None
+        }
+    }
+
+    /// The feeds to fetch, leaving out the ones disabled in `feed_options`.
+    pub fn enabled_feeds(&self) -> impl Iterator<Item = &String> {
+        self.all_feeds()
+            .filter(|feed| self.feed_options(feed).enabled)
+    }
+
+    /// The configured colour of the feed, or one derived from its URL, so each
+    /// feed keeps the same colour between runs.
+    pub fn feed_color(&self, url: &str) -> String {
+        if let Some(color) = &self.feed_options(url).color {
+            return color.clone();
+        }
+
+        let hash = url
+            .bytes()
+            .fold(5381u32, |hash, b| hash.wrapping_mul(33) ^ b as u32);
+
+        format!("hsl({}, 55%, 45%)", hash % 360)
+    }
+
+    pub fn accepts_encoding(&self, encoding: &str) -> bool {
+        self.accept_encoding
+            .iter()
+            .any(|e| e.eq_ignore_ascii_case(encoding))
+    }
+
+    /// The Accept-Encoding header for feed requests, with only the encodings
+    /// the client can decode, so an unknown or malformed one in
+    /// accept_encoding is never sent.
+    pub fn accept_encoding_header(&self) -> String {
+        let encodings: Vec<&str> = DECODED_ENCODINGS
+            .into_iter()
+            .filter(|encoding| self.accepts_encoding(encoding))
+            .collect();
+
+        match encodings.join(", ") {
+            encodings if encodings.is_empty() => String::from("identity"),
+            encodings => encodings,
+        }
+    }
+
+    fn expand_env_vars(self) -> Self {
+        Self {
+            feeds: self.feeds.iter().map(|f| expand_env_vars(f)).collect(),
+            merged_feeds: self
+                .merged_feeds
+                .into_iter()
+                .map(|merged| MergedFeed {
+                    sources: merged.sources.iter().map(|f| expand_env_vars(f)).collect(),
+                    ..merged
+                })
+                .collect(),
+            feed_options: self
+                .feed_options
+                .into_iter()
+                .map(|(url, options)| (expand_env_vars(&url), options.expand_env_vars()))
+                .collect(),
+            export_targets: self
+                .export_targets
+                .into_iter()
+                .map(ExportTarget::expand_env_vars)
+                .collect(),
+            time_zone: expand_env_vars(&self.time_zone),
+            digest_smtp_host: self.digest_smtp_host.as_deref().map(expand_env_vars),
+            digest_smtp_user: self.digest_smtp_user.as_deref().map(expand_env_vars),
+            digest_smtp_password: self.digest_smtp_password.as_deref().map(expand_env_vars),
+            digest_to: self.digest_to.as_deref().map(expand_env_vars),
+            nextcloud_sync: self.nextcloud_sync.map(|nextcloud| NextcloudConfig {
+                url: expand_env_vars(&nextcloud.url),
+                username: expand_env_vars(&nextcloud.username),
+                password: expand_env_vars(&nextcloud.password),
+            }),
+            translate_config: self.translate_config.map(|translate| TranslateConfig {
+                endpoint: expand_env_vars(&translate.endpoint),
+                api_key: translate.api_key.as_deref().map(expand_env_vars),
+                target_language: translate.target_language,
+            }),
+            websocket_push: self.websocket_push.map(|ws| WsConfig {
+                url: expand_env_vars(&ws.url),
+            }),
+            safe_browsing_api_key: self.safe_browsing_api_key.as_deref().map(expand_env_vars),
+            ..self
+        }
+    }
+}
+
+/// Replaces `${VAR}` placeholders with the value of the environment variable,
+/// or with an empty string when the variable is unset.
+pub fn expand_env_vars(s: &str) -> String {
+    ENV_VAR
+        .replace_all(s, |caps: &Captures| match env::var(&caps[1]) {
+            Ok(value) => value,
+            Err(_) => {
+                tracing::warn!(
+                    "environment variable {} referenced in settings is not set",
+                    &caps[1]
+                );
+                String::new()
+            }
+        })
+        .into_owned()
+}
+
+/// Migrates settings.json to the current schema version first, saving the
+/// migrated file.
+pub fn read_settings() -> Result<Settings, Box<dyn Error>> {
+    let data = read_to_string(profile::path(SETTINGS_FILE))?;
+    let mut value: serde_json::Value = serde_json::from_str(&data)?;
+    let version = migration::version(&value);
+
+    if version < migration::CURRENT_VERSION {
+        value = migration::migrate_settings(value, version);
+        write_settings(&value)?;
+    }
+    let settings: Settings = serde_json::from_value(value)?;
+    let settings = settings.expand_env_vars();
+
+    warn_unknown_keys(&settings);
+    if let Some(browser) = &settings.browser {
+        if browser::find_program(browser).is_none() {
+            tracing::warn!("browser {browser:?} not found, links open in the default browser");
+        }
+    }
+
+    Ok(settings)
+}
+
+fn warn_unknown_keys(settings: &Settings) {
+    let mut unknown: Vec<&String> = settings.extra.keys().collect();
+    unknown.sort();
+    for key in unknown {
+        tracing::warn!("unknown setting {key} in settings.json is ignored");
+    }
+}
+
+/// Whether settings.json is missing, as when the app first runs, rather than
+/// unreadable.
+pub fn is_first_run() -> bool {
+    fs::metadata(settings_path()).is_err_and(|e| e.kind() == io::ErrorKind::NotFound)
+}
+
+/// Writes settings.json from the choices made in the first-run wizard, for
+/// every other setting to keep its default.
+pub fn create_settings(
+    feeds: &[String],
+    theme: ThemeMode,
+    maximized: bool,
+    time_zone: &str,
+) -> Result<(), Box<dyn Error>> {
+    let settings = serde_json::json!({
+        "version": migration::CURRENT_VERSION,
+        "feeds": feeds,
+        "maximized": maximized,
+        "time_zone": time_zone,
+        "theme": theme.id(),
+    });
+    write_settings(&settings)?;
+
+    Ok(())
+}
+
+pub fn settings_path() -> PathBuf {
+    profile::path(SETTINGS_FILE)
+}
+
+/// Reads settings.json again while the app runs, along with the JSON it was
+/// parsed from, to compare with the next reload. Unlike `read_settings` the
+/// file is not migrated, as writing it would be seen as another change.
+pub fn reload_settings() -> Result<(Settings, serde_json::Value), Box<dyn Error>> {
+    let value: serde_json::Value = serde_json::from_str(&read_to_string(settings_path())?)?;
+    let settings: Settings = serde_json::from_value(value.clone())?;
+    warn_unknown_keys(&settings);
+
+    Ok((settings.expand_env_vars(), value))
+}
+
+/// The log_level in settings.json, read on its own without expanding or
+/// migrating anything, so logging can be set up before the settings load.
+pub fn read_log_level() -> LogLevel {
+    read_to_string(settings_path())
+        .ok()
+        .and_then(|data| serde_json::from_str::<serde_json::Value>(&data).ok())
+        .and_then(|settings| serde_json::from_value(settings.get("log_level")?.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn write_settings(settings: &impl Serialize) -> Result<(), Box<dyn Error>> {
+    let settings = serde_json::to_value(settings)?;
+    state::write(&settings_path(), serde_json::to_string_pretty(&settings)?)?;
+    *WRITTEN.lock().unwrap() = Some(settings);
+
+    Ok(())
+}
+
+/// Whether settings.json is as the app last wrote it, rather than edited since.
+pub fn is_own_write(settings: &serde_json::Value) -> bool {
+    WRITTEN.lock().unwrap().as_ref() == Some(settings)
+}
+
+/// What changed in settings.json between two reads of it. Feeds, including
+/// the sources of merged feeds, are compared one by one, every other setting
+/// only by its top-level key.
+#[derive(Default)]
+pub struct SettingsChanges {
+    pub added_feeds: Vec<String>,
+    pub removed_feeds: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl SettingsChanges {
+    pub fn between(old: &serde_json::Value, new: &serde_json::Value) -> Self {
+        let feeds = |settings: &serde_json::Value| -> Vec<String> {
+            serde_json::from_value::<Settings>(settings.clone())
+                .map(|settings| settings.all_feeds().cloned().collect())
+                .unwrap_or_default()
+        };
+        let (old_feeds, new_feeds) = (feeds(old), feeds(new));
+        let keys = |settings: &serde_json::Value| -> Vec<String> {
+            settings
+                .as_object()
+                .map(|s| s.keys().cloned().collect())
+                .unwrap_or_default()
+        };
+        let mut changed: Vec<String> = keys(old)
+            .into_iter()
+            .chain(keys(new))
+            .filter(|key| key != "feeds" && old.get(key) != new.get(key))
+            .collect();
+        changed.sort();
+        changed.dedup();
+
+        SettingsChanges {
+            added_feeds: new_feeds
+                .iter()
+                .filter(|f| !old_feeds.contains(f))
+                .cloned()
+                .collect(),
+            removed_feeds: old_feeds
+                .iter()
+                .filter(|f| !new_feeds.contains(f))
+                .cloned()
+                .collect(),
+            changed,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_feeds.is_empty() && self.removed_feeds.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Stores the muted domains in settings.json, leaving every other key as written.
+pub fn save_mute_domains(domains: &[String]) -> Result<(), Box<dyn Error>> {
+    save_mute_list("mute_domains", domains)
+}
+
+/// Stores one of mute_domains, mute_categories or mute_keywords.
+pub fn save_mute_list(key: &str, values: &[String]) -> Result<(), Box<dyn Error>> {
+    let data = read_to_string(profile::path(SETTINGS_FILE))?;
+    let mut settings: serde_json::Value = serde_json::from_str(&data)?;
+
+    settings
+        .as_object_mut()
+        .ok_or("settings.json is not a JSON object")?
+        .insert(String::from(key), serde_json::to_value(values)?);
+    write_settings(&settings)?;
+
+    Ok(())
+}
+
+/// Appends feeds to settings.json, leaving the ones already there as written.
+pub fn add_feeds(feeds: &[String]) -> Result<(), Box<dyn Error>> {
+    let data = read_to_string(profile::path(SETTINGS_FILE))?;
+    let mut settings: serde_json::Value = serde_json::from_str(&data)?;
+
+    settings
+        .get_mut("feeds")
+        .and_then(|f| f.as_array_mut())
+        .ok_or("settings.json has no feeds array")?
+        .extend(feeds.iter().cloned().map(serde_json::Value::String));
+    write_settings(&settings)?;
+
+    Ok(())
+}
+
+/// Whether the feed is an `http` or `https` URL, rather than a local path or
+/// another scheme.
+pub fn is_web_url(feed: &str) -> bool {
+    Url::parse(feed).is_ok_and(|url| ["http", "https"].contains(&url.scheme()))
+}
+
+/// The feed URLs in pasted text, such as a list of URLs or an OPML document,
+/// in the order they appear and without duplicates. In OPML only the `xmlUrl`
+/// attributes are taken, as the other URLs point to websites.
+pub fn find_feed_urls(text: &str) -> Vec<String> {
+    let candidates: Vec<&str> = if OPML_XML_URL.is_match(text) {
+        OPML_XML_URL
+            .captures_iter(text)
+            .filter_map(|c| c.get(1))
+            .map(|m| m.as_str())
+            .collect()
+    } else {
+        URL_PATTERN
+            .find_iter(text)
+            .map(|m| m.as_str().trim_end_matches(['.', ',', ';', ')', ']']))
+            .collect()
+    };
+    let mut urls: Vec<String> = Vec::new();
+
+    for candidate in candidates {
+        let candidate = candidate.replace("&amp;", "&");
+        if is_web_url(&candidate) && !urls.contains(&candidate) {
+            urls.push(candidate);
+        }
+    }
+
+    urls
+}
+
+/// Sets the `title` in the feed_options of each feed in settings.json, leaving
+/// every other key as written.
+pub fn set_feed_titles(titles: &[(String, String)]) -> Result<(), Box<dyn Error>> {
+    let data = read_to_string(profile::path(SETTINGS_FILE))?;
+    let mut settings: serde_json::Value = serde_json::from_str(&data)?;
+    let feed_options = settings
+        .as_object_mut()
+        .ok_or("settings.json is not a JSON object")?
+        .entry("feed_options")
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .ok_or("feed_options is not a JSON object")?;
+
+    for (feed, title) in titles {
+        feed_options
+            .entry(feed.clone())
+            .or_insert_with(|| serde_json::json!({}))
+            .as_object_mut()
+            .ok_or("feed_options entries must be JSON objects")?
+            .insert(
+                String::from("title"),
+                serde_json::Value::from(title.clone()),
+            );
+    }
+    write_settings(&settings)?;
+
+    Ok(())
+}
+
+/// Writes the feeds to settings.json, appending the ones not listed yet and
+/// replacing the feed_options of the rest.
+pub fn save_feed_configs(feeds: &[FeedConfig]) -> Result<(), Box<dyn Error>> {
+    let data = read_to_string(profile::path(SETTINGS_FILE))?;
+    let mut settings: serde_json::Value = serde_json::from_str(&data)?;
+    let object = settings
+        .as_object_mut()
+        .ok_or("settings.json is not a JSON object")?;
+    let urls = object
+        .get_mut("feeds")
+        .and_then(|f| f.as_array_mut())
+        .ok_or("settings.json has no feeds array")?;
+    for feed in feeds {
+        if !urls.iter().any(|u| u == feed.url.as_str()) {
+            urls.push(serde_json::Value::from(feed.url.clone()));
+        }
+    }
+    let feed_options = object
+        .entry("feed_options")
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .ok_or("feed_options is not a JSON object")?;
+    for feed in feeds {
+        feed_options.insert(feed.url.clone(), serde_json::to_value(&feed.options)?);
+    }
+    write_settings(&settings)?;
+
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for feed in feeds {
+        for name in &feed.groups {
+            groups
+                .entry(name.clone())
+                .or_default()
+                .push(feed.url.clone());
+        }
+    }
+    if groups.is_empty() {
+        Ok(())
+    } else {
+        add_to_bundles(&groups)
+    }
+}
+
+/// Adds feeds to the bundles of settings.json by bundle name, creating the
+/// bundles that do not exist yet.
+pub fn add_to_bundles(groups: &BTreeMap<String, Vec<String>>) -> Result<(), Box<dyn Error>> {
+    let data = read_to_string(profile::path(SETTINGS_FILE))?;
+    let mut settings: serde_json::Value = serde_json::from_str(&data)?;
+    let bundles = settings
+        .as_object_mut()
+        .ok_or("settings.json is not a JSON object")?
+        .entry("bundles")
+        .or_insert_with(|| serde_json::json!([]))
+        .as_array_mut()
+        .ok_or("bundles is not a JSON array")?;
+
+    for (name, feeds) in groups {
+        let position = bundles.iter().position(|b| b["name"] == name.as_str());
+        let bundle = match position {
+            Some(i) => &mut bundles[i],
+            None => {
+                bundles.push(serde_json::json!({ "type": "bundle", "name": name, "feeds": [] }));
+                bundles.last_mut().ok_or("bundles is empty")?
+            }
+        };
+        let members = bundle["feeds"]
+            .as_array_mut()
+            .ok_or("bundle feeds must be a JSON array")?;
+        for feed in feeds {
+            if !members.iter().any(|m| m == feed.as_str()) {
+                members.push(serde_json::Value::from(feed.clone()));
+            }
+        }
+    }
+    write_settings(&settings)?;
+
+    Ok(())
+}
+
+/// Replaces the sources in settings.json with a merged feed of them. Merged
+/// feeds among the sources are folded into the new one.
+pub fn merge_feeds(merged: &MergedFeed) -> Result<(), Box<dyn Error>> {
+    let data = read_to_string(profile::path(SETTINGS_FILE))?;
+    let mut settings: serde_json::Value = serde_json::from_str(&data)?;
+    let is_source = |feed: &serde_json::Value| merged.sources.iter().any(|s| feed == s.as_str());
+    let settings = settings
+        .as_object_mut()
+        .ok_or("settings.json is not a JSON object")?;
+
+    settings
+        .get_mut("feeds")
+        .and_then(|f| f.as_array_mut())
+        .ok_or("settings.json has no feeds array")?
+        .retain(|feed| !is_source(feed));
+    let merged_feeds = settings
+        .entry("merged_feeds")
+        .or_insert_with(|| serde_json::json!([]))
+        .as_array_mut()
+        .ok_or("merged_feeds is not a JSON array")?;
+    merged_feeds.retain(|m| {
+        !m["sources"]
+            .as_array()
+            .is_some_and(|s| s.iter().any(is_source))
+    });
+    merged_feeds.push(serde_json::json!({
+        "type": "merged",
+        "title": merged.title,
+        "sources": merged.sources,
+    }));
+    write_settings(settings)?;
+
+    Ok(())
+}
+
+/// Removes a merged feed from settings.json, putting its sources back in
+/// `feeds`.
+pub fn split_feed(title: &str) -> Result<(), Box<dyn Error>> {
+    let data = read_to_string(profile::path(SETTINGS_FILE))?;
+    let mut settings: serde_json::Value = serde_json::from_str(&data)?;
+    let settings = settings
+        .as_object_mut()
+        .ok_or("settings.json is not a JSON object")?;
+    let merged_feeds = settings
+        .get_mut("merged_feeds")
+        .and_then(|m| m.as_array_mut())
+        .ok_or("settings.json has no merged_feeds array")?;
+    let position = merged_feeds
+        .iter()
+        .position(|m| m["title"] == title)
+        .ok_or_else(|| format!("no merged feed is titled {title}"))?;
+    let sources = merged_feeds.remove(position)["sources"].take();
+
+    let feeds = settings
+        .get_mut("feeds")
+        .and_then(|f| f.as_array_mut())
+        .ok_or("settings.json has no feeds array")?;
+    for source in sources.as_array().into_iter().flatten() {
+        if !feeds.contains(source) {
+            feeds.push(source.clone());
+        }
+    }
+    write_settings(settings)?;
+
+    Ok(())
+}
+
+/// Sets `enabled: false` in the feed_options of each feed in settings.json,
+/// leaving every other key as written.
+pub fn disable_feeds(feeds: &[String]) -> Result<(), Box<dyn Error>> {
+    let data = read_to_string(profile::path(SETTINGS_FILE))?;
+    let mut settings: serde_json::Value = serde_json::from_str(&data)?;
+    let feed_options = settings
+        .as_object_mut()
+        .ok_or("settings.json is not a JSON object")?
+        .entry("feed_options")
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .ok_or("feed_options is not a JSON object")?;
+
+    for feed in feeds {
+        feed_options
+            .entry(feed.clone())
+            .or_insert_with(|| serde_json::json!({}))
+            .as_object_mut()
+            .ok_or("feed_options entries must be JSON objects")?
+            .insert(String::from("enabled"), serde_json::Value::Bool(false));
+    }
+    write_settings(&settings)?;
+
+    Ok(())
+}
+
+/// Checks that the feed is a URL, acct: address or file path that can be
+/// fetched, giving a warning for a file that does not exist yet.
+pub(crate) fn check_feed(feed: &str) -> Result<Option<String>, String> {
+    if webfinger::is_acct(feed) {
+        if !webfinger::is_valid_acct(feed) {
+            return Err(String::from("expected an acct:user@domain address"));
+        }
+        return Ok(None);
+    }
+    if local_feeds::is_local(feed) {
+        return match local_feeds::local_path(feed) {
+            Some(path) if path.is_file() => Ok(None),
+            Some(path) => Ok(Some(format!("{} is not a file yet", path.display()))),
+            None => Err(String::from("expected a file path")),
+        };
+    }
+    match Url::parse(feed) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => Ok(None),
+        Ok(url) => Err(format!("unsupported scheme {}", url.scheme())),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn validate(settings: &Settings) -> (Vec<String>, Vec<String>) {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+
+    if settings.all_feeds().next().is_none() {
+        warnings.push(String::from("no feeds configured"));
+    }
+    if let Some(version) = settings
+        .version
+        .filter(|&version| version > migration::CURRENT_VERSION)
+    {
+        warnings.push(format!(
+            "version {version} is newer than this build supports ({}), some settings may be ignored",
+            migration::CURRENT_VERSION
+        ));
+    }
+    let mut unknown: Vec<&String> = settings.extra.keys().collect();
+    unknown.sort();
+    for key in unknown {
+        warnings.push(format!("unknown setting {key} is ignored"));
+    }
+    for feed in settings.all_feeds() {
+        match check_feed(feed) {
+            Ok(Some(warning)) => warnings.push(format!("feed {feed}: {warning}")),
+            Ok(None) => {}
+            Err(error) => errors.push(format!("feed {feed}: {error}")),
+        }
+    }
+    for encoding in settings.accept_encoding.iter().filter(|encoding| {
+        !encoding.eq_ignore_ascii_case("identity")
+            && !DECODED_ENCODINGS
+                .iter()
+                .any(|decoded| decoded.eq_ignore_ascii_case(encoding))
+    }) {
+        warnings.push(format!(
+            "accept_encoding {encoding:?} is not supported and is not requested"
+        ));
+    }
+    if Tz::from_str(&settings.time_zone).is_err() {
+        warnings.push(format!(
+            "time_zone {:?} is not a known time zone, UTC will be used",
+            settings.time_zone
+        ));
+    }
+    if let Some(proxy) = &settings.tor_socks_proxy {
+        match Url::parse(proxy) {
+            Ok(url) if url.scheme() == "socks5h" => {}
+            Ok(url) if url.scheme() == "socks5" => warnings.push(format!(
+                "tor_socks_proxy {proxy}: socks5 resolves host names locally, use socks5h to resolve them through Tor"
+            )),
+            Ok(url) => errors.push(format!(
+                "tor_socks_proxy {proxy}: unsupported scheme {}",
+                url.scheme()
+            )),
+            Err(e) => errors.push(format!("tor_socks_proxy {proxy}: {e}")),
+        }
+    }
+    if let Some(doh) = &settings.dns_over_https {
+        match Url::parse(doh) {
+            Ok(url) if url.scheme() == "https" && url.path() == "/dns-query" => {}
+            Ok(url) if url.scheme() == "https" => warnings.push(format!(
+                "dns_over_https {doh}: queries are always sent to /dns-query, not {}",
+                url.path()
+            )),
+            Ok(url) => errors.push(format!(
+                "dns_over_https {doh}: unsupported scheme {}",
+                url.scheme()
+            )),
+            Err(e) => errors.push(format!("dns_over_https {doh}: {e}")),
+        }
+        if settings.dns_over_tls.is_some() {
+            warnings.push(String::from(
+                "dns_over_https and dns_over_tls are both set, dns_over_tls will be ignored",
+            ));
+        }
+    }
+    if settings.tor_socks_proxy.is_some()
+        && (settings.dns_over_https.is_some() || settings.dns_over_tls.is_some())
+    {
+        warnings.push(String::from(
+            "requests go through tor_socks_proxy, so dns_over_https and dns_over_tls are not used",
+        ));
+    }
+    if let Some(proxy) = &settings.image_proxy {
+        match Url::parse(proxy) {
+            Ok(url) if url.scheme() == "https" => {}
+            Ok(url) => warnings.push(format!(
+                "image_proxy {proxy}: images loaded over {} may still be blocked as mixed content",
+                url.scheme()
+            )),
+            Err(e) => errors.push(format!("image_proxy {proxy}: {e}")),
+        }
+    }
+    for id in settings.toolbar_items.iter().flatten() {
+        if ToolbarItem::from_id(id).is_none() {
+            warnings.push(format!(
+                "toolbar_items: unknown item {id:?} will be ignored"
+            ));
+        }
+    }
+    if let Some(schedule) = &settings.digest_schedule {
+        if let Err(e) = Schedule::from_str(schedule) {
+            errors.push(format!("digest_schedule {schedule:?}: {e}"));
+        }
+        if settings.digest_smtp_host.is_none() || settings.digest_to.is_none() {
+            warnings.push(String::from(
+                "digest_schedule is set but digest_smtp_host or digest_to is missing",
+            ));
+        }
+    }
+    if let Some(nextcloud) = &settings.nextcloud_sync {
+        match Url::parse(&nextcloud.url) {
+            Ok(url) if url.scheme() == "https" => {}
+            Ok(url) if url.scheme() == "http" => warnings.push(format!(
+                "nextcloud_sync url {}: the password is sent unencrypted over http",
+                nextcloud.url
+            )),
+            Ok(url) => errors.push(format!(
+                "nextcloud_sync url {}: unsupported scheme {}",
+                nextcloud.url,
+                url.scheme()
+            )),
+            Err(e) => errors.push(format!("nextcloud_sync url {}: {e}", nextcloud.url)),
+        }
+    }
+    for bundle in &settings.bundles {
+        if bundles::nesting_depth(bundle, &settings.bundles) > bundles::MAX_BUNDLE_DEPTH {
+            warnings.push(format!(
+                "bundle {}: bundles nested more than {} deep are ignored",
+                bundle.name,
+                bundles::MAX_BUNDLE_DEPTH
+            ));
+        }
+        for feed in bundles::resolve_bundle(bundle, &settings.bundles) {
+            if !settings.all_feeds().any(|f| *f == feed) {
+                warnings.push(format!(
+                    "bundle {}: {feed} is neither a configured feed nor a bundle",
+                    bundle.name
+                ));
+            }
+        }
+    }
+    for merged in &settings.merged_feeds {
+        if merged.sources.len() < 2 {
+            warnings.push(format!(
+                "merged feed {} has fewer than two sources",
+                merged.title
+            ));
+        }
+    }
+    if settings
+        .reading_goal
+        .as_ref()
+        .is_some_and(|goal| goal.count == 0)
+    {
+        warnings.push(String::from(
+            "reading_goal count is 0, so the goal is always met",
+        ));
+    }
+    if let Some(threshold) = settings.fuzzy_dedup_threshold {
+        if !(0.0..=1.0).contains(&threshold) {
+            errors.push(format!(
+                "fuzzy_dedup_threshold {threshold}: must be between 0.0 and 1.0"
+            ));
+        }
+    }
+    if let Some(ws) = &settings.websocket_push {
+        match Url::parse(&ws.url) {
+            Ok(url) if ["ws", "wss"].contains(&url.scheme()) => {}
+            Ok(url) => errors.push(format!(
+                "websocket_push url {}: unsupported scheme {}",
+                ws.url,
+                url.scheme()
+            )),
+            Err(e) => errors.push(format!("websocket_push url {}: {e}", ws.url)),
+        }
+        if let Some(setting) = settings.routing_setting() {
+            warnings.push(format!(
+                "websocket_push is skipped while {setting} is set, as it would connect directly"
+            ));
+        }
+    }
+    if let Some(translate) = &settings.translate_config {
+        if let Err(e) = Url::parse(&translate.endpoint) {
+            errors.push(format!(
+                "translate_config endpoint {}: {e}",
+                translate.endpoint
+            ));
+        }
+    }
+    if settings.max_connections_per_host == Some(0) {
+        warnings.push(String::from(
+            "max_connections_per_host is 0, one connection per host will be used",
+        ));
+    }
+    for target in &settings.export_targets {
+        match target {
+            ExportTarget::File { path, .. } if path.is_empty() => {
+                errors.push(String::from("export_targets: file target without a path"))
+            }
+            ExportTarget::Http { url, method, .. } => {
+                if let Err(e) = Url::parse(url) {
+                    errors.push(format!("export_targets {url}: {e}"));
+                }
+                if reqwest::Method::from_bytes(method.as_bytes()).is_err() {
+                    errors.push(format!("export_targets {url}: invalid method {method:?}"));
+                }
+            }
+            ExportTarget::Email { to, .. } if !to.contains('@') => {
+                errors.push(format!("export_targets: invalid email address {to:?}"))
+            }
+            _ => {}
+        }
+    }
+    for (url, options) in &settings.feed_options {
+        if !settings.all_feeds().any(|feed| feed == url) {
+            warnings.push(format!("feed_options {url}: not in feeds, will be ignored"));
+        }
+        if let Some(color) = &options.color {
+            let hex = color.strip_prefix('#').unwrap_or_default();
+            if ![3, 6].contains(&hex.len()) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                warnings.push(format!(
+                    "feed_options {url}: color {color:?} is not a CSS hex colour"
+                ));
+            }
+        }
+        if options.weight.is_some_and(|weight| weight < 0.0) {
+            warnings.push(format!(
+                "feed_options {url}: negative weight, the feed will not be listed"
+            ));
+        }
+        if let Some(FeedAuth::Oauth2Pkce {
+            auth_url,
+            token_url,
+            ..
+        }) = &options.auth
+        {
+            for endpoint in [auth_url, token_url] {
+                if let Err(e) = Url::parse(endpoint) {
+                    errors.push(format!("feed_options {url}: auth {endpoint}: {e}"));
+                }
+            }
+        }
+        for (name, value) in options.custom_headers.iter().flatten() {
+            if HeaderName::from_bytes(name.as_bytes()).is_err() {
+                errors.push(format!("feed_options {url}: invalid header name {name:?}"));
+            } else if HeaderValue::from_str(value).is_err() {
+                errors.push(format!(
+                    "feed_options {url}: invalid value for header {name}"
+                ));
+            }
+        }
+    }
+    if let Some(font_url) = &settings.content_font_url {
+        if let Err(e) = Url::parse(font_url) {
+            errors.push(format!("content_font_url {font_url}: {e}"));
+        }
+        if settings.content_font_family.is_none() {
+            warnings.push(String::from(
+                "content_font_url is set but content_font_family is missing",
+            ));
+        }
+    }
+    if let (Some(min), Some(max)) = (
+        settings.min_poll_interval_seconds,
+        settings.max_poll_interval_seconds,
+    ) {
+        if min > max {
+            warnings.push(format!(
+                "min_poll_interval_seconds {min} is above max_poll_interval_seconds {max}, the minimum will be used"
+            ));
+        }
+    }
+    let weights = settings.ranking_weights;
+    if [weights.recency, weights.popularity, weights.personal]
+        .iter()
+        .any(|w| *w < 0.0)
+    {
+        warnings.push(String::from(
+            "ranking_weights: negative weights push matching entries down",
+        ));
+    }
+    if settings.auto_disable_after_days.is_some() && !settings.health_check_on_startup {
+        warnings.push(String::from(
+            "auto_disable_after_days is set but health_check_on_startup is disabled",
+        ));
+    }
+    if let Some(min) = settings.min_content_score {
+        if !(0.0..=1.0).contains(&min) {
+            warnings.push(format!(
+                "min_content_score {min} is outside 0.0 to 1.0, scores are never above 1.0"
+            ));
+        }
+    }
+    if settings.notification_digest_seconds.is_some() && !settings.notify_new_entries {
+        warnings.push(String::from(
+            "notification_digest_seconds is set but notify_new_entries is disabled",
+        ));
+    }
+    if settings.smart_notification_window && !settings.notify_new_entries {
+        warnings.push(String::from(
+            "smart_notification_window is enabled but notify_new_entries is disabled",
+        ));
+    }
+    if settings.show_render_timings && !settings.debug {
+        warnings.push(String::from(
+            "show_render_timings is enabled but debug is disabled",
+        ));
+    }
+    if let Some(browser) = &settings.browser {
+        if browser::find_program(browser).is_none() {
+            warnings.push(format!(
+                "browser {browser:?}: program not found, links will open in the default browser"
+            ));
+        }
+    }
+
+    (errors, warnings)
+}
+
+pub fn check_config() -> bool {
+    let settings = match read_settings() {
+        Ok(settings) => settings,
+        Err(e) => {
+            println!("error: could not read settings.json: {e}");
+            return false;
+        }
+    };
+    let (errors, warnings) = validate(&settings);
+
+    for warning in &warnings {
+        println!("warning: {warning}");
+    }
+    for error in &errors {
+        println!("error: {error}");
+    }
+    println!(
+        "settings.json: {} feed(s), {} warning(s), {} error(s)",
+        settings.all_feeds().count(),
+        warnings.len(),
+        errors.len()
+    );
+
+    errors.is_empty()
+}
@@ -0,0 +1,290 @@
+//! Compares the entries of two feeds, matching them by ID, normalized link or
+//! title, merges the entries of several and folds duplicates into one. Also
+//! compares a feed with its entries from the previous refresh.
+
+use crate::{compat::EntryExt, text::strip_html, SETTINGS};
+use feed_rs::model::Entry;
+use similar::{ChangeTag, TextDiff};
+use std::collections::{HashMap, HashSet};
+use url::Url;
+
+/// The entries of the selected feeds, newest first.
+pub fn merge_feeds(selected: &[&str], entries: &[(String, Entry)]) -> Vec<(String, Entry)> {
+    let mut merged: Vec<(String, Entry)> = entries
+        .iter()
+        .filter(|e| selected.contains(&e.0.as_str()))
+        .cloned()
+        .collect();
+
+    merged.sort_by_key(|e| std::cmp::Reverse(e.1.best_date()));
+
+    merged
+}
+
+pub struct FeedDiff {
+    pub only_a: Vec<Entry>,
+    pub both: Vec<(Entry, Entry)>,
+    pub only_b: Vec<Entry>,
+}
+
+/// Normalizes a link so mirrors of the same article compare equal: the scheme,
+/// a leading `www.`, the fragment, tracking parameters and trailing slashes
+/// are ignored.
+pub fn normalize_link(link: &str) -> String {
+    let Ok(mut url) = Url::parse(link.trim()) else {
+        return link.trim().to_lowercase();
+    };
+    let query: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !key.starts_with("utm_"))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+
+    url.set_fragment(None);
+    if query.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(query);
+    }
+
+    let host = url.host_str().unwrap_or_default().to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let path = url.path().trim_end_matches('/');
+
+    match url.query() {
+        Some(query) => format!("{host}{path}?{query}"),
+        None => format!("{host}{path}"),
+    }
+}
+
+/// Normalizes a title so copies of it with different case or punctuation
+/// compare equal.
+pub fn normalize_title(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// What entries are compared by, normalized once rather than on every
+/// comparison. Entries without an ID, link or title never match on it.
+struct EntryKey {
+    id: String,
+    link: Option<String>,
+    title: String,
+}
+
+impl EntryKey {
+    fn new(entry: &Entry) -> Self {
+        Self {
+            id: entry.id.clone(),
+            link: entry.best_link().map(normalize_link),
+            // Not best_title, which gives the same "N/A" to every untitled entry.
+            title: entry
+                .title
+                .as_ref()
+                .map(|title| normalize_title(&title.content))
+                .unwrap_or_default(),
+        }
+    }
+
+    fn same_link(&self, other: &EntryKey) -> bool {
+        self.link.is_some() && self.link == other.link
+    }
+
+    fn same_id_or_link(&self, other: &EntryKey) -> bool {
+        (!self.id.is_empty() && self.id == other.id) || self.same_link(other)
+    }
+
+    /// Compares entries of different feeds, by link and title only, as IDs
+    /// are only unique within a feed: many feeds number their items from 1.
+    /// Titles match exactly after normalization or, with a threshold, when
+    /// their Jaro-Winkler similarity reaches it.
+    fn matches(&self, other: &EntryKey, fuzzy_threshold: Option<f32>) -> bool {
+        if self.same_link(other) {
+            return true;
+        }
+        if self.title.is_empty() || other.title.is_empty() {
+            return false;
+        }
+
+        self.title == other.title
+            || fuzzy_threshold.is_some_and(|threshold| {
+                strsim::jaro_winkler(&self.title, &other.title) >= threshold as f64
+            })
+    }
+}
+
+pub fn diff_feeds(a: &[(String, Entry)], b: &[(String, Entry)]) -> FeedDiff {
+    let mut only_a = Vec::new();
+    let mut both = Vec::new();
+    let mut matched_b = vec![false; b.len()];
+    let keys_b: Vec<EntryKey> = b.iter().map(|(_, entry)| EntryKey::new(entry)).collect();
+
+    for (_, entry_a) in a {
+        let key_a = EntryKey::new(entry_a);
+        match keys_b
+            .iter()
+            .position(|key_b| key_a.matches(key_b, SETTINGS.fuzzy_dedup_threshold))
+        {
+            Some(i) => {
+                matched_b[i] = true;
+                both.push((entry_a.clone(), b[i].1.clone()));
+            }
+            None => only_a.push(entry_a.clone()),
+        }
+    }
+
+    let only_b = b
+        .iter()
+        .zip(matched_b)
+        .filter(|(_, matched)| !matched)
+        .map(|((_, entry), _)| entry.clone())
+        .collect();
+
+    FeedDiff {
+        only_a,
+        both,
+        only_b,
+    }
+}
+
+/// The other feeds an entry was also in, by entry ID.
+pub type AlsoIn = HashMap<String, Vec<String>>;
+
+/// Folds entries that are the same item, from mirror feeds or reposts, into
+/// the first of them, which keeps the earliest publication date. Entries of
+/// the same feed are folded by ID or link, and entries of different feeds by
+/// link or title, as a feed may well have several items with the same title,
+/// such as commits, while IDs only tell apart the items of one feed. Returns
+/// the other feeds each
+/// folded entry was in, by entry ID.
+pub fn deduplicate(
+    entries: Vec<(String, Entry)>,
+    fuzzy_threshold: Option<f32>,
+) -> (Vec<(String, Entry)>, AlsoIn) {
+    let mut kept: Vec<(String, Entry, EntryKey)> = Vec::with_capacity(entries.len());
+    let mut also_in = AlsoIn::new();
+
+    for (feed_title, entry) in entries {
+        let key = EntryKey::new(&entry);
+        match kept.iter_mut().find(|(kept_feed, _, kept_key)| {
+            if *kept_feed == feed_title {
+                kept_key.same_id_or_link(&key)
+            } else {
+                kept_key.matches(&key, fuzzy_threshold)
+            }
+        }) {
+            Some((kept_feed, kept_entry, _)) => {
+                if let Some(published) = entry.published {
+                    kept_entry.published =
+                        Some(kept_entry.published.map_or(published, |p| p.min(published)));
+                }
+                let sources = also_in.entry(kept_entry.id.clone()).or_default();
+                if feed_title != *kept_feed && !sources.contains(&feed_title) {
+                    sources.push(feed_title);
+                }
+            }
+            None => kept.push((feed_title, entry, key)),
+        }
+    }
+    also_in.retain(|_, sources| !sources.is_empty());
+
+    (
+        kept.into_iter()
+            .map(|(feed_title, entry, _)| (feed_title, entry))
+            .collect(),
+        also_in,
+    )
+}
+
+/// What changed in a feed since the previous refresh.
+#[derive(Clone, Default, PartialEq)]
+pub struct SnapshotDiff {
+    pub added: Vec<Entry>,
+    /// The entries whose title or summary changed, as before and after.
+    pub changed: Vec<(Entry, Entry)>,
+    pub removed: Vec<Entry>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+
+    /// The counts of what changed, such as "Δ3 new, 1 changed", leaving out
+    /// what did not.
+    pub fn summary(&self) -> String {
+        let counts = [
+            (self.added.len(), "new"),
+            (self.changed.len(), "changed"),
+            (self.removed.len(), "removed"),
+        ];
+        let parts: Vec<String> = counts
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, label)| format!("{count} {label}"))
+            .collect();
+
+        format!("Δ{}", parts.join(", "))
+    }
+}
+
+/// Compares the entries of a feed from two refreshes by entry ID.
+pub fn diff_snapshots(old: &[Entry], new: &[Entry]) -> SnapshotDiff {
+    let old_by_id: HashMap<&str, &Entry> = old.iter().map(|e| (e.id.as_str(), e)).collect();
+    let new_ids: HashSet<&str> = new.iter().map(|e| e.id.as_str()).collect();
+    let mut diff = SnapshotDiff::default();
+
+    for entry in new {
+        match old_by_id.get(entry.id.as_str()) {
+            None => diff.added.push(entry.clone()),
+            Some(previous)
+                if previous.best_title() != entry.best_title()
+                    || previous.best_summary() != entry.best_summary() =>
+            {
+                diff.changed.push(((*previous).clone(), entry.clone()));
+            }
+            Some(_) => {}
+        }
+    }
+    diff.removed = old
+        .iter()
+        .filter(|e| !new_ids.contains(e.id.as_str()))
+        .cloned()
+        .collect();
+
+    diff
+}
+
+/// A run of words in a word-level diff.
+#[derive(Clone, PartialEq)]
+pub enum WordChange {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+/// The word-level changes from the old text to the new one, with markup
+/// stripped from both.
+pub fn word_diff(old: &str, new: &str) -> Vec<WordChange> {
+    let (old, new) = (strip_html(old), strip_html(new));
+    let diff = TextDiff::from_words(&old, &new);
+    let mut changes: Vec<WordChange> = Vec::new();
+
+    for change in diff.iter_all_changes() {
+        let value = change.value();
+        match (change.tag(), changes.last_mut()) {
+            (ChangeTag::Equal, Some(WordChange::Same(run)))
+            | (ChangeTag::Delete, Some(WordChange::Removed(run)))
+            | (ChangeTag::Insert, Some(WordChange::Added(run))) => run.push_str(value),
+            (ChangeTag::Equal, _) => changes.push(WordChange::Same(value.to_owned())),
+            (ChangeTag::Delete, _) => changes.push(WordChange::Removed(value.to_owned())),
+            (ChangeTag::Insert, _) => changes.push(WordChange::Added(value.to_owned())),
+        }
+    }
+
+    changes
+}
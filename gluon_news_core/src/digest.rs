@@ -0,0 +1,119 @@
+use crate::{compat::EntryExt, config::SmtpConfig, fetch_news, read_state, text, News, SETTINGS};
+use chrono::{Duration, Utc};
+use cron::Schedule;
+use feed_rs::model::Entry;
+use lettre::{
+    message::header::ContentType, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
+use std::{error::Error, str::FromStr};
+
+const SUMMARY_LENGTH: usize = 280;
+
+pub fn generate_digest_html(entries: &[(String, Entry)]) -> String {
+    let mut html = String::from(
+        "<html><body style=\"font-family: sans-serif;\"><h1>Gluon News digest</h1><ul>",
+    );
+
+    for (feed_title, entry) in entries {
+        let title = text::escape_html(&entry.best_title());
+        let link = text::escape_html(entry.best_link().unwrap_or_default());
+        let summary = match &entry.summary {
+            Some(summary) => text::escape_html(&text::truncate(
+                &text::strip_html(&summary.content),
+                SUMMARY_LENGTH,
+            )),
+            None => String::new(),
+        };
+
+        html.push_str(&format!(
+            "<li><p><a href=\"{link}\">{title}</a><br><small>{}</small></p><p>{summary}</p></li>",
+            text::escape_html(feed_title)
+        ));
+    }
+
+    html.push_str("</ul></body></html>");
+
+    html
+}
+
+/// Sends an HTML email through the given SMTP server. The sender is the SMTP
+/// user, or the recipient when there is none.
+pub(crate) async fn send_html_email(
+    smtp: &SmtpConfig,
+    to: &str,
+    subject: &str,
+    html: String,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let from = smtp.user.as_deref().unwrap_or(to);
+    let email = Message::builder()
+        .from(from.parse()?)
+        .to(to.parse()?)
+        .subject(subject)
+        .header(ContentType::TEXT_HTML)
+        .body(html)?;
+    let mut mailer = AsyncSmtpTransport::<Tokio1Executor>::relay(&smtp.host)?;
+
+    if let Some(port) = smtp.port {
+        mailer = mailer.port(port);
+    }
+    if let (Some(user), Some(password)) = (&smtp.user, &smtp.password) {
+        mailer = mailer.credentials(Credentials::new(user.clone(), password.clone()));
+    }
+
+    mailer.build().send(email).await?;
+
+    Ok(())
+}
+
+async fn send_digest(html: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (Some(host), Some(to)) = (&SETTINGS.digest_smtp_host, &SETTINGS.digest_to) else {
+        return Err("digest_smtp_host and digest_to must be set".into());
+    };
+    let smtp = SmtpConfig {
+        host: host.clone(),
+        port: SETTINGS.digest_smtp_port,
+        user: SETTINGS.digest_smtp_user.clone(),
+        password: SETTINGS.digest_smtp_password.clone(),
+    };
+
+    send_html_email(&smtp, to, "Gluon News digest", html).await
+}
+
+pub async fn run_scheduler() {
+    let Some(expression) = &SETTINGS.digest_schedule else {
+        return;
+    };
+    let schedule = match Schedule::from_str(expression) {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            tracing::warn!("invalid digest_schedule {expression:?}: {e}");
+            return;
+        }
+    };
+
+    while let Some(next) = schedule.upcoming(Utc).next() {
+        if let Ok(delay) = (next - Utc::now()).to_std() {
+            tokio::time::sleep(delay).await;
+        }
+
+        let feeds: Vec<&str> = SETTINGS.enabled_feeds().map(|f| f.as_str()).collect();
+        let Ok(news) = fetch_news(&feeds).await.map(News::from_results) else {
+            continue;
+        };
+        let read = read_state::load();
+        let since = Utc::now() - Duration::hours(24);
+        let entries: Vec<(String, Entry)> = news
+            .entries
+            .into_iter()
+            .filter(|e| e.1.published.is_some_and(|p| p >= since) && !read.contains(&e.1.id))
+            .collect();
+
+        if entries.is_empty() {
+            continue;
+        }
+        if let Err(e) = send_digest(generate_digest_html(&entries)).await {
+            tracing::warn!("could not send digest: {e}");
+        }
+    }
+}
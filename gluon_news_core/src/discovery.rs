@@ -0,0 +1,80 @@
+//! Finds the feeds of a website from its address, for adding a site without
+//! knowing where its feed is.
+
+use feed_rs::parser;
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::Client;
+use std::{collections::HashMap, error::Error};
+use url::Url;
+
+const FEED_TYPES: [&str; 4] = [
+    "application/rss+xml",
+    "application/atom+xml",
+    "application/feed+json",
+    "application/json",
+];
+
+lazy_static! {
+    static ref LINK_TAG: Regex = Regex::new(r"(?is)<link\b[^>]*>").unwrap();
+    static ref ATTRIBUTE: Regex =
+        Regex::new(r#"(?is)([a-z-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+}
+
+/// The feeds a page names in its `<link rel="alternate">` tags, as absolute
+/// URLs, or the address itself when it is a feed already.
+///
+/// A client of its own is used, as the first-run wizard discovers feeds
+/// before there are settings to build the shared client from.
+pub async fn discover_feeds(address: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let address = if address.contains("://") {
+        address.to_owned()
+    } else {
+        format!("https://{address}")
+    };
+    let response = Client::new()
+        .get(&address)
+        .header("User-Agent", "gluon_news")
+        .send()
+        .await?
+        .error_for_status()?;
+    let base = response.url().clone();
+    let body = response.text().await?;
+
+    if parser::parse(body.as_bytes()).is_ok() {
+        return Ok(vec![base.to_string()]);
+    }
+
+    Ok(feed_links(&body, &base))
+}
+
+fn feed_links(html: &str, base: &Url) -> Vec<String> {
+    let mut feeds: Vec<String> = Vec::new();
+
+    for tag in LINK_TAG.find_iter(html) {
+        let attributes: HashMap<String, &str> = ATTRIBUTE
+            .captures_iter(tag.as_str())
+            .filter_map(|c| {
+                let value = c.get(2).or_else(|| c.get(3))?.as_str();
+                Some((c[1].to_ascii_lowercase(), value))
+            })
+            .collect();
+        let is_alternate = attributes.get("rel").is_some_and(|rel| {
+            rel.split_whitespace()
+                .any(|r| r.eq_ignore_ascii_case("alternate"))
+        });
+        let is_feed = attributes
+            .get("type")
+            .is_some_and(|t| FEED_TYPES.contains(&t.trim().to_ascii_lowercase().as_str()));
+        let href = attributes
+            .get("href")
+            .and_then(|href| base.join(&href.replace("&amp;", "&")).ok());
+        if let (true, true, Some(href)) = (is_alternate, is_feed, href) {
+            if !feeds.contains(&href.to_string()) {
+                feeds.push(href.to_string());
+            }
+        }
+    }
+
+    feeds
+}
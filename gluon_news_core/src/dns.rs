@@ -0,0 +1,93 @@
+//! Resolves host names over DNS-over-HTTPS or DNS-over-TLS, so the feeds
+//! looked up are hidden from whoever runs the network.
+
+use hickory_resolver::{
+    config::{NameServerConfigGroup, ResolverConfig, ResolverOpts},
+    TokioAsyncResolver,
+};
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use url::Url;
+
+const DOH_PORT: u16 = 443;
+const DOT_PORT: u16 = 853;
+
+pub struct SecureResolver {
+    resolver: TokioAsyncResolver,
+}
+
+impl Resolve for SecureResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.resolver.clone();
+
+        Box::pin(async move {
+            let lookup = resolver.lookup_ip(name.as_str()).await?;
+            // The port is replaced by the one of the URL being requested.
+            let addrs: Addrs = Box::new(
+                lookup
+                    .iter()
+                    .map(|ip| SocketAddr::new(ip, 0))
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            );
+
+            Ok(addrs)
+        })
+    }
+}
+
+/// The addresses of a DNS server given by name, looked up once with the
+/// system resolver.
+fn server_ips(host: &str, port: u16) -> Result<Vec<IpAddr>, String> {
+    if let Ok(ip) = host.trim_start_matches('[').trim_end_matches(']').parse() {
+        return Ok(vec![ip]);
+    }
+
+    (host, port)
+        .to_socket_addrs()
+        .map(|addrs| addrs.map(|addr| addr.ip()).collect())
+        .map_err(|e| format!("could not look up {host}: {e}"))
+}
+
+/// A resolver using the DoH server at `url`, such as
+/// "https://1.1.1.1/dns-query". The server must answer on /dns-query.
+pub fn doh_resolver(url: &str) -> Result<SecureResolver, String> {
+    let url = Url::parse(url).map_err(|e| e.to_string())?;
+    let host = url.host_str().ok_or("the URL has no host")?;
+    let port = url.port().unwrap_or(DOH_PORT);
+    let servers = NameServerConfigGroup::from_ips_https(
+        &server_ips(host, port)?,
+        port,
+        host.to_owned(),
+        true,
+    );
+
+    Ok(resolver(servers))
+}
+
+/// A resolver using the DoT server at `server`, a host with an optional port
+/// such as "1.1.1.1" or "dns.quad9.net:853".
+pub fn dot_resolver(server: &str) -> Result<SecureResolver, String> {
+    let (host, port) = match server.rsplit_once(':') {
+        Some((host, port)) if !host.ends_with(':') => (
+            host,
+            port.parse().map_err(|_| format!("invalid port {port}"))?,
+        ),
+        _ => (server, DOT_PORT),
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let servers =
+        NameServerConfigGroup::from_ips_tls(&server_ips(host, port)?, port, host.to_owned(), true);
+
+    Ok(resolver(servers))
+}
+
+fn resolver(servers: NameServerConfigGroup) -> SecureResolver {
+    SecureResolver {
+        resolver: TokioAsyncResolver::tokio(
+            ResolverConfig::from_parts(None, vec![], servers),
+            ResolverOpts::default(),
+        ),
+    }
+}
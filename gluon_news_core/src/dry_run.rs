@@ -0,0 +1,120 @@
+//! Works out what a refresh would fetch, without making any requests, to see
+//! what each refresh costs.
+
+use crate::{
+    config::{self, Settings},
+    health, local_feeds, rate_limits,
+    telemetry::{format_bytes, Telemetry},
+};
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+/// The feeds a refresh would request, and the ones it would not.
+#[derive(Default)]
+pub struct FetchPlan {
+    /// The feeds that would be requested.
+    pub fetched: Vec<String>,
+    /// The fetched feeds with no earlier fetch to estimate their size from.
+    pub never_fetched: Vec<String>,
+    /// The feeds read from disk rather than downloaded.
+    pub local: Vec<String>,
+    /// The feeds skipped until their rate limit is over.
+    pub rate_limited: Vec<(String, DateTime<Utc>)>,
+    /// The feeds skipped as disabled in `feed_options`.
+    pub disabled: Vec<String>,
+    /// The feeds the startup health check would disable, as they have been
+    /// unreachable for too long.
+    pub unhealthy: Vec<String>,
+    /// The feeds that would fail, with why.
+    pub invalid: Vec<(String, String)>,
+    /// The size of the last fetch of each fetched feed, added up.
+    pub estimated_bytes: u64,
+}
+
+impl FetchPlan {
+    /// The plan for the feeds in `settings`, as of `now`.
+    pub fn new(settings: &Settings, now: DateTime<Utc>) -> Self {
+        let telemetry = Telemetry::load();
+        let rate_limits = rate_limits::load();
+        let unhealthy = if settings.health_check_on_startup {
+            health::due_to_disable(settings, now)
+        } else {
+            Vec::new()
+        };
+        let mut plan = FetchPlan::default();
+
+        for feed in settings.all_feeds() {
+            if !settings.feed_options(feed).enabled {
+                plan.disabled.push(feed.clone());
+                continue;
+            }
+            if unhealthy.contains(feed) {
+                plan.unhealthy.push(feed.clone());
+                continue;
+            }
+            if let Err(e) = config::check_feed(feed) {
+                plan.invalid.push((feed.clone(), e));
+                continue;
+            }
+            if let Some(until) = rate_limits.get(feed).filter(|until| **until > now) {
+                plan.rate_limited.push((feed.clone(), *until));
+                continue;
+            }
+            if local_feeds::is_local(feed) {
+                plan.local.push(feed.clone());
+            } else {
+                match telemetry.get(feed) {
+                    Some(last) => plan.estimated_bytes += last.response_bytes,
+                    None => plan.never_fetched.push(feed.clone()),
+                }
+            }
+            plan.fetched.push(feed.clone());
+        }
+        for search in &settings.search_feeds {
+            let url = search.url();
+            match telemetry.get(&url) {
+                Some(last) => plan.estimated_bytes += last.response_bytes,
+                None => plan.never_fetched.push(url.clone()),
+            }
+            plan.fetched.push(url);
+        }
+
+        plan
+    }
+}
+
+impl fmt::Display for FetchPlan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (feed, until) in &self.rate_limited {
+            writeln!(f, "skip {feed}: rate limited until {until}")?;
+        }
+        for feed in &self.disabled {
+            writeln!(f, "skip {feed}: disabled")?;
+        }
+        for feed in &self.unhealthy {
+            writeln!(
+                f,
+                "skip {feed}: unreachable for too long, would be disabled"
+            )?;
+        }
+        for (feed, error) in &self.invalid {
+            writeln!(f, "fail {feed}: {error}")?;
+        }
+        for feed in &self.never_fetched {
+            writeln!(f, "fetch {feed}: never fetched, size unknown")?;
+        }
+        write!(
+            f,
+            "Would fetch {} feeds ({} read from disk, {} never fetched before), {} would be skipped ({} rate limited, {} disabled, {} unreachable), {} would fail, estimated download: ~{} based on the last fetch of each feed",
+            self.fetched.len(),
+            self.local.len(),
+            self.never_fetched.len(),
+            self.rate_limited.len() + self.disabled.len() + self.unhealthy.len(),
+            self.rate_limited.len(),
+            self.disabled.len(),
+            self.unhealthy.len(),
+            self.invalid.len(),
+            format_bytes(self.estimated_bytes)
+        )
+    }
+}
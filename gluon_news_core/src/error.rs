@@ -0,0 +1,260 @@
+use std::{error::Error, fmt, io};
+use tokio::task::JoinError;
+
+#[derive(Debug)]
+pub enum FeedError {
+    NetworkError(reqwest::Error),
+    ParseError(String),
+    AuthError,
+    Timeout,
+    RateLimited(u64),
+    WebFinger(WebFingerError),
+    OAuth(OAuthError),
+    Io(io::Error),
+    /// The proxy or resolver configured cannot be used, so nothing is fetched
+    /// rather than fetching without it.
+    Client(String),
+}
+
+impl FeedError {
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            FeedError::NetworkError(e) => {
+                e.is_connect() || e.status().is_some_and(|s| s.is_server_error())
+            }
+            FeedError::Timeout => true,
+            FeedError::WebFinger(e) => e.is_retryable(),
+            FeedError::ParseError(_)
+            | FeedError::AuthError
+            | FeedError::RateLimited(_)
+            | FeedError::OAuth(_)
+            | FeedError::Io(_)
+            | FeedError::Client(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for FeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeedError::NetworkError(e) => write!(f, "network error: {e}"),
+            FeedError::ParseError(e) => write!(f, "could not parse feed: {e}"),
+            FeedError::AuthError => write!(f, "authentication required"),
+            FeedError::Timeout => write!(f, "timed out"),
+            FeedError::RateLimited(seconds) => {
+                write!(f, "rate limited, retry in {}m", seconds.div_ceil(60))
+            }
+            FeedError::WebFinger(e) => write!(f, "{e}"),
+            FeedError::OAuth(e) => write!(f, "{e}"),
+            FeedError::Io(e) => write!(f, "could not read feed file: {e}"),
+            FeedError::Client(e) => write!(f, "not fetching, {e}"),
+        }
+    }
+}
+
+impl Error for FeedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FeedError::NetworkError(e) => Some(e),
+            FeedError::WebFinger(e) => Some(e),
+            FeedError::OAuth(e) => Some(e),
+            FeedError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for FeedError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            FeedError::Timeout
+        } else {
+            FeedError::NetworkError(e)
+        }
+    }
+}
+
+impl From<WebFingerError> for FeedError {
+    fn from(e: WebFingerError) -> Self {
+        FeedError::WebFinger(e)
+    }
+}
+
+impl From<OAuthError> for FeedError {
+    fn from(e: OAuthError) -> Self {
+        FeedError::OAuth(e)
+    }
+}
+
+impl From<io::Error> for FeedError {
+    fn from(e: io::Error) -> Self {
+        FeedError::Io(e)
+    }
+}
+
+#[derive(Debug)]
+pub enum WebFingerError {
+    InvalidAccount(String),
+    NetworkError(reqwest::Error),
+    ParseError(String),
+    NoFeedLink,
+}
+
+impl WebFingerError {
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            WebFingerError::NetworkError(e) => {
+                e.is_timeout() || e.is_connect() || e.status().is_some_and(|s| s.is_server_error())
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for WebFingerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebFingerError::InvalidAccount(acct) => write!(f, "invalid account {acct}"),
+            WebFingerError::NetworkError(e) => write!(f, "webfinger lookup failed: {e}"),
+            WebFingerError::ParseError(e) => write!(f, "could not parse webfinger response: {e}"),
+            WebFingerError::NoFeedLink => write!(f, "webfinger response has no Atom feed link"),
+        }
+    }
+}
+
+impl Error for WebFingerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WebFingerError::NetworkError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for WebFingerError {
+    fn from(e: reqwest::Error) -> Self {
+        WebFingerError::NetworkError(e)
+    }
+}
+
+#[derive(Debug)]
+pub enum OAuthError {
+    Keyring(keyring::Error),
+    Io(io::Error),
+    NetworkError(reqwest::Error),
+    Denied(String),
+    /// The feed has no tokens yet, or they can no longer be refreshed, so it
+    /// has to be authorized in the browser first.
+    NotAuthorized,
+}
+
+impl fmt::Display for OAuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OAuthError::Keyring(e) => write!(f, "could not access the keychain: {e}"),
+            OAuthError::Io(e) => write!(f, "could not start authorization: {e}"),
+            OAuthError::NetworkError(e) => write!(f, "token request failed: {e}"),
+            OAuthError::Denied(reason) => write!(f, "authorization failed: {reason}"),
+            OAuthError::NotAuthorized => write!(f, "not authorized yet"),
+        }
+    }
+}
+
+impl Error for OAuthError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            OAuthError::Keyring(e) => Some(e),
+            OAuthError::Io(e) => Some(e),
+            OAuthError::NetworkError(e) => Some(e),
+            OAuthError::Denied(_) | OAuthError::NotAuthorized => None,
+        }
+    }
+}
+
+impl From<keyring::Error> for OAuthError {
+    fn from(e: keyring::Error) -> Self {
+        OAuthError::Keyring(e)
+    }
+}
+
+impl From<io::Error> for OAuthError {
+    fn from(e: io::Error) -> Self {
+        OAuthError::Io(e)
+    }
+}
+
+impl From<reqwest::Error> for OAuthError {
+    fn from(e: reqwest::Error) -> Self {
+        OAuthError::NetworkError(e)
+    }
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    Io(io::Error),
+    ParseError(serde_json::Error),
+    /// Reading or writing settings.json or the bookmarks failed.
+    Settings(String),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io(e) => write!(f, "could not read the backup: {e}"),
+            ImportError::ParseError(e) => write!(f, "could not parse the backup: {e}"),
+            ImportError::Settings(e) => write!(f, "could not save the imported feeds: {e}"),
+        }
+    }
+}
+
+impl Error for ImportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ImportError::Io(e) => Some(e),
+            ImportError::ParseError(e) => Some(e),
+            ImportError::Settings(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for ImportError {
+    fn from(e: io::Error) -> Self {
+        ImportError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ImportError {
+    fn from(e: serde_json::Error) -> Self {
+        ImportError::ParseError(e)
+    }
+}
+
+#[derive(Debug)]
+pub enum FeedBatchError {
+    NoFeeds,
+    TaskFailed(JoinError),
+}
+
+impl fmt::Display for FeedBatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeedBatchError::NoFeeds => write!(f, "no feeds configured"),
+            FeedBatchError::TaskFailed(e) => write!(f, "fetch task failed: {e}"),
+        }
+    }
+}
+
+impl Error for FeedBatchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FeedBatchError::NoFeeds => None,
+            FeedBatchError::TaskFailed(e) => Some(e),
+        }
+    }
+}
+
+impl From<JoinError> for FeedBatchError {
+    fn from(e: JoinError) -> Self {
+        FeedBatchError::TaskFailed(e)
+    }
+}
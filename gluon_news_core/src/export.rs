@@ -0,0 +1,91 @@
+//! Sends entries to every configured export target at once.
+
+use crate::{
+    collections::{self, ExportFormat},
+    config::ExportTarget,
+    digest, CLIENT,
+};
+use feed_rs::model::Entry;
+use futures::future::join_all;
+use reqwest::Method;
+use std::{error::Error, path::Path};
+
+const EXPORT_SUBJECT: &str = "Gluon News export";
+
+type ExportResult = Result<(), Box<dyn Error + Send + Sync>>;
+
+impl ExportTarget {
+    /// A short description for logs and notices.
+    pub fn describe(&self) -> String {
+        match self {
+            ExportTarget::File { path, .. } => format!("file {path}"),
+            ExportTarget::Http { method, url, .. } => format!("{method} {url}"),
+            ExportTarget::Email { to, .. } => format!("email to {to}"),
+        }
+    }
+}
+
+async fn write_file(path: &str, format: ExportFormat, entries: &[(String, Entry)]) -> ExportResult {
+    let ids: Vec<String> = entries.iter().map(|e| e.1.id.clone()).collect();
+    let data = match format {
+        ExportFormat::Json => collections::to_json(&ids, entries)?,
+        ExportFormat::Opml => collections::to_opml("Gluon News", &ids, entries),
+    };
+
+    if let Some(parent) = Path::new(path).parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(path, data).await?;
+
+    Ok(())
+}
+
+async fn send_http(
+    url: &str,
+    method: &str,
+    headers: &std::collections::HashMap<String, String>,
+    entries: &[(String, Entry)],
+) -> ExportResult {
+    let ids: Vec<String> = entries.iter().map(|e| e.1.id.clone()).collect();
+    let mut request = CLIENT
+        .request(Method::from_bytes(method.as_bytes())?, url)
+        .header("User-Agent", "gluon_news")
+        .header("Content-Type", "application/json")
+        .body(collections::to_json(&ids, entries)?);
+
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    request.send().await?.error_for_status()?;
+
+    Ok(())
+}
+
+async fn export_to(target: &ExportTarget, entries: &[(String, Entry)]) -> ExportResult {
+    match target {
+        ExportTarget::File { path, format } => write_file(path, *format, entries).await,
+        ExportTarget::Http {
+            url,
+            method,
+            headers,
+        } => send_http(url, method, headers, entries).await,
+        ExportTarget::Email { smtp_config, to } => {
+            let html = digest::generate_digest_html(entries);
+            digest::send_html_email(smtp_config, to, EXPORT_SUBJECT, html).await
+        }
+    }
+}
+
+/// Exports the entries to all targets in parallel, returning each target's
+/// description with its result, in the order the targets are configured.
+pub async fn export_all(
+    targets: &[ExportTarget],
+    entries: &[(String, Entry)],
+) -> Vec<(String, ExportResult)> {
+    join_all(
+        targets
+            .iter()
+            .map(|target| async move { (target.describe(), export_to(target, entries).await) }),
+    )
+    .await
+}
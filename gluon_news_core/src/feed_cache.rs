@@ -0,0 +1,170 @@
+//! The reading state in a single file, rather than in a file per feature.
+//! The read entries, bookmarks, tags, reminders and read streak are all
+//! loaded and saved through it.
+//!
+//! It is a JSON file like the rest of the profile rather than a SQLite
+//! database, as the app has no database to add tables to. There are no notes
+//! or statistics files to move into it: notes were never added, and the
+//! statistics are worked out from the entries each time.
+
+use crate::{bookmarks, profile, read_state, reminders::Reminders, state, streak::ReadDays, tags};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs::{self, read_to_string},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+const FEED_CACHE_FILE: &str = "feed_cache.json";
+
+lazy_static! {
+    /// The cache as last read or saved, with when its file was modified then,
+    /// so the file is only parsed again once something else changed it.
+    static ref OPENED: Mutex<Option<(SystemTime, FeedCache)>> = Mutex::new(None);
+}
+
+/// The read, bookmarked and tagged entries, the pending reminders and the
+/// days read on, saved together.
+#[derive(Default, Deserialize, Serialize)]
+pub struct FeedCache {
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(default)]
+    pub(crate) read: HashSet<String>,
+    #[serde(default)]
+    pub(crate) bookmarks: HashSet<String>,
+    #[serde(default)]
+    pub(crate) tags: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub(crate) reminders: Reminders,
+    #[serde(default)]
+    pub(crate) read_days: ReadDays,
+}
+
+impl FeedCache {
+    /// Opens the cache at `path`. A cache that does not exist yet is created
+    /// from the files of the profile, which are left as they are.
+    pub fn open(path: &Path) -> Result<FeedCache, Box<dyn Error>> {
+        match read_to_string(path) {
+            Ok(data) => {
+                let mut cache: FeedCache = serde_json::from_str(&data)?;
+                cache.path = path.to_path_buf();
+                Ok(cache)
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                let cache = FeedCache {
+                    path: path.to_path_buf(),
+                    read: read_state::load_legacy(),
+                    bookmarks: bookmarks::load_legacy(),
+                    tags: tags::load_legacy(),
+                    reminders: Reminders::load_legacy(),
+                    read_days: ReadDays::load_legacy(),
+                };
+                cache.save()?;
+                Ok(cache)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Opens the cache of the profile.
+    pub fn open_default() -> Result<FeedCache, Box<dyn Error>> {
+        Self::open(&profile::path(FEED_CACHE_FILE))
+    }
+
+    /// Saved to a file next to the cache first, which then replaces it, so a
+    /// crash while saving leaves the cache as it was.
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        state::write(&self.path, serde_json::to_string(self)?)?;
+
+        Ok(())
+    }
+
+    pub fn read(&self) -> &HashSet<String> {
+        &self.read
+    }
+
+    pub fn read_mut(&mut self) -> &mut HashSet<String> {
+        &mut self.read
+    }
+
+    pub fn bookmarks(&self) -> &HashSet<String> {
+        &self.bookmarks
+    }
+
+    pub fn bookmarks_mut(&mut self) -> &mut HashSet<String> {
+        &mut self.bookmarks
+    }
+
+    pub fn tags(&self) -> &HashMap<String, Vec<String>> {
+        &self.tags
+    }
+
+    pub fn tags_mut(&mut self) -> &mut HashMap<String, Vec<String>> {
+        &mut self.tags
+    }
+
+    pub fn reminders(&self) -> &Reminders {
+        &self.reminders
+    }
+
+    pub fn reminders_mut(&mut self) -> &mut Reminders {
+        &mut self.reminders
+    }
+
+    pub fn read_days(&self) -> &ReadDays {
+        &self.read_days
+    }
+
+    pub fn read_days_mut(&mut self) -> &mut ReadDays {
+        &mut self.read_days
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Uses the cache of the profile, opening it again only when its file changed
+/// since it was last read or saved. The cache is kept for next time unless
+/// `use_cache` fails.
+fn with_cache<T>(
+    use_cache: impl FnOnce(&mut FeedCache) -> Result<T, Box<dyn Error>>,
+) -> Result<T, Box<dyn Error>> {
+    let path = profile::path(FEED_CACHE_FILE);
+    let mut opened = OPENED.lock().unwrap();
+    let on_disk = modified(&path);
+    let mut cache = match opened.take() {
+        Some((seen, cache)) if on_disk == Some(seen) && cache.path == path => cache,
+        _ => FeedCache::open(&path)?,
+    };
+    let result = use_cache(&mut cache)?;
+
+    if let Some(modified) = modified(&path) {
+        *opened = Some((modified, cache));
+    }
+
+    Ok(result)
+}
+
+/// Part of the cache of the profile, or of an empty one when it cannot be
+/// read, which is never saved over the file.
+pub(crate) fn load<T>(get: impl Fn(&FeedCache) -> T) -> T {
+    with_cache(|cache| Ok(get(cache))).unwrap_or_else(|e| {
+        tracing::warn!("could not open the feed cache: {e}");
+        get(&FeedCache::default())
+    })
+}
+
+/// Changes the cache of the profile and saves it.
+pub(crate) fn update(change: impl FnOnce(&mut FeedCache)) -> Result<(), Box<dyn Error>> {
+    with_cache(|cache| {
+        change(cache);
+        cache.save()
+    })
+}
@@ -0,0 +1,116 @@
+//! Imports a Feedly backup: the subscriptions with their categories and
+//! custom titles, and the saved articles.
+
+use crate::{bookmarks, config, error::ImportError};
+use serde::Deserialize;
+use std::{collections::BTreeMap, fs::read_to_string, path::Path};
+
+/// Feedly identifies feeds as `feed/` followed by the feed URL.
+const FEED_PREFIX: &str = "feed/";
+
+#[derive(Deserialize)]
+struct Category {
+    label: String,
+}
+
+#[derive(Deserialize)]
+struct Subscription {
+    id: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    categories: Vec<Category>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedArticle {
+    /// The ID the entry has in its own feed, which Feedly keeps.
+    origin_id: String,
+}
+
+/// A backup as written by Feedly, or just its subscriptions list.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Backup {
+    Full {
+        subscriptions: Vec<Subscription>,
+        #[serde(default)]
+        saved: Vec<SavedArticle>,
+    },
+    Subscriptions(Vec<Subscription>),
+}
+
+/// How much of a backup was new.
+#[derive(Debug, Default)]
+pub struct ImportStats {
+    pub feeds: usize,
+    pub groups: usize,
+    pub titles: usize,
+    pub saved: usize,
+}
+
+/// Adds the backup's feeds to settings.json, their categories as bundles and
+/// their custom titles as title overrides, and bookmarks the saved articles.
+/// Saved articles are matched by ID, so they show when their feed has them.
+pub fn import_feedly_backup(path: &Path) -> Result<ImportStats, ImportError> {
+    let (subscriptions, saved) = match serde_json::from_str(&read_to_string(path)?)? {
+        Backup::Full {
+            subscriptions,
+            saved,
+        } => (subscriptions, saved),
+        Backup::Subscriptions(subscriptions) => (subscriptions, Vec::new()),
+    };
+    let subscribed = config::read_settings()
+        .map_err(|e| ImportError::Settings(e.to_string()))?
+        .feeds;
+    let mut stats = ImportStats::default();
+    let mut new_feeds: Vec<String> = Vec::new();
+    let mut titles: Vec<(String, String)> = Vec::new();
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for subscription in subscriptions {
+        let Some(url) = subscription.id.strip_prefix(FEED_PREFIX) else {
+            continue;
+        };
+        if !subscribed.iter().any(|f| f == url) && !new_feeds.iter().any(|f| f == url) {
+            new_feeds.push(url.to_owned());
+        }
+        if let Some(title) = subscription.title.filter(|t| !t.trim().is_empty()) {
+            titles.push((url.to_owned(), title));
+        }
+        for category in subscription.categories {
+            groups
+                .entry(category.label)
+                .or_default()
+                .push(url.to_owned());
+        }
+    }
+
+    let settings_error = |e: Box<dyn std::error::Error>| ImportError::Settings(e.to_string());
+    if !new_feeds.is_empty() {
+        config::add_feeds(&new_feeds).map_err(settings_error)?;
+    }
+    if !titles.is_empty() {
+        config::set_feed_titles(&titles).map_err(settings_error)?;
+    }
+    if !groups.is_empty() {
+        config::add_to_bundles(&groups).map_err(settings_error)?;
+    }
+
+    let mut bookmarked = bookmarks::load();
+    for article in saved {
+        if bookmarked.insert(article.origin_id) {
+            stats.saved += 1;
+        }
+    }
+    if stats.saved > 0 {
+        bookmarks::save(&bookmarked).map_err(settings_error)?;
+    }
+
+    stats.feeds = new_feeds.len();
+    stats.titles = titles.len();
+    stats.groups = groups.len();
+
+    Ok(stats)
+}
@@ -0,0 +1,100 @@
+//! Records how long each feed takes to fetch, to estimate how long a refresh
+//! will take before it starts.
+
+use crate::{host_of, state, SETTINGS};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, error::Error, time::Duration};
+
+const HISTORY_LEN: usize = 10;
+const FETCH_TIMES_FILE: &str = "fetch_times.json";
+
+/// The latest fetch durations of a feed, oldest first.
+#[derive(Clone)]
+pub struct FeedStats {
+    pub url: String,
+    pub durations: Vec<Duration>,
+}
+
+impl FeedStats {
+    /// The duration 90% of the recent fetches took at most.
+    fn percentile_90(&self) -> Duration {
+        let mut durations = self.durations.clone();
+        durations.sort();
+        let index = (durations.len() * 9).div_ceil(10).saturating_sub(1);
+
+        durations.get(index).copied().unwrap_or_default()
+    }
+}
+
+/// Fetch durations in milliseconds by feed URL, keeping the latest 10 per feed.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct FetchTimes {
+    feeds: HashMap<String, Vec<u64>>,
+}
+
+impl FetchTimes {
+    pub fn load() -> Self {
+        state::load(FETCH_TIMES_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        state::save(FETCH_TIMES_FILE, self)
+    }
+
+    pub fn record(&mut self, url: &str, duration: Duration) {
+        let durations = self.feeds.entry(url.to_owned()).or_default();
+
+        durations.push(duration.as_millis() as u64);
+        if durations.len() > HISTORY_LEN {
+            durations.drain(..durations.len() - HISTORY_LEN);
+        }
+    }
+
+    /// The stats of the given feeds, skipping the ones never fetched.
+    pub fn stats(&self, urls: &[&str]) -> Vec<FeedStats> {
+        urls.iter()
+            .filter_map(|url| {
+                let durations = self.feeds.get(*url)?;
+                Some(FeedStats {
+                    url: url.to_string(),
+                    durations: durations
+                        .iter()
+                        .map(|&ms| Duration::from_millis(ms))
+                        .collect(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// How long fetching the feeds should take. Feeds are fetched in parallel, so
+/// this is the slowest host: the sum of the 90th percentile fetch times of its
+/// feeds, divided by the connections allowed per host, but never less than its
+/// slowest feed. The delay before the last feed starts is added on top.
+pub fn estimate_fetch_duration(feed_stats: &[FeedStats]) -> Duration {
+    let mut hosts: HashMap<String, Vec<Duration>> = HashMap::new();
+    for stats in feed_stats {
+        hosts
+            .entry(host_of(&stats.url))
+            .or_default()
+            .push(stats.percentile_90());
+    }
+
+    let slowest_host = hosts
+        .values()
+        .map(|times| {
+            let parallelism = SETTINGS
+                .max_connections_per_host
+                .unwrap_or(times.len())
+                .clamp(1, times.len());
+            let slowest = times.iter().max().copied().unwrap_or_default();
+
+            (times.iter().sum::<Duration>() / parallelism as u32).max(slowest)
+        })
+        .max()
+        .unwrap_or_default();
+    let stagger = Duration::from_millis(SETTINGS.fetch_stagger_ms.unwrap_or_default());
+
+    slowest_host + stagger * feed_stats.len().saturating_sub(1) as u32
+}
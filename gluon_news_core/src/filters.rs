@@ -0,0 +1,135 @@
+use crate::{compat::EntryExt, reading_speed::word_count, text};
+use chrono::{DateTime, Duration, Local, Utc};
+use feed_rs::model::Entry;
+use std::collections::{BTreeMap, HashSet};
+
+/// Word count at which the length part of the content score is full.
+const FULL_SCORE_WORDS: f32 = 1000.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DateFilter {
+    Today,
+    Last7Days,
+    Last30Days,
+    AllTime,
+}
+
+impl DateFilter {
+    pub const ALL: [DateFilter; 4] = [
+        DateFilter::Today,
+        DateFilter::Last7Days,
+        DateFilter::Last30Days,
+        DateFilter::AllTime,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DateFilter::Today => "Today",
+            DateFilter::Last7Days => "Last 7 days",
+            DateFilter::Last30Days => "Last 30 days",
+            DateFilter::AllTime => "All time",
+        }
+    }
+
+    fn since(&self) -> Option<DateTime<Utc>> {
+        match self {
+            DateFilter::Today => Local::now()
+                .date_naive()
+                .and_hms_opt(0, 0, 0)?
+                .and_local_timezone(Local)
+                .earliest()
+                .map(|midnight| midnight.with_timezone(&Utc)),
+            DateFilter::Last7Days => Some(Utc::now() - Duration::days(7)),
+            DateFilter::Last30Days => Some(Utc::now() - Duration::days(30)),
+            DateFilter::AllTime => None,
+        }
+    }
+
+    pub fn matches(&self, date: Option<DateTime<Utc>>) -> bool {
+        match (self.since(), date) {
+            (None, _) => true,
+            (Some(since), Some(date)) => date >= since,
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// Whether the query appears in the entry title or summary, ignoring case.
+pub fn entry_matches(entry: &Entry, query: &str) -> bool {
+    let query = query.trim().to_lowercase();
+
+    if query.is_empty() {
+        return true;
+    }
+
+    let title = entry.title.as_ref().map(|t| t.content.to_lowercase());
+    let summary = entry
+        .summary
+        .as_ref()
+        .map(|s| text::strip_html(&s.content).to_lowercase());
+
+    title.is_some_and(|t| t.contains(&query)) || summary.is_some_and(|s| s.contains(&query))
+}
+
+/// How substantial the entry looks, from 0 to 1: its length counts for 0.4,
+/// with full marks at 1000 words, and having a content body rather than only a
+/// summary, media and an author count for 0.2 each.
+pub fn content_score(entry: &Entry) -> f32 {
+    let content = entry.best_content();
+    let words = word_count(&text::strip_html(&content)) as f32;
+    let has_body = entry.content.as_ref().is_some_and(|c| c.body.is_some());
+    let has_media = !entry.media.is_empty() || content.to_ascii_lowercase().contains("<img");
+    let has_author = entry.authors.iter().any(|a| !a.name.trim().is_empty());
+
+    0.4 * (words / FULL_SCORE_WORDS).min(1.0)
+        + [has_body, has_media, has_author]
+            .iter()
+            .map(|&present| if present { 0.2 } else { 0.0 })
+            .sum::<f32>()
+}
+
+/// Where the fold of an entry list goes, given the dates of its entries:
+/// after the first `after_n` entries, or at the first entry older than
+/// `after_hours`, whichever comes first. No fold is set when neither is.
+pub fn fold_position(
+    dates: impl IntoIterator<Item = Option<DateTime<Utc>>>,
+    after_n: Option<usize>,
+    after_hours: Option<u32>,
+    now: DateTime<Utc>,
+) -> Option<usize> {
+    let cutoff = after_hours.map(|hours| now - Duration::hours(hours as i64));
+    let by_age = cutoff.and_then(|cutoff| {
+        dates
+            .into_iter()
+            .position(|date| date.is_some_and(|date| date < cutoff))
+    });
+
+    match (after_n, by_age) {
+        (Some(n), Some(i)) => Some(n.min(i)),
+        (n, i) => n.or(i),
+    }
+}
+
+/// The unread entries published since `since`, grouped by feed title in
+/// alphabetical order, newest first within each feed.
+pub fn unread_since(
+    entries: &[(String, Entry)],
+    read: &HashSet<String>,
+    since: DateTime<Utc>,
+) -> Vec<(String, Vec<Entry>)> {
+    let mut groups: BTreeMap<String, Vec<Entry>> = BTreeMap::new();
+
+    for (feed_title, entry) in entries {
+        if !read.contains(&entry.id) && entry.best_date().is_some_and(|d| d >= since) {
+            groups
+                .entry(feed_title.clone())
+                .or_default()
+                .push(entry.clone());
+        }
+    }
+    for entries in groups.values_mut() {
+        entries.sort_by_key(|e| std::cmp::Reverse(e.best_date()));
+    }
+
+    groups.into_iter().collect()
+}
@@ -0,0 +1,53 @@
+//! The time spent actively reading each day, counted while the window has
+//! focus and has had input recently.
+
+use crate::state;
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, error::Error, time::Duration};
+
+const FOCUS_TIME_FILE: &str = "focus_time.json";
+/// How long without input before the user no longer counts as reading.
+pub const IDLE_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// The seconds read on each day.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct FocusTime {
+    seconds: BTreeMap<NaiveDate, u64>,
+}
+
+impl FocusTime {
+    pub fn load() -> Self {
+        state::load(FOCUS_TIME_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        state::save(FOCUS_TIME_FILE, self)
+    }
+
+    pub fn record(&mut self, day: NaiveDate, seconds: u64) {
+        *self.seconds.entry(day).or_default() += seconds;
+    }
+
+    pub fn minutes(&self, day: NaiveDate) -> u64 {
+        self.seconds.get(&day).copied().unwrap_or_default() / 60
+    }
+
+    /// The minutes read on each of the seven days up to `today`, oldest first.
+    pub fn week(&self, today: NaiveDate) -> Vec<(NaiveDate, u64)> {
+        (0..7)
+            .rev()
+            .filter_map(|days_ago| today.checked_sub_days(Days::new(days_ago)))
+            .map(|day| (day, self.minutes(day)))
+            .collect()
+    }
+}
+
+/// Minutes as "23m", or "1h 5m" from an hour on.
+pub fn format_minutes(minutes: u64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{minutes}m"),
+        (hours, minutes) => format!("{hours}h {minutes}m"),
+    }
+}
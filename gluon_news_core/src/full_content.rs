@@ -0,0 +1,287 @@
+//! Fetches the full article for entries whose feed only carries an excerpt,
+//! keeping the main content of the page the same way Readability does.
+
+use crate::{
+    compat::EntryExt,
+    compression::{compress_text, decompress_text},
+    host_of, profile, state, text, CLIENT, SETTINGS,
+};
+use feed_rs::model::Entry;
+use futures::future::join_all;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs::{self, read, read_to_string},
+    sync::{Arc, Mutex},
+};
+use tokio::sync::Semaphore;
+use url::Url;
+
+const FULL_CONTENT_FILE: &str = "full_content.json";
+/// Takes the place of the uncompressed file when compress_cache is set.
+const COMPRESSED_FULL_CONTENT_FILE: &str = "full_content.json.zst";
+const USER_AGENT: &str = "gluon_news";
+const MIN_SUMMARY_CHARS: usize = 500;
+const MIN_PARAGRAPH_CHARS: usize = 25;
+const DEFAULT_FETCHES_PER_HOST: usize = 2;
+
+lazy_static! {
+    /// Extracted content by article link, `None` when the page had nothing to
+    /// extract or robots.txt disallows fetching it.
+    static ref EXTRACTED: Mutex<HashMap<String, Option<String>>> = Mutex::new(load());
+    static ref ROBOTS: Mutex<HashMap<String, Vec<Rule>>> = Mutex::new(HashMap::new());
+    static ref BOILERPLATE: Regex = Regex::new(
+        r"(?is)<!--.*?-->|<script\b.*?</script\s*>|<style\b.*?</style\s*>|<noscript\b.*?</noscript\s*>|<nav\b.*?</nav\s*>|<header\b.*?</header\s*>|<footer\b.*?</footer\s*>|<aside\b.*?</aside\s*>|<form\b.*?</form\s*>"
+    )
+    .unwrap();
+    static ref ARTICLE: Regex = Regex::new(r"(?is)<article\b[^>]*>(.*?)</article\s*>").unwrap();
+    static ref MAIN: Regex = Regex::new(r"(?is)<main\b[^>]*>(.*?)</main\s*>").unwrap();
+    static ref PARAGRAPH: Regex = Regex::new(r"(?is)<p\b[^>]*>(.*?)</p\s*>").unwrap();
+}
+
+/// Reads whichever of the compressed and uncompressed files there is, so
+/// changing compress_cache keeps the cache.
+fn load() -> HashMap<String, Option<String>> {
+    read(profile::path(COMPRESSED_FULL_CONTENT_FILE))
+        .map(|data| decompress_text(&data))
+        .or_else(|_| read_to_string(profile::path(FULL_CONTENT_FILE)))
+        .map(|data| state::parse(FULL_CONTENT_FILE, &data))
+        .unwrap_or_default()
+}
+
+pub fn save() -> Result<(), Box<dyn Error>> {
+    let _span = tracing::debug_span!("save_full_content").entered();
+    let data = serde_json::to_string(&*EXTRACTED.lock().unwrap())?;
+    tracing::debug!("saving {} bytes of full content", data.len());
+    let stale = if SETTINGS.compress_cache {
+        state::write(
+            &profile::path(COMPRESSED_FULL_CONTENT_FILE),
+            compress_text(&data),
+        )?;
+        profile::path(FULL_CONTENT_FILE)
+    } else {
+        state::write(&profile::path(FULL_CONTENT_FILE), data)?;
+        profile::path(COMPRESSED_FULL_CONTENT_FILE)
+    };
+    if stale.exists() {
+        fs::remove_file(stale)?;
+    }
+
+    Ok(())
+}
+
+/// The size of the cache on disk, in bytes.
+pub fn cache_size() -> u64 {
+    [FULL_CONTENT_FILE, COMPRESSED_FULL_CONTENT_FILE]
+        .iter()
+        .filter_map(|file| fs::metadata(profile::path(file)).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Drops the content of articles no longer in any feed, then saves the cache,
+/// compressed when compress_cache is set.
+pub fn vacuum(links: &HashSet<&str>) -> Result<(), Box<dyn Error>> {
+    let _span = tracing::debug_span!("vacuum_full_content").entered();
+    let mut extracted = EXTRACTED.lock().unwrap();
+    let before = extracted.len();
+    extracted.retain(|link, _| links.contains(link.as_str()));
+    tracing::debug!("dropped {} cached articles", before - extracted.len());
+    drop(extracted);
+
+    save()
+}
+
+/// The content extracted from the article at `link`, if any.
+pub fn cached(link: &str) -> Option<String> {
+    EXTRACTED.lock().unwrap().get(link).cloned().flatten()
+}
+
+/// Keeps the paragraphs of the page's main content, looking in the largest
+/// `<article>`, then `<main>`, then the whole page, after dropping navigation,
+/// headers, footers, sidebars and scripts.
+pub fn extract(html: &str) -> Option<String> {
+    let html = BOILERPLATE.replace_all(html, "");
+    let region = |re: &Regex| {
+        re.captures_iter(&html)
+            .filter_map(|c| c.get(1))
+            .map(|m| m.as_str())
+            .max_by_key(|region| paragraphs(region).len())
+            .map(str::to_owned)
+    };
+    let region = region(&ARTICLE)
+        .or_else(|| region(&MAIN))
+        .unwrap_or_else(|| html.to_string());
+    let content = paragraphs(&region);
+
+    if content.is_empty() {
+        return None;
+    }
+
+    Some(text::sanitize_html(&content.join("\n")))
+}
+
+fn paragraphs(html: &str) -> Vec<String> {
+    PARAGRAPH
+        .captures_iter(html)
+        .filter(|c| text::strip_html(&c[1]).chars().count() >= MIN_PARAGRAPH_CHARS)
+        .map(|c| format!("<p>{}</p>", &c[1]))
+        .collect()
+}
+
+#[derive(Clone)]
+struct Rule {
+    allow: bool,
+    path: String,
+}
+
+/// Picks the rules of the robots.txt group naming this app, or of the `*`
+/// group when none does.
+fn parse_robots(robots: &str) -> Vec<Rule> {
+    let mut groups: Vec<(Vec<String>, Vec<Rule>)> = Vec::new();
+    let mut in_agents = false;
+
+    for line in robots.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        let Some((field, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+
+        match field.trim().to_ascii_lowercase().as_str() {
+            "user-agent" => {
+                if !in_agents {
+                    groups.push((Vec::new(), Vec::new()));
+                }
+                if let Some(group) = groups.last_mut() {
+                    group.0.push(value.to_ascii_lowercase());
+                }
+                in_agents = true;
+            }
+            field @ ("allow" | "disallow") => {
+                in_agents = false;
+                if let Some(group) = groups.last_mut() {
+                    if !value.is_empty() {
+                        group.1.push(Rule {
+                            allow: field == "allow",
+                            path: value.trim_end_matches(['*', '$']).to_owned(),
+                        });
+                    }
+                }
+            }
+            _ => in_agents = false,
+        }
+    }
+
+    let group = |matches: &dyn Fn(&str) -> bool| {
+        groups
+            .iter()
+            .find(|(agents, _)| agents.iter().any(|a| matches(a)))
+            .map(|(_, rules)| rules.clone())
+    };
+
+    group(&|agent| agent != "*" && USER_AGENT.contains(agent))
+        .or_else(|| group(&|agent| agent == "*"))
+        .unwrap_or_default()
+}
+
+/// The longest matching rule wins, with allow winning ties.
+fn is_allowed(rules: &[Rule], path: &str) -> bool {
+    rules
+        .iter()
+        .filter(|rule| path.starts_with(&rule.path))
+        .max_by_key(|rule| (rule.path.len(), rule.allow))
+        .is_none_or(|rule| rule.allow)
+}
+
+async fn robots_allow(url: &Url) -> bool {
+    let origin = url.origin().ascii_serialization();
+    let cached = ROBOTS.lock().unwrap().get(&origin).cloned();
+    let rules = match cached {
+        Some(rules) => rules,
+        None => {
+            let robots = match CLIENT
+                .get(format!("{origin}/robots.txt"))
+                .header("User-Agent", USER_AGENT)
+                .send()
+                .await
+            {
+                Ok(response) if response.status().is_success() => {
+                    response.text().await.unwrap_or_default()
+                }
+                _ => String::new(),
+            };
+            let rules = parse_robots(&robots);
+            ROBOTS.lock().unwrap().insert(origin, rules.clone());
+            rules
+        }
+    };
+
+    is_allowed(&rules, url.path())
+}
+
+/// Returns `None` when the article could not be fetched right now, so it is
+/// tried again on the next refresh.
+async fn fetch(link: &str) -> Option<Option<String>> {
+    let url = Url::parse(link).ok()?;
+
+    if !robots_allow(&url).await {
+        tracing::info!("robots.txt disallows fetching {link}");
+        return Some(None);
+    }
+
+    let response = CLIENT
+        .get(url)
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status());
+    let html = match response {
+        Ok(response) => response.text().await.ok()?,
+        Err(e) => {
+            tracing::warn!("could not fetch full content of {link}: {e}");
+            return None;
+        }
+    };
+
+    Some(extract(&html))
+}
+
+/// Extracts the full content of the entries whose summary is shorter than
+/// 500 characters and that were not looked at before. The articles are
+/// fetched together, with at most `max_connections_per_host` of them, or 2
+/// when it is not set, from the same host at a time.
+pub async fn extract_missing(entries: &[Entry]) {
+    let per_host = SETTINGS
+        .max_connections_per_host
+        .unwrap_or(DEFAULT_FETCHES_PER_HOST)
+        .max(1);
+    let mut links: Vec<&str> = entries
+        .iter()
+        .filter(|entry| text::strip_html(&entry.best_summary()).chars().count() < MIN_SUMMARY_CHARS)
+        .filter_map(|entry| entry.best_link())
+        .filter(|link| !EXTRACTED.lock().unwrap().contains_key(*link))
+        .collect();
+    links.sort_unstable();
+    links.dedup();
+
+    let mut semaphores: HashMap<String, Arc<Semaphore>> = HashMap::new();
+    let fetches: Vec<_> = links
+        .into_iter()
+        .map(|link| {
+            let semaphore = semaphores
+                .entry(host_of(link))
+                .or_insert_with(|| Arc::new(Semaphore::new(per_host)))
+                .clone();
+            async move {
+                let _permit = semaphore.acquire().await.ok();
+                if let Some(content) = fetch(link).await {
+                    EXTRACTED.lock().unwrap().insert(link.to_owned(), content);
+                }
+            }
+        })
+        .collect();
+    join_all(fetches).await;
+}
@@ -0,0 +1,119 @@
+//! Checks on startup that each feed still answers, and disables the feeds that
+//! have not for too long.
+
+use crate::{
+    config::{self, Settings},
+    local_feeds, state, webfinger, CLIENT, SETTINGS,
+};
+use chrono::{DateTime, Duration, Utc};
+use futures::future::join_all;
+use reqwest::StatusCode;
+use std::{collections::HashMap, error::Error};
+
+const FEED_HEALTH_FILE: &str = "feed_health.json";
+const CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// The feeds that did not answer, and the ones disabled because they have not
+/// answered for `auto_disable_after_days`.
+#[derive(Default)]
+pub struct HealthReport {
+    pub unreachable: Vec<String>,
+    pub disabled: Vec<String>,
+}
+
+/// When each currently unreachable feed was first found unreachable.
+fn load() -> HashMap<String, DateTime<Utc>> {
+    state::load(FEED_HEALTH_FILE)
+}
+
+fn save(unreachable_since: &HashMap<String, DateTime<Utc>>) -> Result<(), Box<dyn Error>> {
+    state::save(FEED_HEALTH_FILE, unreachable_since)
+}
+
+/// The feeds the next check would disable if they still do not answer, as
+/// they have been unreachable for `auto_disable_after_days` by `now`.
+pub fn due_to_disable(settings: &Settings, now: DateTime<Utc>) -> Vec<String> {
+    let Some(days) = settings.auto_disable_after_days else {
+        return Vec::new();
+    };
+    let unreachable_since = load();
+
+    settings
+        .all_feeds()
+        .filter(|feed| {
+            unreachable_since
+                .get(*feed)
+                .is_some_and(|since| now - *since >= Duration::days(days as i64))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Sends a HEAD request. Servers that do not allow HEAD, or refuse it to bots,
+/// still count as up.
+async fn is_reachable(url: &str) -> bool {
+    match CLIENT
+        .head(url)
+        .header("User-Agent", "gluon_news")
+        .timeout(CHECK_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(response) => {
+            let status = response.status();
+            !status.is_client_error() && !status.is_server_error()
+                || [StatusCode::FORBIDDEN, StatusCode::METHOD_NOT_ALLOWED].contains(&status)
+        }
+        Err(_) => false,
+    }
+}
+
+/// Checks every enabled feed. WebFinger addresses and local feeds are
+/// skipped, as they are not web URLs. When no feed answers, the network is
+/// taken to be down and nothing is recorded, so feeds are not disabled for
+/// starts without a connection.
+pub async fn check_feeds(feeds: &[String]) -> HealthReport {
+    let feeds: Vec<&String> = feeds
+        .iter()
+        .filter(|f| {
+            !webfinger::is_acct(f) && !local_feeds::is_local(f) && SETTINGS.feed_options(f).enabled
+        })
+        .collect();
+    let reachable = join_all(feeds.iter().map(|f| is_reachable(f))).await;
+    if !reachable.is_empty() && !reachable.contains(&true) {
+        tracing::warn!("no feed answered, skipping the health check as the network seems down");
+        return HealthReport::default();
+    }
+    let mut unreachable_since = load();
+    let now = Utc::now();
+    let mut report = HealthReport::default();
+
+    for (feed, reachable) in feeds.into_iter().zip(reachable) {
+        if reachable {
+            unreachable_since.remove(feed);
+            continue;
+        }
+
+        let since = *unreachable_since.entry(feed.clone()).or_insert(now);
+        report.unreachable.push(feed.clone());
+        if let Some(days) = SETTINGS.auto_disable_after_days {
+            if now - since >= Duration::days(days as i64) {
+                report.disabled.push(feed.clone());
+            }
+        }
+    }
+
+    if !report.disabled.is_empty() {
+        if let Err(e) = config::disable_feeds(&report.disabled) {
+            tracing::warn!("could not disable unreachable feeds: {e}");
+        }
+        for feed in &report.disabled {
+            unreachable_since.remove(feed);
+        }
+    }
+    if let Err(e) = save(&unreachable_since) {
+        tracing::warn!("could not save feed health: {e}");
+    }
+
+    report
+}
@@ -0,0 +1,177 @@
+use futures::future::join_all;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use reqwest::Client;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use url::Url;
+
+pub const DEFAULT_MAX_CACHE_SIZE_MB: u64 = 100;
+
+lazy_static! {
+    static ref IMG_SRC: Regex =
+        Regex::new(r#"(?i)<img\b[^>]*?\bsrc\s*=\s*["']([^"']+)["']"#).unwrap();
+}
+
+pub fn cache_dir() -> PathBuf {
+    env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .unwrap_or_else(env::temp_dir)
+        .join("gluon_news")
+        .join("images")
+}
+
+/// Named after a hash that stays the same between builds, unlike that of
+/// `DefaultHasher`, so cached images are found again after an update.
+fn cache_path(dir: &Path, url: &str) -> PathBuf {
+    let hash: String = Sha256::digest(url.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    let extension = Path::new(url.split(['?', '#']).next().unwrap_or_default())
+        .extension()
+        .and_then(|e| e.to_str())
+        .filter(|e| e.len() <= 4)
+        .unwrap_or("img");
+
+    dir.join(format!("{hash}.{extension}"))
+}
+
+/// A cached image is touched when used again, as the least recently used
+/// images are evicted first.
+async fn download(client: &Client, url: &str, path: &Path) -> Option<()> {
+    if path.exists() {
+        File::options()
+            .append(true)
+            .open(path)
+            .and_then(|file| file.set_modified(SystemTime::now()))
+            .ok();
+        return Some(());
+    }
+
+    let bytes = client
+        .get(url.replace("&amp;", "&"))
+        .header("User-Agent", "gluon_news")
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?
+        .bytes()
+        .await
+        .ok()?;
+
+    tokio::fs::write(path, bytes).await.ok()
+}
+
+/// Removes the least recently used images until the cache fits in
+/// `max_bytes`, never removing those in `keep`.
+fn evict(dir: &Path, max_bytes: u64, keep: &HashSet<&PathBuf>) {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<(PathBuf, u64, SystemTime)> = read_dir
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let metadata = e.metadata().ok()?;
+            Some((e.path(), metadata.len(), metadata.modified().ok()?))
+        })
+        .collect();
+    let mut total: u64 = files.iter().map(|f| f.1).sum();
+
+    files.sort_by_key(|f| f.2);
+
+    for (path, len, _) in files {
+        if total <= max_bytes {
+            break;
+        }
+        if keep.contains(&path) {
+            continue;
+        }
+        if fs::remove_file(path).is_ok() {
+            total -= len;
+        }
+    }
+}
+
+/// Routes the plain HTTP images through `proxy`, so they are not blocked as
+/// mixed content and the feed's server never sees the user's address.
+pub fn proxy_images(html: &str, proxy: &str) -> String {
+    IMG_SRC
+        .replace_all(html, |caps: &Captures| {
+            let src = &caps[1];
+            if !src.to_ascii_lowercase().starts_with("http://") {
+                return caps[0].to_owned();
+            }
+            let encoded: String =
+                url::form_urlencoded::byte_serialize(src.replace("&amp;", "&").as_bytes())
+                    .collect();
+
+            caps[0].replace(src, &format!("{proxy}{encoded}"))
+        })
+        .into_owned()
+}
+
+/// The sources of the images in the HTML, in order and without duplicates.
+pub fn image_sources(html: &str) -> Vec<String> {
+    let mut sources: Vec<String> = Vec::new();
+
+    for src in IMG_SRC.captures_iter(html).filter_map(|c| c.get(1)) {
+        let src = src.as_str().replace("&amp;", "&");
+        if !sources.contains(&src) {
+            sources.push(src);
+        }
+    }
+
+    sources
+}
+
+pub async fn download_images(client: &Client, html: &str, max_cache_size_mb: u64) -> String {
+    let dir = cache_dir();
+
+    if tokio::fs::create_dir_all(&dir).await.is_err() {
+        return html.to_owned();
+    }
+
+    let mut urls: Vec<&str> = IMG_SRC
+        .captures_iter(html)
+        .filter_map(|c| c.get(1))
+        .map(|m| m.as_str())
+        .filter(|url| url.starts_with("http"))
+        .collect();
+    urls.sort();
+    urls.dedup();
+
+    let downloads = join_all(urls.into_iter().map(|url| {
+        let path = cache_path(&dir, url);
+        async move { download(client, url, &path).await.map(|_| (url, path)) }
+    }))
+    .await;
+    let local: HashMap<&str, PathBuf> = downloads.into_iter().flatten().collect();
+
+    evict(
+        &dir,
+        max_cache_size_mb.saturating_mul(1024 * 1024),
+        &local.values().collect(),
+    );
+
+    // A bare path would resolve against the origin of the webview.
+    IMG_SRC
+        .replace_all(html, |caps: &Captures| {
+            match local
+                .get(&caps[1])
+                .and_then(|path| Url::from_file_path(path).ok())
+            {
+                Some(url) => caps[0].replace(&caps[1], url.as_str()),
+                None => caps[0].to_owned(),
+            }
+        })
+        .into_owned()
+}
@@ -0,0 +1,950 @@
+//! Feed fetching, settings and entry state shared by the Gluon News app and
+//! any other tool that wants them without pulling in the UI.
+
+pub mod archive;
+pub mod bookmarks;
+pub mod browser;
+pub mod bundles;
+pub mod charset;
+pub mod clustering;
+pub mod collections;
+pub mod commits;
+pub mod compat;
+pub mod compression;
+pub mod config;
+pub mod diff;
+pub mod digest;
+pub mod discovery;
+pub mod dns;
+pub mod dry_run;
+pub mod error;
+pub mod export;
+pub mod feed_cache;
+pub mod feedly;
+pub mod fetch_times;
+pub mod filters;
+pub mod focus_time;
+pub mod full_content;
+pub mod health;
+pub mod images;
+pub mod local_feeds;
+pub mod media;
+pub mod migration;
+pub mod newsletter;
+pub mod nlp;
+pub mod oauth;
+pub mod pdf;
+pub mod plugin;
+pub mod poll_history;
+pub mod position;
+pub mod preferences;
+pub mod profile;
+pub mod ranking;
+pub mod rate_limits;
+pub mod read_later;
+pub mod read_state;
+pub mod reading_goal;
+pub mod reading_hours;
+pub mod reading_speed;
+pub mod reminders;
+pub mod reputation;
+pub mod sanitizer;
+pub mod search_feeds;
+pub mod search_history;
+pub mod share;
+pub mod short_links;
+pub mod state;
+pub mod stats;
+pub mod streak;
+pub mod sync;
+pub mod tags;
+pub mod telemetry;
+pub mod text;
+pub mod translate;
+pub mod undo;
+pub mod update_checker;
+pub mod webfinger;
+pub mod websocket;
+
+use chrono::Utc;
+use clustering::Topic;
+use compat::{EntryExt, FeedExt};
+use config::{read_settings, MergedFeed, RankingMode, Settings};
+use diff::AlsoIn;
+use error::{FeedBatchError, FeedError};
+use feed_rs::{
+    model::Feed,
+    parser::{self, ParseFeedError, ParseFeedResult},
+};
+use fetch_times::FetchTimes;
+use futures::{future::join_all, stream};
+use lazy_static::lazy_static;
+use nlp::SmartRanker;
+use plugin::{EntryProcessor, ExtendedEntry};
+use poll_history::PollHistory;
+use rand::Rng;
+use reqwest::{
+    header::{
+        HeaderMap, HeaderName, HeaderValue, ACCEPT, ACCEPT_ENCODING, CACHE_CONTROL, CONTENT_LENGTH,
+        CONTENT_TYPE, COOKIE, PRAGMA, RETRY_AFTER,
+    },
+    Client, Proxy, RequestBuilder, Response, StatusCode,
+};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    io::{self, BufReader, Read},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use telemetry::{FetchTelemetry, Telemetry};
+use tokio::{
+    net::TcpStream,
+    sync::{mpsc::Sender, Semaphore},
+};
+use tokio_util::io::{StreamReader, SyncIoBridge};
+use tracing::Instrument;
+use url::Url;
+
+pub use feed_rs::model::Entry;
+
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(3600);
+const DEFAULT_RETRY_BASE_DELAY_MS: u64 = 500;
+const MAX_RETRY_DELAY_MS: u64 = 30_000;
+const MAX_RETRIES: u32 = 3;
+const DEFAULT_RATE_LIMIT_SECONDS: u64 = 60;
+const PROXY_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Responses at least this large, or of unknown size, are parsed as they arrive.
+const STREAMING_THRESHOLD_BYTES: u64 = 1024 * 1024;
+const HINT_PREFIX_BYTES: usize = 64 * 1024;
+
+lazy_static! {
+    static ref CONFIGURED_CLIENT: Result<Client, String> = build_client();
+    /// Never falls back to a direct connection or the system resolver: when
+    /// the proxy or resolver set up cannot be used, `client_error` says why
+    /// and using the client panics. The app checks before it starts.
+    pub static ref CLIENT: Client = match &*CONFIGURED_CLIENT {
+        Ok(client) => client.clone(),
+        Err(e) => panic!("{e}"),
+    };
+    pub static ref SETTINGS: Arc<Settings> = Arc::new(read_settings().unwrap_or_default());
+}
+
+pub enum FetchResult {
+    /// Feed title, entries, update hint and the feed URL as configured.
+    Success(String, Vec<Entry>, Option<Duration>, String),
+    Failure(String, FeedError),
+}
+
+pub struct News {
+    pub entries: Vec<(String, Entry)>,
+    pub failures: Vec<(String, FeedError)>,
+    pub reading_times: HashMap<String, u32>,
+    /// Full article content by entry ID, for entries with only an excerpt.
+    pub extracted: HashMap<String, String>,
+    /// How often each feed seems to update, by feed title.
+    pub poll_estimates: HashMap<String, Duration>,
+    /// Titles of the feeds marked as commit feeds.
+    pub commit_feeds: HashSet<String>,
+    /// The colour of each feed, by feed title.
+    pub feed_colors: HashMap<String, String>,
+    /// How many entries have each category, across all feeds.
+    pub category_counts: HashMap<String, usize>,
+    /// Ranking scores by entry ID, used to sort the entries in `Ranked` mode.
+    pub scores: HashMap<String, f64>,
+    /// Unread entries similar to what the user reads, with smart ranking on.
+    pub recommended: HashSet<String>,
+    /// Content scores by entry ID, for the entries that were kept.
+    pub content_scores: HashMap<String, f32>,
+    /// The entries grouped by inferred topic, largest topic first.
+    pub topics: Vec<Topic>,
+    /// The other feeds duplicates of each entry were folded from, by entry ID.
+    pub also_in: AlsoIn,
+    /// The title of each feed fetched, by its URL as configured.
+    pub feed_titles: HashMap<String, String>,
+    /// The links of every entry of each feed fetched, before any were
+    /// filtered, folded or cut, by the feed's URL as configured.
+    pub feed_links: HashMap<String, HashSet<String>>,
+    pub poll_interval: Duration,
+}
+
+impl News {
+    pub fn from_results(results: Vec<FetchResult>) -> Self {
+        let _span = tracing::debug_span!("process_entries", feeds = results.len()).entered();
+        let mut failures: Vec<(String, FeedError)> = Vec::new();
+        let mut reading_times: HashMap<String, u32> = HashMap::new();
+        let mut extracted: HashMap<String, String> = HashMap::new();
+        let mut poll_estimates: HashMap<String, Duration> = HashMap::new();
+        let mut commit_feeds: HashSet<String> = HashSet::new();
+        let mut feed_colors: HashMap<String, String> = HashMap::new();
+        let mut feed_titles: HashMap<String, String> = HashMap::new();
+        let mut feed_links: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut category_counts: HashMap<String, usize> = HashMap::new();
+        let mut content_scores: HashMap<String, f32> = HashMap::new();
+        let mut intervals: Vec<Duration> = Vec::new();
+        let processors = plugin::pipeline(&SETTINGS.processors);
+        let mut history = PollHistory::load();
+        let min_interval = Duration::from_secs(
+            SETTINGS
+                .min_poll_interval_seconds
+                .unwrap_or(poll_history::DEFAULT_MIN_POLL_INTERVAL_SECONDS),
+        );
+        let max_interval = Duration::from_secs(
+            SETTINGS
+                .max_poll_interval_seconds
+                .unwrap_or(poll_history::DEFAULT_MAX_POLL_INTERVAL_SECONDS),
+        );
+
+        // The feeds are recorded as they are collected, ahead of the pipeline
+        // running over their entries.
+        let feeds = results.into_iter().filter_map(|result| match result {
+            FetchResult::Success(feed_title, feed_entries, hint, url) => {
+                if SETTINGS.feed_options(&url).commit_feed {
+                    commit_feeds.insert(feed_title.clone());
+                }
+                feed_colors.insert(feed_title.clone(), SETTINGS.feed_color(&url));
+                let weight = SETTINGS.feed_options(&url).weight.unwrap_or(1.0);
+                feed_links.insert(
+                    url.clone(),
+                    feed_entries
+                        .iter()
+                        .filter_map(|e| e.best_link().map(String::from))
+                        .collect(),
+                );
+                feed_titles.insert(url, feed_title.clone());
+                history.record(&feed_title, &feed_entries);
+                let estimate = history.estimate(&feed_title, min_interval, max_interval);
+                if let Some(estimate) = estimate {
+                    poll_estimates.insert(feed_title.clone(), estimate);
+                }
+                intervals.extend(estimate.or(hint));
+                Some((feed_title, weight, feed_entries))
+            }
+            FetchResult::Failure(url, error) => {
+                failures.push((url, error));
+                None
+            }
+        });
+        let feeds: Vec<(String, Vec<Entry>)> =
+            if SETTINGS.feed_options.values().any(|o| o.weight.is_some()) {
+                let feeds: Vec<(String, f32, Vec<Entry>)> = feeds.collect();
+                let budget = SETTINGS
+                    .max_total_entries
+                    .unwrap_or_else(|| feeds.iter().map(|f| f.2.len()).sum());
+                apply_weights(feeds, budget)
+            } else {
+                feeds.map(|(title, _, entries)| (title, entries)).collect()
+            };
+        let mut entries: Vec<(String, Entry)> = Vec::new();
+        for (extended, content_score) in entry_pipeline(feeds.into_iter(), &processors) {
+            content_scores.insert(extended.entry.id.clone(), content_score);
+            if let Some(reading_time) = extended.reading_time {
+                reading_times.insert(extended.entry.id.clone(), reading_time);
+            }
+            if let Some(content) = extended.entry.best_link().and_then(full_content::cached) {
+                extracted.insert(extended.entry.id.clone(), content);
+            }
+            for category in extended.entry.category_names() {
+                *category_counts.entry(category).or_default() += 1;
+            }
+            entries.push((extended.feed_title, extended.entry));
+        }
+
+        let (mut entries, also_in) = diff::deduplicate(entries, SETTINGS.fuzzy_dedup_threshold);
+        sort_newest_first(&mut entries);
+        if let Some(max_total_entries) = SETTINGS.max_total_entries {
+            entries.truncate(max_total_entries);
+        }
+
+        let read = read_state::load();
+        let scores = ranking::scores(&entries, &read, SETTINGS.ranking_weights, Utc::now());
+        let recommended = if SETTINGS.enable_smart_ranking {
+            let ranker = SmartRanker::new(&entries, &read);
+            entries
+                .iter()
+                .filter(|e| {
+                    !read.contains(&e.1.id) && ranker.score(&e.1) >= nlp::RECOMMENDED_SIMILARITY
+                })
+                .map(|e| e.1.id.clone())
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        let topics = clustering::topics(
+            &entries,
+            SETTINGS
+                .topic_cluster_count
+                .unwrap_or(clustering::DEFAULT_TOPIC_CLUSTER_COUNT),
+        );
+        if SETTINGS.ranking == RankingMode::Ranked {
+            entries.sort_by(|a, b| scores[&b.1.id].total_cmp(&scores[&a.1.id]));
+        }
+
+        if let Err(e) = history.save() {
+            tracing::warn!("could not save poll history: {e}");
+        }
+
+        let poll_interval = match SETTINGS.interval_seconds {
+            Some(interval_seconds) => Duration::from_secs(interval_seconds),
+            None => intervals.into_iter().min().unwrap_or(DEFAULT_POLL_INTERVAL),
+        };
+
+        Self {
+            entries,
+            failures,
+            reading_times,
+            extracted,
+            poll_estimates,
+            commit_feeds,
+            feed_colors,
+            category_counts,
+            scores,
+            recommended,
+            content_scores,
+            topics,
+            also_in,
+            feed_titles,
+            feed_links,
+            poll_interval,
+        }
+    }
+
+    /// Lists the entries of the sources of each merged feed under its title,
+    /// as if they came from a single feed.
+    pub fn apply_merges(&mut self, merged_feeds: &[MergedFeed]) {
+        for merged in merged_feeds {
+            let mut source_titles: Vec<String> = Vec::new();
+            for url in &merged.sources {
+                if let Some(title) = self.feed_titles.get_mut(url) {
+                    source_titles.push(std::mem::replace(title, merged.title.clone()));
+                }
+            }
+            if source_titles.is_empty() {
+                continue;
+            }
+
+            // The first source fetched gives the merged feed its colour.
+            let color = source_titles
+                .iter()
+                .find_map(|t| self.feed_colors.get(t))
+                .cloned();
+            if let Some(color) = color {
+                self.feed_colors.insert(merged.title.clone(), color);
+            }
+            let estimate = source_titles
+                .iter()
+                .filter_map(|t| self.poll_estimates.get(t))
+                .min()
+                .copied();
+            if let Some(estimate) = estimate {
+                self.poll_estimates.insert(merged.title.clone(), estimate);
+            }
+            if source_titles.iter().any(|t| self.commit_feeds.contains(t)) {
+                self.commit_feeds.insert(merged.title.clone());
+            }
+            for (feed_title, entry) in &mut self.entries {
+                if source_titles.contains(feed_title) {
+                    feed_title.clone_from(&merged.title);
+                }
+                if let Some(also_in) = self.also_in.get_mut(&entry.id) {
+                    for title in also_in.iter_mut() {
+                        if source_titles.contains(title) {
+                            title.clone_from(&merged.title);
+                        }
+                    }
+                    also_in.retain(|title| title != feed_title);
+                    also_in.dedup();
+                }
+            }
+        }
+        self.also_in.retain(|_, feeds| !feeds.is_empty());
+    }
+}
+
+/// Keeps the newest entries of each feed up to its share of the budget, that
+/// is its weight over the sum of the weights. A busy feed then no longer
+/// swamps the list, while a feed with fewer entries than its share keeps them
+/// all.
+pub fn apply_weights(
+    feed_entries: Vec<(String, f32, Vec<Entry>)>,
+    budget: usize,
+) -> Vec<(String, Vec<Entry>)> {
+    let total_weight: f32 = feed_entries.iter().map(|f| f.1.max(0.0)).sum();
+
+    feed_entries
+        .into_iter()
+        .map(|(feed_title, weight, mut entries)| {
+            let share = if total_weight > 0.0 {
+                (weight.max(0.0) / total_weight * budget as f32) as usize
+            } else {
+                0
+            };
+            entries.sort_by_key(|e| Reverse(e.best_date()));
+            entries.truncate(share);
+            (feed_title, entries)
+        })
+        .collect()
+}
+
+/// Runs the entry processors on the entries of each feed, with the content
+/// score of each entry, leaving out those below min_content_score. Entries are
+/// processed as the iterator is consumed, so nothing is collected along the
+/// way.
+fn entry_pipeline<'a>(
+    feeds: impl Iterator<Item = (String, Vec<Entry>)> + 'a,
+    processors: &'a [Box<dyn EntryProcessor>],
+) -> impl Iterator<Item = (ExtendedEntry, f32)> + 'a {
+    feeds
+        .flat_map(|(feed_title, entries)| {
+            entries.into_iter().map(move |entry| ExtendedEntry {
+                feed_title: feed_title.clone(),
+                entry,
+                reading_time: None,
+            })
+        })
+        .map(|mut extended| {
+            plugin::run(processors, &mut extended);
+            extended
+        })
+        .filter_map(|extended| {
+            let content_score = filters::content_score(&extended.entry);
+            (!SETTINGS
+                .min_content_score
+                .is_some_and(|min| content_score < min))
+            .then_some((extended, content_score))
+        })
+}
+
+/// Sorts entries by publication date, or by the last update for entries
+/// without one, newest first, with undated entries last. Entries with the
+/// same date keep their order.
+pub fn sort_newest_first(entries: &mut [(String, Entry)]) {
+    entries.sort_by(|a, b| {
+        b.1.best_date()
+            .unwrap_or_default()
+            .cmp(&a.1.best_date().unwrap_or_default())
+    });
+}
+
+/// Why the client could not be built with the configured proxy and resolver,
+/// if it could not.
+pub fn client_error() -> Option<&'static str> {
+    CONFIGURED_CLIENT.as_ref().err().map(String::as_str)
+}
+
+fn build_client() -> Result<Client, String> {
+    let mut builder = Client::builder()
+        .gzip(SETTINGS.accepts_encoding("gzip"))
+        .deflate(SETTINGS.accepts_encoding("deflate"))
+        .brotli(SETTINGS.accepts_encoding("br"));
+
+    if let Some(proxy) = &SETTINGS.tor_socks_proxy {
+        let proxy = Proxy::all(proxy).map_err(|e| format!("tor_socks_proxy {proxy}: {e}"))?;
+        builder = builder.proxy(proxy);
+    }
+    // DNS-over-HTTPS wins when both are set, as validation warns.
+    let resolver = match (&SETTINGS.dns_over_https, &SETTINGS.dns_over_tls) {
+        (Some(url), _) => Some(("dns_over_https", url, dns::doh_resolver(url))),
+        (None, Some(server)) => Some(("dns_over_tls", server, dns::dot_resolver(server))),
+        (None, None) => None,
+    };
+    match resolver {
+        Some((_, _, Ok(resolver))) => builder = builder.dns_resolver(Arc::new(resolver)),
+        Some((setting, value, Err(e))) => return Err(format!("{setting} {value}: {e}")),
+        None => {}
+    }
+
+    builder
+        .build()
+        .map_err(|e| format!("could not set up the HTTP client: {e}"))
+}
+
+/// Tor runs outside the app, so warn early when nothing listens on the proxy address.
+pub async fn check_proxy_reachable(proxy: &str) {
+    let address = Url::parse(proxy).ok().and_then(|url| {
+        Some(format!(
+            "{}:{}",
+            url.host_str()?,
+            url.port().unwrap_or(9050)
+        ))
+    });
+    let Some(address) = address else {
+        return;
+    };
+
+    match tokio::time::timeout(PROXY_CHECK_TIMEOUT, TcpStream::connect(&address)).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => {
+            tracing::warn!("tor_socks_proxy {address} is not reachable, is Tor running? {e}")
+        }
+        Err(_) => tracing::warn!("tor_socks_proxy {address} did not answer, is Tor running?"),
+    }
+}
+
+fn tag_text<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{tag}>");
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find("</")?;
+
+    Some(xml[start..end].trim())
+}
+
+fn update_hint(xml: &str) -> Option<Duration> {
+    let period = match tag_text(xml, "sy:updatePeriod")? {
+        "hourly" => 3600,
+        "daily" => 86400,
+        "weekly" => 604800,
+        "monthly" => 2592000,
+        "yearly" => 31536000,
+        _ => return None,
+    };
+    let frequency = tag_text(xml, "sy:updateFrequency")
+        .and_then(|f| f.parse::<u64>().ok())
+        .filter(|f| *f > 0)
+        .unwrap_or(1);
+
+    Some(Duration::from_secs(period / frequency))
+}
+
+fn backoff_delay(attempt: u32, base_ms: u64) -> Duration {
+    let ceiling = base_ms
+        .saturating_mul(2u64.saturating_pow(attempt))
+        .min(MAX_RETRY_DELAY_MS);
+
+    if ceiling == 0 {
+        return Duration::ZERO;
+    }
+
+    Duration::from_millis(rand::thread_rng().gen_range(0..ceiling))
+}
+
+fn retry_after_seconds(response: &Response) -> Option<u64> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+
+    rate_limits::parse_retry_after(value, Utc::now())
+}
+
+/// A feed response, kept whole when small and passed to the parser as it
+/// arrives when large or of unknown size.
+enum FeedBody {
+    Buffered(String),
+    Streamed(Response),
+}
+
+/// Keeps a copy of the first bytes read through it, for the update hints that
+/// live in the feed header.
+struct PrefixReader<R> {
+    inner: R,
+    prefix: Vec<u8>,
+    read_bytes: u64,
+}
+
+impl<R: Read> Read for PrefixReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.read_bytes += read as u64;
+        let kept = read.min(HINT_PREFIX_BYTES.saturating_sub(self.prefix.len()));
+        self.prefix.extend_from_slice(&buf[..kept]);
+
+        Ok(read)
+    }
+}
+
+/// A request for the feed with the headers its options call for.
+async fn feed_request(client: &Client, url: &str) -> Result<RequestBuilder, FeedError> {
+    let options = SETTINGS.feed_options(url);
+    let mut request = client
+        .get(url)
+        .header("User-Agent", "gluon_news")
+        .header(ACCEPT_ENCODING, SETTINGS.accept_encoding_header())
+        .header(
+            ACCEPT,
+            options.preferred_format.unwrap_or_default().accept(),
+        );
+    if let Some(auth) = &options.auth {
+        request = request.bearer_auth(oauth::access_token(url, auth).await?);
+    }
+    if let Some(cookies) = options
+        .cookies
+        .as_ref()
+        .filter(|cookies| !cookies.is_empty())
+    {
+        tracing::debug!("sending {} cookie(s) to {url}", cookies.len());
+        let cookie = cookies
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join("; ");
+        request = request.header(COOKIE, cookie);
+    }
+    if let Some(custom_headers) = &options.custom_headers {
+        // Unlike `header`, `headers` replaces the values already set.
+        let headers: HeaderMap = custom_headers
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((
+                    HeaderName::from_bytes(name.as_bytes()).ok()?,
+                    HeaderValue::from_str(value).ok()?,
+                ))
+            })
+            .collect();
+        request = request.headers(headers);
+    }
+
+    Ok(request)
+}
+
+fn check_status(response: Response) -> Result<Response, FeedError> {
+    match response.status() {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(FeedError::AuthError),
+        StatusCode::TOO_MANY_REQUESTS => Err(FeedError::RateLimited(
+            retry_after_seconds(&response).unwrap_or(DEFAULT_RATE_LIMIT_SECONDS),
+        )),
+        _ => Ok(response.error_for_status()?),
+    }
+}
+
+async fn decode_body(response: Response) -> Result<String, FeedError> {
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from);
+    let bytes = response.bytes().await?;
+
+    Ok(charset::decode_feed(&bytes, content_type.as_deref()))
+}
+
+/// Small feeds are read whole, as parsing them while they download gains
+/// nothing. Their size is taken from the Content-Length header, as reqwest
+/// stops reporting it once it decompresses the body, or else from the size of
+/// the feed's last fetch.
+async fn request_feed(url: &str, last_bytes: Option<u64>) -> Result<FeedBody, FeedError> {
+    let response = check_status(feed_request(&CLIENT, url).await?.send().await?)?;
+    let length = response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .or(last_bytes);
+
+    match length {
+        Some(length) if length < STREAMING_THRESHOLD_BYTES => {
+            Ok(FeedBody::Buffered(decode_body(response).await?))
+        }
+        _ => Ok(FeedBody::Streamed(response)),
+    }
+}
+
+/// Fetches a feed past any cache on the way, for when a feed keeps serving a
+/// stale copy. Feeds are never requested conditionally, so there are no
+/// If-None-Match or If-Modified-Since headers to leave out; caches in between
+/// are told to fetch the feed again instead of serving what they stored,
+/// which also updates their copy for the fetches after. A rate limit recorded
+/// for the feed is lifted.
+pub async fn force_fetch_feed(url: &str, client: &Client) -> Result<Feed, FeedError> {
+    let request = feed_request(client, url)
+        .await?
+        .header(CACHE_CONTROL, "no-cache")
+        .header(PRAGMA, "no-cache");
+    let text = decode_body(check_status(request.send().await?)?).await?;
+
+    let mut rate_limits = rate_limits::load();
+    if rate_limits.remove(url).is_some() {
+        if let Err(e) = rate_limits::save(&rate_limits) {
+            tracing::warn!("could not save rate limits: {e}");
+        }
+    }
+
+    parser::parse(text.as_bytes()).map_err(|e| FeedError::ParseError(e.to_string()))
+}
+
+async fn fetch_feed(url: &str, last_bytes: Option<u64>) -> Result<FeedBody, FeedError> {
+    if let Some(path) = local_feeds::local_path(url) {
+        let bytes = tokio::fs::read(path).await?;
+        return Ok(FeedBody::Buffered(charset::decode_feed(&bytes, None)));
+    }
+    let base_ms = SETTINGS
+        .retry_base_delay_ms
+        .unwrap_or(DEFAULT_RETRY_BASE_DELAY_MS);
+    let mut attempt = 0;
+
+    loop {
+        match request_feed(url, last_bytes).await {
+            Err(e) if attempt < MAX_RETRIES && e.is_retryable() => {
+                tokio::time::sleep(backoff_delay(attempt, base_ms)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Fetches and parses a feed, along with the telemetry of the fetch when the
+/// feed could be parsed. Streamed feeds are parsed while they download, so
+/// their parse time includes the download of everything past the headers.
+async fn fetch_one(url: String, last_bytes: Option<u64>) -> (FetchResult, Option<FetchTelemetry>) {
+    if let Some(e) = client_error().filter(|_| !local_feeds::is_local(&url)) {
+        return (FetchResult::Failure(url, FeedError::Client(e.into())), None);
+    }
+    let feed_url = if webfinger::is_acct(&url) {
+        match webfinger::resolve_webfinger(&url).await {
+            Ok(feed_url) => feed_url,
+            Err(e) => return (FetchResult::Failure(url, e.into()), None),
+        }
+    } else {
+        url.clone()
+    };
+    let fetch_start = Instant::now();
+    let body = fetch_feed(&feed_url, last_bytes).await;
+    let fetch_time = fetch_start.elapsed();
+    let parse_start = Instant::now();
+    let parsed = match body {
+        Ok(FeedBody::Buffered(text)) => {
+            parser::parse(text.as_bytes()).map(|feed| (feed, update_hint(&text), text.len() as u64))
+        }
+        Ok(FeedBody::Streamed(response)) => parse_streamed(response).await,
+        Err(e) => return (FetchResult::Failure(url, e), None),
+    };
+    let parse_time = parse_start.elapsed();
+
+    match parsed {
+        Ok((feed, hint, response_bytes)) => {
+            let telemetry = FetchTelemetry {
+                url: url.clone(),
+                response_bytes,
+                entry_count: feed.entries.len() as u32,
+                parse_time_ms: parse_time.as_millis() as u64,
+                fetch_time_ms: fetch_time.as_millis() as u64,
+            };
+            let feed_title = SETTINGS
+                .feed_options(&url)
+                .title
+                .clone()
+                .unwrap_or_else(|| feed.best_title());
+            let hint = hint.or(feed.ttl.map(|ttl| Duration::from_secs(ttl as u64 * 60)));
+            let mut entries = feed.entries;
+            if SETTINGS.expand_short_links {
+                short_links::expand_links(&mut entries).await;
+            }
+            if SETTINGS.fetch_full_content {
+                full_content::extract_missing(&entries).await;
+            }
+
+            (
+                FetchResult::Success(feed_title, entries, hint, url),
+                Some(telemetry),
+            )
+        }
+        Err(e) => (
+            FetchResult::Failure(url, FeedError::ParseError(e.to_string())),
+            None,
+        ),
+    }
+}
+
+/// Parses the body chunk by chunk on a blocking thread, so a large feed is
+/// never held in memory as a whole. The size of the body is returned with the
+/// feed.
+async fn parse_streamed(response: Response) -> ParseFeedResult<(Feed, Option<Duration>, u64)> {
+    let stream = stream::unfold(Some(response), |response| async move {
+        let mut response = response?;
+        match response.chunk().await {
+            Ok(Some(chunk)) => Some((Ok(chunk), Some(response))),
+            Ok(None) => None,
+            Err(e) => Some((Err(io::Error::other(e)), None)),
+        }
+    });
+    let reader = SyncIoBridge::new(StreamReader::new(Box::pin(stream)));
+
+    tokio::task::spawn_blocking(move || {
+        let mut reader = PrefixReader {
+            inner: BufReader::new(reader),
+            prefix: Vec::new(),
+            read_bytes: 0,
+        };
+        let feed = parser::parse(&mut reader)?;
+
+        Ok((
+            feed,
+            update_hint(&String::from_utf8_lossy(&reader.prefix)),
+            reader.read_bytes,
+        ))
+    })
+    .await
+    .unwrap_or_else(|e| Err(ParseFeedError::IoError(io::Error::other(e))))
+}
+
+/// The host a feed is fetched from, used to group feeds sharing a server.
+fn host_of(url: &str) -> String {
+    if webfinger::is_acct(url) {
+        return url.rsplit('@').next().unwrap_or(url).to_ascii_lowercase();
+    }
+
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .unwrap_or_else(|| url.to_owned())
+}
+
+pub async fn fetch_news(urls: &[&str]) -> Result<Vec<FetchResult>, FeedBatchError> {
+    fetch_news_with_progress(urls, None, None).await
+}
+
+/// How far a refresh is, sent each time a feed is done.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FetchProgress {
+    pub completed: usize,
+    pub total: usize,
+    /// The URL of the feed done last, as configured.
+    pub latest_feed: String,
+}
+
+/// Counts the feed as done and reports it, when progress is reported.
+async fn report_progress(
+    progress: Option<Sender<FetchProgress>>,
+    completed: &AtomicUsize,
+    total: usize,
+    url: String,
+) {
+    if let Some(progress) = progress {
+        let completed = completed.fetch_add(1, Ordering::SeqCst) + 1;
+        let _ = progress
+            .send(FetchProgress {
+                completed,
+                total,
+                latest_feed: url,
+            })
+            .await;
+    }
+}
+
+/// Like `fetch_news`, sending a `FetchProgress` on `progress_tx` as soon as
+/// each feed is done, and the feed's entries on `entries_tx` before that. The
+/// entries are sent as parsed, before any filtering or deduplication.
+pub async fn fetch_news_with_progress(
+    urls: &[&str],
+    progress_tx: Option<Sender<FetchProgress>>,
+    entries_tx: Option<Sender<(String, Entry)>>,
+) -> Result<Vec<FetchResult>, FeedBatchError> {
+    if urls.is_empty() {
+        return Err(FeedBatchError::NoFeeds);
+    }
+
+    let completed = Arc::new(AtomicUsize::new(0));
+    let total = urls.len();
+
+    let now = Utc::now();
+    let mut rate_limits = rate_limits::load();
+    rate_limits.retain(|_, until| *until > now);
+    let mut telemetry = Telemetry::load();
+
+    let mut semaphores: HashMap<String, Arc<Semaphore>> = HashMap::new();
+    if let Some(max_connections) = SETTINGS.max_connections_per_host {
+        for url in urls {
+            semaphores
+                .entry(host_of(url))
+                .or_insert_with(|| Arc::new(Semaphore::new(max_connections.max(1))));
+        }
+    }
+
+    let stagger = Duration::from_millis(SETTINGS.fetch_stagger_ms.unwrap_or_default());
+    let mut started = 0;
+    let tasks: Vec<_> = urls
+        .iter()
+        .map(|url| {
+            let url = url.to_string();
+            let semaphore = semaphores.get(&host_of(&url)).cloned();
+            let last_bytes = telemetry.get(&url).map(|t| t.response_bytes);
+            let progress = progress_tx.clone();
+            let entries = entries_tx.clone();
+            let completed = completed.clone();
+            match rate_limits.get(&url) {
+                Some(until) => {
+                    let seconds = (*until - now).num_seconds() as u64;
+                    tokio::task::spawn(async move {
+                        report_progress(progress, &completed, total, url.clone()).await;
+                        (
+                            FetchResult::Failure(url, FeedError::RateLimited(seconds)),
+                            None,
+                            None,
+                        )
+                    })
+                }
+                None => {
+                    let delay = stagger * started;
+                    started += 1;
+                    tokio::task::spawn(async move {
+                        tokio::time::sleep(delay).await;
+                        let _permit = match &semaphore {
+                            Some(semaphore) => semaphore.acquire().await.ok(),
+                            None => None,
+                        };
+                        let start = Instant::now();
+                        let span = tracing::debug_span!("fetch_feed", url = %url);
+                        let (result, telemetry) =
+                            fetch_one(url.clone(), last_bytes).instrument(span).await;
+                        if let (Some(entries), FetchResult::Success(feed_title, feed_entries, ..)) =
+                            (entries, &result)
+                        {
+                            for entry in feed_entries {
+                                let _ = entries.send((feed_title.clone(), entry.clone())).await;
+                            }
+                        }
+                        report_progress(progress, &completed, total, url).await;
+                        (result, Some(start.elapsed()), telemetry)
+                    })
+                }
+            }
+        })
+        .collect();
+    let timed = join_all(tasks)
+        .await
+        .into_iter()
+        .map(|r| Ok(r?))
+        .collect::<Result<Vec<_>, FeedBatchError>>()?;
+
+    let mut fetch_times = FetchTimes::load();
+    let mut results = Vec::with_capacity(timed.len());
+    for (url, (result, elapsed, fetch_telemetry)) in urls.iter().zip(timed) {
+        if let Some(elapsed) = elapsed {
+            fetch_times.record(url, elapsed);
+        }
+        if let Some(fetch_telemetry) = fetch_telemetry {
+            telemetry.record(fetch_telemetry);
+        }
+        results.push(result);
+    }
+    if let Err(e) = fetch_times.save() {
+        tracing::warn!("could not save fetch times: {e}");
+    }
+    if let Err(e) = telemetry.save() {
+        tracing::warn!("could not save fetch telemetry: {e}");
+    }
+
+    for result in &results {
+        if let FetchResult::Failure(url, FeedError::RateLimited(seconds)) = result {
+            rate_limits
+                .entry(url.clone())
+                .or_insert(now + chrono::Duration::seconds(*seconds as i64));
+        }
+    }
+    if let Err(e) = rate_limits::save(&rate_limits) {
+        tracing::warn!("could not save rate limits: {e}");
+    }
+    if SETTINGS.expand_short_links {
+        if let Err(e) = short_links::save() {
+            tracing::warn!("could not save expanded links: {e}");
+        }
+    }
+    if SETTINGS.fetch_full_content {
+        if let Err(e) = full_content::save() {
+            tracing::warn!("could not save extracted content: {e}");
+        }
+    }
+
+    Ok(results)
+}
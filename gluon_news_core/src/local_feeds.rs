@@ -0,0 +1,19 @@
+//! Feeds read from the local filesystem, such as ones generated by a script,
+//! given as `file://` URLs or absolute paths.
+
+use std::path::{Path, PathBuf};
+use url::Url;
+
+pub fn is_local(feed: &str) -> bool {
+    feed.starts_with("file:") || Path::new(feed).is_absolute()
+}
+
+/// The path of a local feed, or None when the feed is not local or is a
+/// `file:` URL that does not name a path.
+pub fn local_path(feed: &str) -> Option<PathBuf> {
+    if feed.starts_with("file:") {
+        Url::parse(feed).ok()?.to_file_path().ok()
+    } else {
+        Some(PathBuf::from(feed)).filter(|path| path.is_absolute())
+    }
+}
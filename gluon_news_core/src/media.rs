@@ -0,0 +1,126 @@
+//! Audio and video enclosures, for podcast and video feeds, and how far each
+//! has been played.
+
+use crate::{images, state, Entry};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    time::Duration,
+};
+
+const PLAYBACK_PROGRESS_FILE: &str = "playback_progress.json";
+const MEDIA_EXTENSIONS: [&str; 8] = ["mp3", "m4a", "ogg", "opus", "wav", "mp4", "webm", "mov"];
+const VIDEO_EXTENSIONS: [&str; 3] = ["mp4", "webm", "mov"];
+
+#[derive(Clone, PartialEq)]
+pub struct MediaItem {
+    pub url: String,
+    pub is_video: bool,
+    pub duration: Option<Duration>,
+    pub thumbnail: Option<String>,
+}
+
+fn extension(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+
+    path.rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+/// The first audio or video enclosure of the entry, going by its MIME type or,
+/// when the feed gives none, the extension of its URL.
+pub fn media_item(entry: &Entry) -> Option<MediaItem> {
+    for object in &entry.media {
+        for content in &object.content {
+            let Some(url) = &content.url else {
+                continue;
+            };
+            let kind = content.content_type.as_ref().map(|mime| mime.type_());
+            let is_video = match kind {
+                Some(kind) if kind == "video" => true,
+                Some(kind) if kind == "audio" => false,
+                Some(_) => continue,
+                None if MEDIA_EXTENSIONS.contains(&extension(url.as_str()).as_str()) => {
+                    VIDEO_EXTENSIONS.contains(&extension(url.as_str()).as_str())
+                }
+                None => continue,
+            };
+            let thumbnail = object
+                .thumbnails
+                .first()
+                .map(|t| t.image.uri.clone())
+                .or_else(|| {
+                    let summary = entry.summary.as_ref()?;
+                    images::image_sources(&summary.content).into_iter().next()
+                });
+
+            return Some(MediaItem {
+                url: url.to_string(),
+                is_video,
+                duration: content.duration.or(object.duration),
+                thumbnail,
+            });
+        }
+    }
+
+    None
+}
+
+/// The feeds where more than half of the entries have media.
+pub fn media_feeds(entries: &[(String, Entry)]) -> HashSet<String> {
+    let mut counts: HashMap<&String, (usize, usize)> = HashMap::new();
+    for (feed_title, entry) in entries {
+        let (total, with_media) = counts.entry(feed_title).or_default();
+        *total += 1;
+        if media_item(entry).is_some() {
+            *with_media += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .filter(|(_, (total, with_media))| with_media * 2 > *total)
+        .map(|(feed_title, _)| feed_title.clone())
+        .collect()
+}
+
+/// Formats a duration as "h:mm:ss", or "m:ss" under an hour.
+pub fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.max(0.0) as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// The seconds played of each entry's media, by entry ID. They are kept in
+/// playback_progress.json like the rest of the state in the profile, as there
+/// is no SQLite database to add a table to.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct PlaybackProgress {
+    positions: HashMap<String, f64>,
+}
+
+impl PlaybackProgress {
+    pub fn load() -> Self {
+        state::load(PLAYBACK_PROGRESS_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        state::save(PLAYBACK_PROGRESS_FILE, self)
+    }
+
+    pub fn get(&self, entry_id: &str) -> f64 {
+        self.positions.get(entry_id).copied().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, entry_id: &str, seconds: f64) {
+        self.positions.insert(entry_id.to_owned(), seconds);
+    }
+}
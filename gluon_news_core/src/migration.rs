@@ -0,0 +1,35 @@
+//! Upgrades settings.json files written for older versions of the settings
+//! schema, so changes to `Settings` do not break them.
+
+use serde_json::Value;
+
+/// The settings schema version this build reads and writes.
+pub const CURRENT_VERSION: u32 = 1;
+
+/// The migration from version `i` to `i + 1` is at index `i`. Files without a
+/// version predate versioning and count as version 0.
+const MIGRATIONS: [fn(Value) -> Value; CURRENT_VERSION as usize] = [
+    // Version 1 only adds the version field, set after the migrations are run.
+    |settings| settings,
+];
+
+/// The schema version settings.json was written for.
+pub fn version(settings: &Value) -> u32 {
+    settings
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(0, |version| version as u32)
+}
+
+/// Applies every migration after `from_version` in order, and stamps the
+/// settings with the current version.
+pub fn migrate_settings(mut value: Value, from_version: u32) -> Value {
+    for migration in MIGRATIONS.iter().skip(from_version as usize) {
+        value = migration(value);
+    }
+    if let Some(settings) = value.as_object_mut() {
+        settings.insert(String::from("version"), Value::from(CURRENT_VERSION));
+    }
+
+    value
+}
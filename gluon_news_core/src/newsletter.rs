@@ -0,0 +1,104 @@
+//! Renders the newest entries of chosen feeds as a newsletter, a single HTML
+//! page with its styles inline so it can be shared as is.
+
+use crate::{compat::EntryExt, config::NewsletterConfig, oauth, profile, text, SETTINGS};
+use chrono_tz::Tz;
+use feed_rs::model::Entry;
+use serde::Serialize;
+use std::{
+    error::Error,
+    fs::{canonicalize, write},
+    path::PathBuf,
+    str::FromStr,
+};
+use tera::{Context, Tera};
+
+const NEWSLETTER_FILE: &str = "newsletter.html";
+const SUMMARY_LENGTH: usize = 400;
+const DATE_FORMAT: &str = "%d %B %Y";
+const TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{{ title }}</title>
+<style>
+body { max-width: 40em; margin: 2em auto; padding: 0 1em; font-family: Georgia, serif; line-height: 1.5; color: #222222; }
+h1 { border-bottom: 2px solid #222222; }
+.intro, .footer { font-style: italic; }
+.entry { margin: 2em 0; }
+.entry h2 { margin-bottom: 0; font-size: 1.3em; }
+.entry a { color: #1a5f9e; text-decoration: none; }
+.meta { color: #777777; font-size: 0.9em; }
+.footer { border-top: 1px solid #cccccc; padding-top: 1em; color: #777777; }
+</style>
+</head>
+<body>
+<h1>{{ title }}</h1>
+<p class="intro">{{ intro_text }}</p>
+{% for entry in entries %}
+<div class="entry">
+<h2>{% if entry.link %}<a href="{{ entry.link }}">{{ entry.title }}</a>{% else %}{{ entry.title }}{% endif %}</h2>
+<div class="meta">{{ entry.feed }}{% if entry.published %} · {{ entry.published }}{% endif %}</div>
+<p>{{ entry.summary }}</p>
+</div>
+{% endfor %}
+<p class="footer">{{ footer_text }}</p>
+</body>
+</html>
+"#;
+
+#[derive(Serialize)]
+struct NewsletterEntry {
+    feed: String,
+    title: String,
+    link: Option<String>,
+    published: Option<String>,
+    summary: String,
+}
+
+/// The newsletter for the first entries of the included feeds, in the order
+/// given. All feeds are included when none are listed.
+pub fn render(
+    config: &NewsletterConfig,
+    entries: &[(String, Entry)],
+) -> Result<String, tera::Error> {
+    let time_zone = Tz::from_str(&SETTINGS.time_zone).unwrap_or(Tz::UTC);
+    let newsletter_entries: Vec<NewsletterEntry> = entries
+        .iter()
+        .filter(|e| config.include_feeds.is_empty() || config.include_feeds.contains(&e.0))
+        .take(config.max_entries)
+        .map(|(feed, entry)| NewsletterEntry {
+            feed: feed.clone(),
+            title: entry.best_title(),
+            link: entry.best_link().map(str::to_owned),
+            published: entry.best_date().map(|date| {
+                date.with_timezone(&time_zone)
+                    .format(DATE_FORMAT)
+                    .to_string()
+            }),
+            summary: text::truncate(&text::strip_html(&entry.best_summary()), SUMMARY_LENGTH),
+        })
+        .collect();
+    let mut context = Context::new();
+    context.insert("title", &config.title);
+    context.insert("intro_text", &config.intro_text);
+    context.insert("footer_text", &config.footer_text);
+    context.insert("entries", &newsletter_entries);
+
+    Tera::one_off(TEMPLATE, &context, true)
+}
+
+/// Writes the newsletter to `newsletter.html` in the profile and opens it in
+/// the browser, returning where it was saved.
+pub fn generate(
+    config: &NewsletterConfig,
+    entries: &[(String, Entry)],
+) -> Result<PathBuf, Box<dyn Error>> {
+    let path = profile::path(NEWSLETTER_FILE);
+    write(&path, render(config, entries)?)?;
+    // The profile path may be relative, which browsers would not resolve.
+    let path = canonicalize(path)?;
+    oauth::open_in_browser(&path.to_string_lossy())?;
+
+    Ok(path)
+}
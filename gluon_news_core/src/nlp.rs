@@ -0,0 +1,142 @@
+//! Recommends entries that read like the ones the user has read, by comparing
+//! TF-IDF term vectors.
+
+use crate::{compat::EntryExt, text};
+use feed_rs::model::Entry;
+use std::collections::{HashMap, HashSet};
+
+/// Cosine similarity to the reading profile above which an entry is recommended.
+pub const RECOMMENDED_SIMILARITY: f32 = 0.2;
+const MIN_TERM_CHARS: usize = 3;
+const STOP_WORDS: [&str; 40] = [
+    "about", "after", "all", "also", "and", "are", "but", "can", "for", "from", "had", "has",
+    "have", "her", "his", "how", "into", "its", "more", "new", "not", "now", "one", "our", "out",
+    "she", "than", "that", "the", "their", "them", "they", "this", "was", "were", "what", "when",
+    "which", "who", "with",
+];
+
+type TermVector = HashMap<String, f32>;
+
+pub(crate) fn terms(entry: &Entry) -> Vec<String> {
+    let text = text::strip_html(&format!("{} {}", entry.best_title(), entry.best_summary()));
+
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|t| t.chars().count() >= MIN_TERM_CHARS && !STOP_WORDS.contains(&t.as_str()))
+        .collect()
+}
+
+fn norm(vector: &TermVector) -> f32 {
+    vector.values().map(|w| w * w).sum::<f32>().sqrt()
+}
+
+fn cosine(a: &TermVector, b: &TermVector) -> f32 {
+    let (norm_a, norm_b) = (norm(a), norm(b));
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+
+    let dot: f32 = a
+        .iter()
+        .filter_map(|(term, w)| b.get(term).map(|v| w * v))
+        .sum();
+
+    dot / (norm_a * norm_b)
+}
+
+/// A TF-IDF model of the known entries, with the user's reading profile as
+/// the centroid of the vectors of the entries they read.
+pub struct SmartRanker {
+    idf: HashMap<String, f32>,
+    profile: TermVector,
+}
+
+impl SmartRanker {
+    pub fn new(entries: &[(String, Entry)], read: &HashSet<String>) -> Self {
+        let documents: Vec<(&Entry, Vec<String>)> =
+            entries.iter().map(|(_, e)| (e, terms(e))).collect();
+        let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+
+        for (_, terms) in &documents {
+            let unique: HashSet<&str> = terms.iter().map(String::as_str).collect();
+            for term in unique {
+                *document_frequency.entry(term).or_default() += 1;
+            }
+        }
+
+        let total = documents.len() as f32;
+        let mut ranker = Self {
+            idf: document_frequency
+                .into_iter()
+                .map(|(term, df)| (term.to_owned(), (total / df as f32).ln() + 1.0))
+                .collect(),
+            profile: TermVector::new(),
+        };
+        let read_vectors: Vec<TermVector> = documents
+            .iter()
+            .filter(|(e, _)| read.contains(&e.id))
+            .map(|(_, terms)| ranker.vector(terms))
+            .collect();
+
+        for vector in &read_vectors {
+            for (term, weight) in vector {
+                *ranker.profile.entry(term.clone()).or_default() +=
+                    weight / read_vectors.len() as f32;
+            }
+        }
+
+        ranker
+    }
+
+    fn vector(&self, terms: &[String]) -> TermVector {
+        let mut counts: HashMap<&str, f32> = HashMap::new();
+        for term in terms {
+            *counts.entry(term).or_default() += 1.0;
+        }
+
+        counts
+            .into_iter()
+            .map(|(term, count)| {
+                let idf = self.idf.get(term).copied().unwrap_or(1.0);
+                (term.to_owned(), count / terms.len() as f32 * idf)
+            })
+            .collect()
+    }
+
+    /// The cosine similarity of the entry to the reading profile, from 0 to 1.
+    pub fn score(&self, entry: &Entry) -> f32 {
+        cosine(&self.vector(&terms(entry)), &self.profile)
+    }
+}
+
+/// The `n` terms of the entry that weigh most by TF-IDF among `entries`,
+/// heaviest first.
+pub(crate) fn top_terms(entry: &Entry, entries: &[(String, Entry)], n: usize) -> Vec<String> {
+    let documents: Vec<HashSet<String>> = entries
+        .iter()
+        .map(|(_, e)| terms(e).into_iter().collect())
+        .collect();
+    let terms = terms(entry);
+    let mut counts: HashMap<&str, f32> = HashMap::new();
+    for term in &terms {
+        *counts.entry(term).or_default() += 1.0;
+    }
+    let total = documents.len().max(1) as f32;
+    let mut weighted: Vec<(&str, f32)> = counts
+        .into_iter()
+        .map(|(term, count)| {
+            let df = documents.iter().filter(|d| d.contains(term)).count().max(1);
+            (
+                term,
+                count / terms.len() as f32 * ((total / df as f32).ln() + 1.0),
+            )
+        })
+        .collect();
+    weighted.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+
+    weighted
+        .into_iter()
+        .take(n)
+        .map(|(term, _)| term.to_owned())
+        .collect()
+}
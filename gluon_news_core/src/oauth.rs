@@ -0,0 +1,267 @@
+//! Authorizes feeds with OAuth 2.0 and PKCE in the system browser, keeping the
+//! tokens in the OS keychain.
+
+use crate::{config::FeedAuth, error::OAuthError, CLIENT};
+use axum::{
+    extract::{Query, State},
+    response::Html,
+    routing::get,
+    Router, Server,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use keyring::Entry;
+use lazy_static::lazy_static;
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    io,
+    net::TcpListener,
+    process::Command,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::oneshot;
+use url::Url;
+
+const KEYRING_SERVICE: &str = "gluon_news";
+const VERIFIER_LEN: usize = 64;
+const STATE_LEN: usize = 32;
+/// Tokens this close to expiring are refreshed before they are used.
+const EXPIRY_MARGIN_SECONDS: i64 = 60;
+const AUTHORIZATION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
+const CALLBACK_PAGE: &str = "<p>Gluon News is authorized, you can close this page.</p>";
+
+type CallbackSender = Arc<Mutex<Option<oneshot::Sender<HashMap<String, String>>>>>;
+
+lazy_static! {
+    /// Authorizes one feed at a time, and keeps a refresh from using tokens
+    /// while they are replaced.
+    static ref AUTHORIZING: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+#[derive(Deserialize, Serialize)]
+struct Tokens {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl Tokens {
+    fn is_fresh(&self) -> bool {
+        self.expires_at
+            .is_none_or(|at| at - Duration::seconds(EXPIRY_MARGIN_SECONDS) > Utc::now())
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+}
+
+/// The tokens are stored as JSON under the feed URL.
+fn load_tokens(feed_url: &str) -> Option<Tokens> {
+    let data = Entry::new(KEYRING_SERVICE, feed_url)
+        .ok()?
+        .get_password()
+        .ok()?;
+
+    serde_json::from_str(&data).ok()
+}
+
+fn save_tokens(feed_url: &str, tokens: &Tokens) -> Result<(), OAuthError> {
+    let data = serde_json::to_string(tokens).map_err(io::Error::other)?;
+
+    Ok(Entry::new(KEYRING_SERVICE, feed_url)?.set_password(&data)?)
+}
+
+fn random_string(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+pub(crate) fn open_in_browser(url: &str) -> io::Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+
+    command.arg(url).spawn().map(|_| ())
+}
+
+async fn request_tokens(
+    token_url: &str,
+    form: &[(&str, &str)],
+    refresh_token: Option<String>,
+) -> Result<Tokens, OAuthError> {
+    let response: TokenResponse = CLIENT
+        .post(token_url)
+        .header("User-Agent", "gluon_news")
+        .form(form)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(Tokens {
+        access_token: response.access_token,
+        // Servers may keep the refresh token and leave it out of the response.
+        refresh_token: response.refresh_token.or(refresh_token),
+        expires_at: response
+            .expires_in
+            .map(|seconds| Utc::now() + Duration::seconds(seconds)),
+    })
+}
+
+async fn callback(
+    State(sender): State<CallbackSender>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Html<&'static str> {
+    if let Some(sender) = sender.lock().ok().and_then(|mut sender| sender.take()) {
+        let _ = sender.send(params);
+    }
+
+    Html(CALLBACK_PAGE)
+}
+
+/// Opens the authorization page in the browser and waits for it to redirect
+/// back to a local server with the code, which is then exchanged for tokens.
+async fn authorize(
+    client_id: &str,
+    auth_url: &str,
+    token_url: &str,
+    scopes: &[String],
+) -> Result<Tokens, OAuthError> {
+    let verifier = random_string(VERIFIER_LEN);
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    let state = random_string(STATE_LEN);
+
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    listener.set_nonblocking(true)?;
+    // The address the listener is bound to rather than `localhost`, which can
+    // resolve to `::1` first (RFC 8252, section 7.3).
+    let redirect_uri = format!(
+        "http://127.0.0.1:{}/callback",
+        listener.local_addr()?.port()
+    );
+    let (sender, receiver) = oneshot::channel();
+    let sender: CallbackSender = Arc::new(Mutex::new(Some(sender)));
+    let (stop, stopped) = oneshot::channel::<()>();
+    let app = Router::new()
+        .route("/callback", get(callback))
+        .with_state(sender);
+    let server = Server::from_tcp(listener)
+        .map_err(io::Error::other)?
+        .serve(app.into_make_service())
+        .with_graceful_shutdown(async {
+            stopped.await.ok();
+        });
+    tokio::spawn(server);
+
+    let mut url = Url::parse(auth_url).map_err(|e| OAuthError::Denied(e.to_string()))?;
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", client_id)
+        .append_pair("redirect_uri", &redirect_uri)
+        .append_pair("scope", &scopes.join(" "))
+        .append_pair("state", &state)
+        .append_pair("code_challenge", &challenge)
+        .append_pair("code_challenge_method", "S256");
+    open_in_browser(url.as_str())?;
+
+    let params = tokio::time::timeout(AUTHORIZATION_TIMEOUT, receiver).await;
+    let _ = stop.send(());
+    let Ok(Ok(params)) = params else {
+        return Err(OAuthError::Denied(String::from(
+            "no answer from the browser",
+        )));
+    };
+
+    if params.get("state") != Some(&state) {
+        return Err(OAuthError::Denied(String::from("state does not match")));
+    }
+    if let Some(error) = params.get("error") {
+        return Err(OAuthError::Denied(error.clone()));
+    }
+    let Some(code) = params.get("code") else {
+        return Err(OAuthError::Denied(String::from("no authorization code")));
+    };
+
+    request_tokens(
+        token_url,
+        &[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &redirect_uri),
+            ("client_id", client_id),
+            ("code_verifier", &verifier),
+        ],
+        None,
+    )
+    .await
+}
+
+/// An access token for the feed, refreshing it when it has expired. Fetches
+/// never open the browser: a feed with no tokens, or with tokens that cannot
+/// be refreshed, is `NotAuthorized` until `authorize_feed` is called.
+pub async fn access_token(feed_url: &str, auth: &FeedAuth) -> Result<String, OAuthError> {
+    let FeedAuth::Oauth2Pkce {
+        client_id,
+        token_url,
+        ..
+    } = auth;
+    let _authorizing = AUTHORIZING.lock().await;
+
+    let tokens = match load_tokens(feed_url) {
+        Some(tokens) if tokens.is_fresh() => return Ok(tokens.access_token),
+        Some(Tokens {
+            refresh_token: Some(refresh_token),
+            ..
+        }) => {
+            let form = [
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token.as_str()),
+                ("client_id", client_id.as_str()),
+            ];
+            match request_tokens(token_url, &form, Some(refresh_token.clone())).await {
+                Ok(tokens) => tokens,
+                Err(e) => {
+                    tracing::warn!("could not refresh the token of {feed_url}: {e}");
+                    return Err(OAuthError::NotAuthorized);
+                }
+            }
+        }
+        _ => return Err(OAuthError::NotAuthorized),
+    };
+    save_tokens(feed_url, &tokens)?;
+
+    Ok(tokens.access_token)
+}
+
+/// Authorizes the feed in the browser and keeps its tokens, for when the user
+/// asks to.
+pub async fn authorize_feed(feed_url: &str, auth: &FeedAuth) -> Result<(), OAuthError> {
+    let FeedAuth::Oauth2Pkce {
+        client_id,
+        auth_url,
+        token_url,
+        scopes,
+    } = auth;
+    let _authorizing = AUTHORIZING.lock().await;
+    let tokens = authorize(client_id, auth_url, token_url, scopes).await?;
+
+    save_tokens(feed_url, &tokens)
+}
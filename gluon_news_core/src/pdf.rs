@@ -0,0 +1,68 @@
+use std::{
+    env,
+    error::Error,
+    path::{Path, PathBuf},
+};
+use tokio::process::Command;
+
+const MAX_FILE_NAME_CHARS: usize = 80;
+
+pub fn downloads_dir() -> PathBuf {
+    env::var_os("XDG_DOWNLOAD_DIR")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join("Downloads")))
+        .or_else(|| env::var_os("USERPROFILE").map(|home| PathBuf::from(home).join("Downloads")))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Builds a file name from the title, keeping only alphanumeric characters.
+pub fn safe_file_name(title: &str, fallback: &str, extension: &str) -> String {
+    let name: String = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .take(MAX_FILE_NAME_CHARS)
+        .collect();
+    let name = name.trim_matches('_');
+
+    if name.is_empty() {
+        format!("{fallback}.{extension}")
+    } else {
+        format!("{name}.{extension}")
+    }
+}
+
+pub fn pdf_file_name(title: &str) -> String {
+    safe_file_name(title, "entry", "pdf")
+}
+
+async fn weasyprint(html_path: &Path, pdf_path: &Path) -> Result<(), Box<dyn Error>> {
+    let status = Command::new("weasyprint")
+        .arg(html_path)
+        .arg(pdf_path)
+        .status()
+        .await?;
+
+    if !status.success() {
+        return Err(format!("weasyprint exited with {status}").into());
+    }
+
+    Ok(())
+}
+
+pub async fn save_as_pdf(title: &str, html: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let html_path = env::temp_dir().join("gluon_news_entry.html");
+    let pdf_path = downloads_dir().join(pdf_file_name(title));
+    let document = format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{title}</title></head>\
+         <body><h1>{title}</h1>{html}</body></html>",
+        title = crate::text::escape_html(title)
+    );
+
+    tokio::fs::create_dir_all(downloads_dir()).await?;
+    tokio::fs::write(&html_path, document).await?;
+    let result = weasyprint(&html_path, &pdf_path).await;
+    tokio::fs::remove_file(&html_path).await.ok();
+    result?;
+
+    Ok(pdf_path)
+}
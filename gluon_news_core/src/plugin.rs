@@ -0,0 +1,114 @@
+//! Entry processors that run over every fetched entry, in the order given by
+//! the `processors` setting. Processors are registered statically in [`pipeline`].
+
+use crate::{
+    compat::EntryExt,
+    config::ProcessorConfig,
+    reading_speed::{estimate_reading_time, word_count, DEFAULT_WPM},
+    text,
+};
+use feed_rs::model::Entry;
+
+pub struct ExtendedEntry {
+    pub feed_title: String,
+    pub entry: Entry,
+    pub reading_time: Option<u32>,
+}
+
+pub enum ProcessResult {
+    Continue,
+    Failed(String),
+}
+
+pub trait EntryProcessor: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn process(&self, entry: &mut ExtendedEntry) -> ProcessResult;
+}
+
+pub struct SanitizeHtml;
+
+impl EntryProcessor for SanitizeHtml {
+    fn name(&self) -> &'static str {
+        "sanitize_html"
+    }
+
+    fn process(&self, entry: &mut ExtendedEntry) -> ProcessResult {
+        if let Some(summary) = entry.entry.summary.as_mut() {
+            summary.content = text::sanitize_html(&summary.content);
+        }
+        if let Some(body) = entry.entry.content.as_mut().and_then(|c| c.body.as_mut()) {
+            *body = text::sanitize_html(body);
+        }
+
+        ProcessResult::Continue
+    }
+}
+
+pub struct TruncateSummary {
+    pub max_chars: usize,
+}
+
+impl EntryProcessor for TruncateSummary {
+    fn name(&self) -> &'static str {
+        "truncate_summary"
+    }
+
+    fn process(&self, entry: &mut ExtendedEntry) -> ProcessResult {
+        if let Some(summary) = entry.entry.summary.as_mut() {
+            let plain = text::strip_html(&summary.content);
+            if plain.chars().count() > self.max_chars {
+                summary.content = text::escape_html(&text::truncate(&plain, self.max_chars));
+            }
+        }
+
+        ProcessResult::Continue
+    }
+}
+
+pub struct ExtractReadingTime {
+    pub wpm: u32,
+}
+
+impl EntryProcessor for ExtractReadingTime {
+    fn name(&self) -> &'static str {
+        "extract_reading_time"
+    }
+
+    fn process(&self, entry: &mut ExtendedEntry) -> ProcessResult {
+        if self.wpm == 0 {
+            return ProcessResult::Failed(String::from("wpm must be greater than zero"));
+        }
+
+        let words = word_count(&text::strip_html(&entry.entry.best_content()));
+        entry.reading_time = Some(estimate_reading_time(words, self.wpm));
+
+        ProcessResult::Continue
+    }
+}
+
+pub fn pipeline(configs: &[ProcessorConfig]) -> Vec<Box<dyn EntryProcessor>> {
+    configs
+        .iter()
+        .map(|config| -> Box<dyn EntryProcessor> {
+            match config {
+                ProcessorConfig::SanitizeHtml => Box::new(SanitizeHtml),
+                ProcessorConfig::TruncateSummary { max_chars } => Box::new(TruncateSummary {
+                    max_chars: *max_chars,
+                }),
+                ProcessorConfig::ExtractReadingTime { wpm } => Box::new(ExtractReadingTime {
+                    wpm: wpm.unwrap_or(DEFAULT_WPM),
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Runs the entry through every processor. A failing processor is logged and
+/// the remaining ones still run.
+pub fn run(processors: &[Box<dyn EntryProcessor>], entry: &mut ExtendedEntry) {
+    for processor in processors {
+        if let ProcessResult::Failed(e) = processor.process(entry) {
+            tracing::warn!("entry processor {} failed: {e}", processor.name());
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{compat::EntryExt, state};
+use chrono::{DateTime, Utc};
+use feed_rs::model::Entry;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, error::Error, time::Duration};
+
+pub const DEFAULT_MIN_POLL_INTERVAL_SECONDS: u64 = 300;
+pub const DEFAULT_MAX_POLL_INTERVAL_SECONDS: u64 = 86400;
+const HISTORY_LEN: usize = 20;
+const POLL_HISTORY_FILE: &str = "poll_history.json";
+
+#[derive(Deserialize, Serialize)]
+struct Arrival {
+    entry_id: String,
+    date: DateTime<Utc>,
+}
+
+/// The dates of the latest entries of each feed, used to poll a feed about as
+/// often as it gets new entries.
+#[derive(Default, Deserialize, Serialize)]
+pub struct PollHistory {
+    feeds: HashMap<String, Vec<Arrival>>,
+}
+
+impl PollHistory {
+    pub fn load() -> Self {
+        state::load(POLL_HISTORY_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        state::save(POLL_HISTORY_FILE, self)
+    }
+
+    /// Adds the entries not seen before, keeping the latest 20 per feed.
+    /// Undated entries are skipped, as they say nothing about when they arrived.
+    pub fn record(&mut self, feed_title: &str, entries: &[Entry]) {
+        let arrivals = self.feeds.entry(feed_title.to_owned()).or_default();
+
+        for entry in entries {
+            let Some(date) = entry.best_date() else {
+                continue;
+            };
+            if !arrivals.iter().any(|a| a.entry_id == entry.id) {
+                arrivals.push(Arrival {
+                    entry_id: entry.id.clone(),
+                    date,
+                });
+            }
+        }
+
+        arrivals.sort_by_key(|a| std::cmp::Reverse(a.date));
+        arrivals.truncate(HISTORY_LEN);
+    }
+
+    /// Twice the median time between new entries, clamped to `min..=max`, or
+    /// `None` until the feed has at least two dated entries.
+    pub fn estimate(&self, feed_title: &str, min: Duration, max: Duration) -> Option<Duration> {
+        let arrivals = self.feeds.get(feed_title)?;
+        let mut gaps: Vec<i64> = arrivals
+            .windows(2)
+            .map(|pair| (pair[0].date - pair[1].date).num_seconds())
+            .collect();
+
+        if gaps.is_empty() {
+            return None;
+        }
+
+        gaps.sort_unstable();
+        let median = gaps[gaps.len() / 2].max(0) as u64;
+
+        Some(Duration::from_secs(median * 2).clamp(min, max.max(min)))
+    }
+}
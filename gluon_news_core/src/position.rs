@@ -0,0 +1,24 @@
+//! Where the user was in the entry list when the app closed, to scroll back
+//! there on the next start.
+
+use crate::state;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+const POSITION_FILE: &str = "position.json";
+
+/// The entry at the top of the list.
+#[derive(Default, Deserialize, Serialize)]
+pub struct ListPosition {
+    pub top_entry_id: Option<String>,
+}
+
+impl ListPosition {
+    pub fn load() -> Self {
+        state::load(POSITION_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        state::save(POSITION_FILE, self)
+    }
+}
@@ -0,0 +1,127 @@
+//! Learns what the user is not interested in from the entries they mute, to
+//! suggest mute rules they have not set yet.
+
+use crate::{compat::EntryExt, nlp, state};
+use feed_rs::model::Entry;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+const MUTE_HISTORY_FILE: &str = "mute_history.json";
+/// Rules are only suggested once this many entries were muted.
+const MIN_MUTES: usize = 5;
+/// A domain, category or keyword shared by this many muted entries is
+/// suggested.
+const MIN_OCCURRENCES: usize = 3;
+const KEYWORDS_PER_ENTRY: usize = 10;
+
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MuteRule {
+    Domain(String),
+    Category(String),
+    Keyword(String),
+}
+
+impl MuteRule {
+    /// What the rule mutes, as in "You might want to mute: entries in Sports".
+    pub fn label(&self) -> String {
+        match self {
+            MuteRule::Domain(domain) => format!("entries from {domain}"),
+            MuteRule::Category(category) => format!("entries in {category}"),
+            MuteRule::Keyword(keyword) => format!("entries mentioning \"{keyword}\""),
+        }
+    }
+
+    pub fn matches(&self, entry: &Entry) -> bool {
+        match self {
+            MuteRule::Domain(domain) => entry.domain().as_ref() == Some(domain),
+            MuteRule::Category(category) => entry.category_names().contains(category),
+            MuteRule::Keyword(keyword) => nlp::terms(entry).contains(keyword),
+        }
+    }
+}
+
+/// What is kept of a muted entry: its domain, categories and the terms that
+/// set it apart from the other entries.
+#[derive(Deserialize, Serialize)]
+pub struct MutedEntry {
+    pub domain: Option<String>,
+    pub categories: Vec<String>,
+    pub keywords: Vec<String>,
+}
+
+impl MutedEntry {
+    /// The keywords are weighed by TF-IDF among `entries`.
+    pub fn new(entry: &Entry, entries: &[(String, Entry)]) -> Self {
+        Self {
+            domain: entry.domain(),
+            categories: entry.category_names(),
+            keywords: nlp::top_terms(entry, entries, KEYWORDS_PER_ENTRY),
+        }
+    }
+
+    fn rules(&self) -> impl Iterator<Item = MuteRule> + '_ {
+        self.domain
+            .iter()
+            .cloned()
+            .map(MuteRule::Domain)
+            .chain(self.categories.iter().cloned().map(MuteRule::Category))
+            .chain(self.keywords.iter().cloned().map(MuteRule::Keyword))
+    }
+}
+
+/// The rules shared by enough of the muted entries, the most shared first.
+pub fn infer_mute_rules(muted_entries: &[MutedEntry]) -> Vec<MuteRule> {
+    if muted_entries.len() < MIN_MUTES {
+        return Vec::new();
+    }
+    let mut occurrences: Vec<(MuteRule, usize)> = Vec::new();
+    for rule in muted_entries.iter().flat_map(MutedEntry::rules) {
+        match occurrences.iter_mut().find(|(r, _)| *r == rule) {
+            Some((_, count)) => *count += 1,
+            None => occurrences.push((rule, 1)),
+        }
+    }
+    // The sort is stable, so ties keep the order the rules were first seen in.
+    occurrences.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+    occurrences
+        .into_iter()
+        .filter(|(_, count)| *count >= MIN_OCCURRENCES)
+        .map(|(rule, _)| rule)
+        .collect()
+}
+
+/// The entries muted so far, and the suggestions turned down.
+#[derive(Default, Deserialize, Serialize)]
+pub struct MuteHistory {
+    muted: Vec<MutedEntry>,
+    dismissed: Vec<MuteRule>,
+}
+
+impl MuteHistory {
+    pub fn load() -> Self {
+        state::load(MUTE_HISTORY_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        state::save(MUTE_HISTORY_FILE, self)
+    }
+
+    pub fn record(&mut self, muted: MutedEntry) {
+        self.muted.push(muted);
+    }
+
+    pub fn dismiss(&mut self, rule: MuteRule) {
+        self.dismissed.push(rule);
+    }
+
+    /// The inferred rules, leaving out those turned down and those `active`
+    /// says are in place already.
+    pub fn suggestions(&self, active: impl Fn(&MuteRule) -> bool) -> Vec<MuteRule> {
+        infer_mute_rules(&self.muted)
+            .into_iter()
+            .filter(|rule| !self.dismissed.contains(rule) && !active(rule))
+            .collect()
+    }
+}
@@ -0,0 +1,55 @@
+//! Named profiles, each with its own settings, feeds and entry state, stored
+//! as subdirectories of `profiles/`. Without a profile, files are read from
+//! and written to the current directory, as before profiles existed.
+
+use std::{fs, io, path::PathBuf, sync::OnceLock};
+
+const PROFILES_DIR: &str = "profiles";
+
+static ACTIVE: OnceLock<String> = OnceLock::new();
+
+/// The names of the existing profiles, sorted.
+pub fn list() -> Vec<String> {
+    let mut profiles: Vec<String> = fs::read_dir(PROFILES_DIR)
+        .map(|read_dir| {
+            read_dir
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+                .filter_map(|e| e.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    profiles.sort();
+
+    profiles
+}
+
+/// Makes `name` the profile every file path resolves into, creating its
+/// directory if needed. It has to be called before the settings or any state
+/// are loaded, and only the first call has any effect.
+pub fn select(name: &str) -> io::Result<()> {
+    if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid profile name {name:?}"),
+        ));
+    }
+
+    fs::create_dir_all(PathBuf::from(PROFILES_DIR).join(name))?;
+    ACTIVE.get_or_init(|| name.to_owned());
+
+    Ok(())
+}
+
+pub fn active() -> Option<&'static str> {
+    ACTIVE.get().map(String::as_str)
+}
+
+/// The path of `file` inside the active profile.
+pub fn path(file: &str) -> PathBuf {
+    match active() {
+        Some(name) => PathBuf::from(PROFILES_DIR).join(name).join(file),
+        None => PathBuf::from(file),
+    }
+}
@@ -0,0 +1,77 @@
+//! Scores entries for the `Ranked` ranking mode, from how recent they are, how
+//! much their topics are being written about and how much the user reads them.
+
+use crate::{compat::EntryExt, config::RankingWeights};
+use chrono::{DateTime, Duration, Utc};
+use feed_rs::model::Entry;
+use std::collections::{HashMap, HashSet};
+
+/// Hours after which the recency part of the score has dropped to about a third.
+const DECAY_HOURS: f64 = 24.0;
+
+/// `recency * time_decay + popularity * trending_score + personal * category_affinity`
+/// by entry ID, each part between 0 and 1:
+///
+/// - `time_decay` falls off exponentially with the entry's age.
+/// - `trending_score` is the share of the entries of the last day that have a
+///   category in common with this one.
+/// - `category_affinity` is the share of read entries in the entry's most read
+///   category.
+pub fn scores(
+    entries: &[(String, Entry)],
+    read: &HashSet<String>,
+    weights: RankingWeights,
+    now: DateTime<Utc>,
+) -> HashMap<String, f64> {
+    let recent_since = now - Duration::hours(DECAY_HOURS as i64);
+    let mut recent_counts: HashMap<String, usize> = HashMap::new();
+    let mut read_counts: HashMap<String, usize> = HashMap::new();
+    let mut recent = 0;
+    let mut read_total = 0;
+
+    for (_, entry) in entries {
+        let categories = entry.category_names();
+        if entry.best_date().is_some_and(|d| d >= recent_since) {
+            recent += 1;
+            for category in &categories {
+                *recent_counts.entry(category.clone()).or_default() += 1;
+            }
+        }
+        if read.contains(&entry.id) {
+            read_total += 1;
+            for category in categories {
+                *read_counts.entry(category).or_default() += 1;
+            }
+        }
+    }
+
+    let share = |counts: &HashMap<String, usize>, total: usize, categories: &[String]| {
+        let best = categories
+            .iter()
+            .filter_map(|c| counts.get(c))
+            .max()
+            .copied()
+            .unwrap_or_default();
+        if total == 0 {
+            0.0
+        } else {
+            best as f64 / total as f64
+        }
+    };
+
+    entries
+        .iter()
+        .map(|(_, entry)| {
+            let categories = entry.category_names();
+            let time_decay = entry.best_date().map_or(0.0, |date| {
+                let hours = (now - date).num_minutes().max(0) as f64 / 60.0;
+                (-hours / DECAY_HOURS).exp()
+            });
+            let score = weights.recency * time_decay
+                + weights.popularity * share(&recent_counts, recent, &categories)
+                + weights.personal * share(&read_counts, read_total, &categories);
+
+            (entry.id.clone(), score)
+        })
+        .collect()
+}
@@ -0,0 +1,27 @@
+use crate::state;
+use chrono::{DateTime, Utc};
+use std::{collections::HashMap, error::Error};
+
+const RATE_LIMITS_FILE: &str = "rate_limits.json";
+
+/// Loads the time until which each rate limited feed should not be requested again.
+pub fn load() -> HashMap<String, DateTime<Utc>> {
+    state::load(RATE_LIMITS_FILE)
+}
+
+pub fn save(rate_limits: &HashMap<String, DateTime<Utc>>) -> Result<(), Box<dyn Error>> {
+    state::save(RATE_LIMITS_FILE, rate_limits)
+}
+
+/// Parses a Retry-After value, given either as delta-seconds or as an HTTP-date.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<u64> {
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse() {
+        return Some(seconds);
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+
+    Some((date.with_timezone(&Utc) - now).num_seconds().max(0) as u64)
+}
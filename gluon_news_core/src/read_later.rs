@@ -0,0 +1,76 @@
+use crate::state;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+const READ_LATER_QUEUE_FILE: &str = "read_later_queue.json";
+
+/// Entries put aside to read later, in the order they should be read. Unlike
+/// stars, entries leave the queue once dealt with.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct ReadLaterQueue {
+    ids: Vec<String>,
+}
+
+impl ReadLaterQueue {
+    pub fn load() -> Self {
+        state::load(READ_LATER_QUEUE_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        state::save(READ_LATER_QUEUE_FILE, self)
+    }
+
+    pub fn ids(&self) -> &[String] {
+        &self.ids
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn contains(&self, entry_id: &str) -> bool {
+        self.ids.iter().any(|id| id == entry_id)
+    }
+
+    /// Adds the entry at the end of the queue, unless it is already queued.
+    pub fn push(&mut self, entry_id: &str) {
+        if !self.contains(entry_id) {
+            self.ids.push(entry_id.to_owned());
+        }
+    }
+
+    /// Puts the entry back at `position`, or at the end when the queue is
+    /// shorter, unless it is already queued.
+    pub fn insert(&mut self, position: usize, entry_id: &str) {
+        if !self.contains(entry_id) {
+            self.ids
+                .insert(position.min(self.ids.len()), entry_id.to_owned());
+        }
+    }
+
+    pub fn remove(&mut self, entry_id: &str) {
+        self.ids.retain(|id| id != entry_id);
+    }
+
+    /// Moves the entry one place towards the front of the queue, or towards
+    /// the back when `up` is false. Does nothing at either end.
+    pub fn shift(&mut self, entry_id: &str, up: bool) {
+        let Some(position) = self.ids.iter().position(|id| id == entry_id) else {
+            return;
+        };
+        let other = if up {
+            position.checked_sub(1)
+        } else {
+            Some(position + 1).filter(|&p| p < self.ids.len())
+        };
+
+        if let Some(other) = other {
+            self.ids.swap(position, other);
+        }
+    }
+}
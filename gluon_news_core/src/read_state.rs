@@ -0,0 +1,17 @@
+use crate::{feed_cache, state};
+use std::{collections::HashSet, error::Error};
+
+const READ_STATE_FILE: &str = "read_state.json";
+
+pub fn load() -> HashSet<String> {
+    feed_cache::load(|cache| cache.read.clone())
+}
+
+pub fn save(read: &HashSet<String>) -> Result<(), Box<dyn Error>> {
+    feed_cache::update(|cache| cache.read = read.clone())
+}
+
+/// The file the read entries were kept in before the feed cache.
+pub(crate) fn load_legacy() -> HashSet<String> {
+    state::load(READ_STATE_FILE)
+}
@@ -0,0 +1,102 @@
+//! The entries read each day, measured against the reading goal.
+
+use crate::{config::ReadingGoal, state};
+use chrono::{Datelike, Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, error::Error};
+
+const READ_COUNTS_FILE: &str = "read_counts.json";
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Period {
+    #[default]
+    Daily,
+    /// Weeks start on Monday.
+    Weekly,
+}
+
+impl Period {
+    /// The first day of the period `day` is in.
+    pub fn start(&self, day: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Daily => day,
+            Period::Weekly => day
+                .checked_sub_days(Days::new(day.weekday().num_days_from_monday() as u64))
+                .unwrap_or(day),
+        }
+    }
+
+    fn len(&self) -> u64 {
+        match self {
+            Period::Daily => 1,
+            Period::Weekly => 7,
+        }
+    }
+
+    /// The current period, as in "5/10 articles today".
+    pub fn label(&self) -> &'static str {
+        match self {
+            Period::Daily => "today",
+            Period::Weekly => "this week",
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Period::Daily => "days",
+            Period::Weekly => "weeks",
+        }
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct ReadCounts {
+    counts: BTreeMap<NaiveDate, u32>,
+}
+
+impl ReadCounts {
+    pub fn load() -> Self {
+        state::load(READ_COUNTS_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        state::save(READ_COUNTS_FILE, self)
+    }
+
+    pub fn record(&mut self, day: NaiveDate) {
+        *self.counts.entry(day).or_default() += 1;
+    }
+
+    /// The entries read in the period `day` is in.
+    pub fn in_period(&self, period: Period, day: NaiveDate) -> u32 {
+        let start = period.start(day);
+        let end = start
+            .checked_add_days(Days::new(period.len() - 1))
+            .unwrap_or(start);
+
+        self.counts.range(start..=end).map(|(_, count)| count).sum()
+    }
+
+    /// The periods in a row the goal was met in, ending with the current
+    /// period, or the one before while the current one is not met yet.
+    pub fn goal_streak(&self, goal: &ReadingGoal, today: NaiveDate) -> u32 {
+        let met = |start: NaiveDate| self.in_period(goal.period, start) >= goal.count;
+        let previous = |start: NaiveDate| start.checked_sub_days(Days::new(goal.period.len()));
+        let current = goal.period.start(today);
+        let mut start = if met(current) {
+            Some(current)
+        } else {
+            previous(current)
+        };
+        let mut streak = 0;
+
+        while let Some(period_start) = start.filter(|&s| met(s)) {
+            streak += 1;
+            start = previous(period_start);
+        }
+
+        streak
+    }
+}
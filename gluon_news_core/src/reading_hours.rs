@@ -0,0 +1,64 @@
+//! The hours of the day the user reads in, to keep notifications to the
+//! hours they are likely to see them.
+
+use crate::{state, SETTINGS};
+use chrono::{Timelike, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, str::FromStr};
+
+const READING_HOURS_FILE: &str = "reading_hours.json";
+/// The reads recorded before peak hours are worked out. Until then every
+/// hour counts as a peak hour.
+const MIN_READS: u32 = 20;
+
+/// The hour of the day in the configured time zone.
+pub fn current_hour() -> u32 {
+    let time_zone = Tz::from_str(&SETTINGS.time_zone).unwrap_or(Tz::UTC);
+
+    Utc::now().with_timezone(&time_zone).hour()
+}
+
+/// The entries opened in each hour of the day.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct ReadingHours {
+    counts: [u32; 24],
+}
+
+impl ReadingHours {
+    pub fn load() -> Self {
+        state::load(READING_HOURS_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        state::save(READING_HOURS_FILE, self)
+    }
+
+    pub fn record(&mut self, hour: u32) {
+        if let Some(count) = self.counts.get_mut(hour as usize) {
+            *count += 1;
+        }
+    }
+
+    /// The hours with at least half the reads of the busiest one.
+    pub fn peak_hours(&self) -> Vec<u32> {
+        if self.counts.iter().sum::<u32>() < MIN_READS {
+            return (0..24).collect();
+        }
+        let busiest = self.counts.iter().copied().max().unwrap_or_default();
+
+        (0..24)
+            .filter(|&hour| self.counts[hour as usize] * 2 >= busiest)
+            .collect()
+    }
+
+    /// Whether `hour` is within `buffer_hours` of a peak hour, counting
+    /// across midnight.
+    pub fn in_window(&self, hour: u32, buffer_hours: u32) -> bool {
+        self.peak_hours().into_iter().any(|peak| {
+            let distance = hour.abs_diff(peak) % 24;
+            distance.min(24 - distance) <= buffer_hours
+        })
+    }
+}
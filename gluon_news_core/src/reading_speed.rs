@@ -0,0 +1,85 @@
+use crate::state;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+pub const DEFAULT_WPM: u32 = 200;
+const MIN_SAMPLES: usize = 20;
+const MIN_SECONDS: i64 = 5;
+const MAX_SECONDS: i64 = 3600;
+const READING_SPEED_FILE: &str = "reading_speed.json";
+
+#[derive(Deserialize, Serialize)]
+struct ReadingSample {
+    entry_id: String,
+    words: usize,
+    opened: DateTime<Utc>,
+    closed: DateTime<Utc>,
+}
+
+impl ReadingSample {
+    fn seconds(&self) -> i64 {
+        (self.closed - self.opened).num_seconds()
+    }
+
+    fn is_plausible(&self) -> bool {
+        self.words > 0 && (MIN_SECONDS..=MAX_SECONDS).contains(&self.seconds())
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct ReadingProfile {
+    samples: Vec<ReadingSample>,
+}
+
+impl ReadingProfile {
+    pub fn load() -> Self {
+        state::load(READING_SPEED_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        state::save(READING_SPEED_FILE, self)
+    }
+
+    pub fn record(
+        &mut self,
+        entry_id: String,
+        words: usize,
+        opened: DateTime<Utc>,
+        closed: DateTime<Utc>,
+    ) {
+        let sample = ReadingSample {
+            entry_id,
+            words,
+            opened,
+            closed,
+        };
+
+        if sample.is_plausible() {
+            self.samples.push(sample);
+        }
+    }
+
+    pub fn personalised_wpm(&self) -> Option<u32> {
+        if self.samples.len() < MIN_SAMPLES {
+            return None;
+        }
+
+        let words: usize = self.samples.iter().map(|s| s.words).sum();
+        let seconds: i64 = self.samples.iter().map(|s| s.seconds()).sum();
+
+        Some(((words as f64 * 60.0 / seconds as f64).round() as u32).max(1))
+    }
+
+    pub fn wpm(&self) -> u32 {
+        self.personalised_wpm().unwrap_or(DEFAULT_WPM)
+    }
+}
+
+pub fn word_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+pub fn estimate_reading_time(words: usize, wpm: u32) -> u32 {
+    (words as u32).div_ceil(wpm.max(1)).max(1)
+}
@@ -0,0 +1,116 @@
+//! Follow-up reminders on entries, fired as desktop notifications.
+
+use crate::{feed_cache, state};
+use chrono::{DateTime, Days, Duration, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+const REMINDERS_FILE: &str = "reminders.json";
+/// The local time "Tomorrow" reminders fire at.
+const TOMORROW_HOUR: u32 = 9;
+
+/// A reminder keeps the entry's title and link, as the entry may be gone from
+/// its feed by the time the reminder fires.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Reminder {
+    pub entry_id: String,
+    pub title: String,
+    pub link: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+/// The pending reminders, soonest first, at most one per entry.
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Reminders {
+    reminders: Vec<Reminder>,
+}
+
+impl Reminders {
+    pub fn load() -> Self {
+        feed_cache::load(|cache| cache.reminders.clone())
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        feed_cache::update(|cache| cache.reminders = self.clone())
+    }
+
+    /// The file the reminders were kept in before the feed cache.
+    pub(crate) fn load_legacy() -> Self {
+        state::load(REMINDERS_FILE)
+    }
+
+    pub fn pending(&self) -> &[Reminder] {
+        &self.reminders
+    }
+
+    pub fn get(&self, entry_id: &str) -> Option<&Reminder> {
+        self.reminders.iter().find(|r| r.entry_id == entry_id)
+    }
+
+    /// Sets the reminder of the entry, replacing the one it had.
+    pub fn schedule(&mut self, reminder: Reminder) {
+        self.cancel(&reminder.entry_id);
+        self.reminders.push(reminder);
+        self.reminders.sort_by_key(|r| r.at);
+    }
+
+    pub fn cancel(&mut self, entry_id: &str) {
+        self.reminders.retain(|r| r.entry_id != entry_id);
+    }
+
+    /// Removes and returns the reminders due by `now`.
+    pub fn take_due(&mut self, now: DateTime<Utc>) -> Vec<Reminder> {
+        let due = self.reminders.partition_point(|r| r.at <= now);
+
+        self.reminders.drain(..due).collect()
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Snooze {
+    OneHour,
+    Tomorrow,
+    ThreeDays,
+}
+
+impl Snooze {
+    pub const ALL: [Snooze; 3] = [Snooze::OneHour, Snooze::Tomorrow, Snooze::ThreeDays];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Snooze::OneHour => "In 1 hour",
+            Snooze::Tomorrow => "Tomorrow",
+            Snooze::ThreeDays => "In 3 days",
+        }
+    }
+
+    /// When the reminder fires, with "Tomorrow" meaning 9:00 the next day in
+    /// the given time zone.
+    pub fn target(&self, now: DateTime<Utc>, time_zone: Tz) -> DateTime<Utc> {
+        match self {
+            Snooze::OneHour => now + Duration::hours(1),
+            Snooze::ThreeDays => now + Duration::days(3),
+            Snooze::Tomorrow => {
+                let tomorrow = now.with_timezone(&time_zone).date_naive() + Days::new(1);
+                let nine = NaiveTime::from_hms_opt(TOMORROW_HOUR, 0, 0).unwrap_or_default();
+                time_zone
+                    .from_local_datetime(&tomorrow.and_time(nine))
+                    .earliest()
+                    .map_or(now + Duration::days(1), |at| at.with_timezone(&Utc))
+            }
+        }
+    }
+}
+
+/// Parses the value of a `datetime-local` input, such as "2023-10-14T18:30",
+/// as a time in the given time zone.
+pub fn parse_local(value: &str, time_zone: Tz) -> Option<DateTime<Utc>> {
+    let local = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M").ok()?;
+
+    time_zone
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|at| at.with_timezone(&Utc))
+}
@@ -0,0 +1,95 @@
+//! How far the sites entries link to can be trusted, going by the
+//! trusted_domains and suspicious_domains settings and, when an API key is
+//! set, Google Safe Browsing.
+
+use crate::{CLIENT, SETTINGS};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashSet;
+
+const SAFE_BROWSING_URL: &str = "https://safebrowsing.googleapis.com/v4/threatMatches:find";
+/// The most URLs Safe Browsing looks up in one request.
+const SAFE_BROWSING_BATCH: usize = 500;
+const THREAT_TYPES: [&str; 4] = [
+    "MALWARE",
+    "SOCIAL_ENGINEERING",
+    "UNWANTED_SOFTWARE",
+    "POTENTIALLY_HARMFUL_APPLICATION",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Credibility {
+    Trusted,
+    Suspicious,
+    Unknown,
+}
+
+/// Whether `host` is one of the domains or a subdomain of one.
+fn on_list(host: &str, domains: &[String]) -> bool {
+    let host = host.to_ascii_lowercase();
+
+    domains.iter().any(|domain| {
+        let domain = domain.to_ascii_lowercase();
+        host == domain || host.ends_with(&format!(".{domain}"))
+    })
+}
+
+/// A domain on both lists counts as suspicious.
+pub fn credibility(host: &str) -> Credibility {
+    if on_list(host, &SETTINGS.suspicious_domains) {
+        Credibility::Suspicious
+    } else if on_list(host, &SETTINGS.trusted_domains) {
+        Credibility::Trusted
+    } else {
+        Credibility::Unknown
+    }
+}
+
+#[derive(Deserialize)]
+struct ThreatMatches {
+    #[serde(default)]
+    matches: Vec<ThreatMatch>,
+}
+
+#[derive(Deserialize)]
+struct ThreatMatch {
+    threat: ThreatEntry,
+}
+
+#[derive(Deserialize)]
+struct ThreatEntry {
+    url: String,
+}
+
+/// The links Google Safe Browsing knows as malware, phishing or otherwise
+/// harmful.
+pub async fn unsafe_links(
+    links: &[String],
+    api_key: &str,
+) -> Result<HashSet<String>, reqwest::Error> {
+    let mut flagged = HashSet::new();
+
+    for batch in links.chunks(SAFE_BROWSING_BATCH) {
+        let body = json!({
+            "client": { "clientId": "gluon_news", "clientVersion": env!("CARGO_PKG_VERSION") },
+            "threatInfo": {
+                "threatTypes": THREAT_TYPES,
+                "platformTypes": ["ANY_PLATFORM"],
+                "threatEntryTypes": ["URL"],
+                "threatEntries": batch.iter().map(|url| json!({ "url": url })).collect::<Vec<_>>(),
+            },
+        });
+        let matches: ThreatMatches = CLIENT
+            .post(SAFE_BROWSING_URL)
+            .query(&[("key", api_key)])
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        flagged.extend(matches.matches.into_iter().map(|m| m.threat.url));
+    }
+
+    Ok(flagged)
+}
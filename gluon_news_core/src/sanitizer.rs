@@ -0,0 +1,153 @@
+//! Cleans entry HTML before it is rendered, keeping only the tags and
+//! attributes allowed by the `sanitizer` setting.
+
+use crate::SETTINGS;
+use ammonia::Builder;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::{collections::HashSet, sync::Mutex};
+use url::Url;
+
+/// Tags removed with their content, whatever the allowlist says.
+const FORBIDDEN_TAGS: [&str; 6] = ["script", "style", "iframe", "object", "embed", "form"];
+/// Also keeps `javascript:` URLs out, as no scheme outside the list is kept.
+const URL_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+/// Attributes allowed on every tag are listed under this name.
+const ANY_TAG: &str = "*";
+
+pub const DEFAULT_ALLOW_TAGS: [&str; 12] = [
+    "a",
+    "p",
+    "br",
+    "em",
+    "strong",
+    "ul",
+    "ol",
+    "li",
+    "blockquote",
+    "code",
+    "pre",
+    "img",
+];
+pub const DEFAULT_ALLOW_ATTRIBUTES: [(&str, &[&str]); 2] = [
+    ("a", &["href", "title"]),
+    ("img", &["src", "alt", "title", "width", "height"]),
+];
+
+/// Event handlers such as `onclick` are never kept, and `rel` is always set
+/// on links, which ammonia requires to not be allowed as well.
+fn is_allowed_attribute(attribute: &&String) -> bool {
+    let attribute = attribute.to_ascii_lowercase();
+
+    !attribute.starts_with("on") && attribute != "rel"
+}
+
+/// Finds the images in entry HTML that only track that the entry was read.
+pub struct CrossOriginFilter {
+    domains: Vec<String>,
+}
+
+impl CrossOriginFilter {
+    pub fn new(domains: &[String]) -> Self {
+        Self {
+            domains: domains.iter().map(|d| d.to_ascii_lowercase()).collect(),
+        }
+    }
+
+    /// Whether the `<img>` tag is 1×1 pixel, or its source is on one of the
+    /// domains or their subdomains.
+    pub fn is_tracking_pixel(&self, tag: &str) -> bool {
+        let attribute = |name: &str| {
+            IMG_ATTRIBUTE
+                .captures_iter(tag)
+                .find(|caps| caps[1].eq_ignore_ascii_case(name))
+                .and_then(|caps| caps.get(2).or(caps.get(3)).or(caps.get(4)))
+                .map(|value| value.as_str().trim().to_owned())
+        };
+        let is_one_pixel =
+            |value: Option<String>| value.is_some_and(|v| v.trim_end_matches("px") == "1");
+
+        if is_one_pixel(attribute("width")) && is_one_pixel(attribute("height")) {
+            return true;
+        }
+        let Some(host) = attribute("src")
+            .and_then(|src| Url::parse(&src).ok())
+            .and_then(|url| url.host_str().map(|host| host.to_ascii_lowercase()))
+        else {
+            return false;
+        };
+
+        self.domains
+            .iter()
+            .any(|domain| host == *domain || host.ends_with(&format!(".{domain}")))
+    }
+
+    /// Removes the tracking pixels from `html`, recording each one blocked.
+    pub fn remove_tracking_pixels(&self, html: &str) -> String {
+        IMG_TAG
+            .replace_all(html, |caps: &Captures| {
+                let tag = &caps[0];
+                if !self.is_tracking_pixel(tag) {
+                    return tag.to_owned();
+                }
+                if BLOCKED_PIXELS.lock().unwrap().insert(tag.to_owned()) {
+                    tracing::debug!("blocked tracking pixel {tag}");
+                }
+                String::new()
+            })
+            .into_owned()
+    }
+}
+
+/// How many different tracking pixels were removed from entries since the
+/// app started.
+pub fn trackers_blocked() -> usize {
+    BLOCKED_PIXELS.lock().unwrap().len()
+}
+
+lazy_static! {
+    static ref IMG_TAG: Regex = Regex::new(r"(?is)<img\b[^>]*>").unwrap();
+    static ref IMG_ATTRIBUTE: Regex =
+        Regex::new(r#"(?is)[\s/](src|width|height)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#)
+            .unwrap();
+    // Entries are sanitized each time they are rendered, so the same pixel is
+    // only counted once.
+    static ref BLOCKED_PIXELS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+    static ref TRACKING_FILTER: CrossOriginFilter =
+        CrossOriginFilter::new(&SETTINGS.tracking_pixel_domains);
+    static ref SANITIZER: Builder<'static> = {
+        let config = &SETTINGS.sanitizer;
+        let mut builder = Builder::empty();
+
+        builder
+            .add_tags(
+                config
+                    .allow_tags
+                    .iter()
+                    .filter(|tag| !FORBIDDEN_TAGS.contains(&tag.to_ascii_lowercase().as_str())),
+            )
+            .clean_content_tags(HashSet::from(FORBIDDEN_TAGS))
+            .url_schemes(HashSet::from(URL_SCHEMES))
+            .link_rel(Some("noopener noreferrer"));
+        for (tag, attributes) in &config.allow_attributes {
+            let attributes = attributes.iter().filter(is_allowed_attribute);
+            if tag == ANY_TAG {
+                builder.add_generic_attributes(attributes);
+            } else {
+                builder.add_tag_attributes(tag, attributes);
+            }
+        }
+
+        builder
+    };
+}
+
+pub fn sanitize(html: &str) -> String {
+    if SETTINGS.block_tracking_pixels {
+        SANITIZER
+            .clean(&TRACKING_FILTER.remove_tracking_pixels(html))
+            .to_string()
+    } else {
+        SANITIZER.clean(html).to_string()
+    }
+}
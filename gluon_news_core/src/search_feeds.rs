@@ -0,0 +1,159 @@
+//! Keyword searches listed as feeds, through the RSS or JSON search APIs of
+//! each source.
+
+use crate::{
+    config::{SearchFeedConfig, SearchSource},
+    error::FeedError,
+    FetchResult, CLIENT,
+};
+use chrono::{DateTime, Utc};
+use feed_rs::{
+    model::{Entry, Link, Text},
+    parser,
+};
+use futures::future::join_all;
+use serde::Deserialize;
+use url::Url;
+
+#[derive(Deserialize)]
+struct GitHubSearch {
+    items: Vec<GitHubRepository>,
+}
+
+#[derive(Deserialize)]
+struct GitHubRepository {
+    full_name: String,
+    html_url: String,
+    description: Option<String>,
+    updated_at: DateTime<Utc>,
+}
+
+impl GitHubRepository {
+    fn into_entry(self) -> Entry {
+        Entry {
+            id: self.html_url.clone(),
+            title: Some(plain_text(self.full_name)),
+            summary: self.description.map(plain_text),
+            updated: Some(self.updated_at),
+            links: vec![Link {
+                href: self.html_url,
+                rel: None,
+                media_type: None,
+                href_lang: None,
+                title: None,
+                length: None,
+            }],
+            ..Entry::default()
+        }
+    }
+}
+
+fn plain_text(content: String) -> Text {
+    Text {
+        content_type: mime::TEXT_PLAIN,
+        src: None,
+        content,
+    }
+}
+
+impl SearchFeedConfig {
+    /// The search API URL of the query, newest results first.
+    pub fn url(&self) -> String {
+        let max_entries = self.max_entries.to_string();
+        let (base, params) = match &self.source {
+            SearchSource::HackerNews => (
+                String::from("https://hnrss.org/newest"),
+                vec![("q", self.query.clone()), ("count", max_entries)],
+            ),
+            SearchSource::Reddit { subreddit } => (
+                format!("https://www.reddit.com/r/{subreddit}/search.rss"),
+                vec![
+                    ("q", self.query.clone()),
+                    ("restrict_sr", String::from("on")),
+                    ("sort", String::from("new")),
+                    ("limit", max_entries),
+                ],
+            ),
+            SearchSource::ArxivCs => (
+                String::from("https://export.arxiv.org/api/query"),
+                vec![
+                    ("search_query", format!("cat:cs.* AND all:{}", self.query)),
+                    ("sortBy", String::from("submittedDate")),
+                    ("sortOrder", String::from("descending")),
+                    ("max_results", max_entries),
+                ],
+            ),
+            SearchSource::GitHub { topic } => (
+                String::from("https://api.github.com/search/repositories"),
+                vec![
+                    ("q", format!("{} topic:{topic}", self.query)),
+                    ("sort", String::from("updated")),
+                    ("per_page", max_entries),
+                ],
+            ),
+        };
+
+        Url::parse_with_params(&base, params).map_or(base, String::from)
+    }
+}
+
+async fn search(config: &SearchFeedConfig) -> Result<Vec<Entry>, FeedError> {
+    let response = CLIENT
+        .get(config.url())
+        .header("User-Agent", "gluon_news")
+        .send()
+        .await?
+        .error_for_status()?;
+    let entries = match config.source {
+        SearchSource::GitHub { .. } => response
+            .json::<GitHubSearch>()
+            .await?
+            .items
+            .into_iter()
+            .map(GitHubRepository::into_entry)
+            .collect(),
+        _ => {
+            parser::parse(response.bytes().await?.as_ref())
+                .map_err(|e| FeedError::ParseError(e.to_string()))?
+                .entries
+        }
+    };
+
+    Ok(entries)
+}
+
+/// The latest results of the search, at most `max_entries`, under the title
+/// of the search.
+pub async fn fetch_search_feed(
+    config: &SearchFeedConfig,
+) -> Result<Vec<(String, Entry)>, FeedError> {
+    let title = config.title();
+
+    Ok(search(config)
+        .await?
+        .into_iter()
+        .take(config.max_entries as usize)
+        .map(|entry| (title.clone(), entry))
+        .collect())
+}
+
+/// Every search feed as a fetched feed, to be listed along the others. A
+/// search that fails is a failed feed, reported as one rather than as a
+/// search with no results.
+pub async fn fetch_search_results(configs: &[SearchFeedConfig]) -> Vec<FetchResult> {
+    join_all(configs.iter().map(|config| async move {
+        match fetch_search_feed(config).await {
+            Ok(entries) => FetchResult::Success(
+                config.title(),
+                entries.into_iter().map(|(_, entry)| entry).collect(),
+                None,
+                config.url(),
+            ),
+            Err(e) => {
+                tracing::warn!("could not search {}: {e}", config.title());
+                FetchResult::Failure(config.url(), e)
+            }
+        }
+    }))
+    .await
+}
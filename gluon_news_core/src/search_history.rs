@@ -0,0 +1,26 @@
+use crate::state;
+use std::error::Error;
+
+const SEARCH_HISTORY_FILE: &str = "search_history.json";
+const MAX_QUERIES: usize = 20;
+
+pub fn load() -> Vec<String> {
+    state::load(SEARCH_HISTORY_FILE)
+}
+
+pub fn save(history: &[String]) -> Result<(), Box<dyn Error>> {
+    state::save(SEARCH_HISTORY_FILE, history)
+}
+
+/// Moves the query to the front of the history, keeping only the most recent ones.
+pub fn record(history: &mut Vec<String>, query: &str) {
+    let query = query.trim();
+
+    if query.is_empty() {
+        return;
+    }
+
+    history.retain(|q| q != query);
+    history.insert(0, query.to_owned());
+    history.truncate(MAX_QUERIES);
+}
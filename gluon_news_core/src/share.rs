@@ -0,0 +1,94 @@
+//! Shares the subscribed feeds between instances as `gluon-news://` links.
+
+use crate::{
+    compression::{compress_text, decompress_untrusted_text},
+    config::{is_web_url, read_settings},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use qrcode::{render::svg, QrCode};
+use std::error::Error;
+use url::Url;
+
+pub const SCHEME: &str = "gluon-news";
+const QR_CODE_SIZE: u32 = 320;
+/// Far more than any list of feeds takes, and little enough memory that a
+/// link cannot exhaust it.
+const MAX_FEEDS_BYTES: usize = 1024 * 1024;
+
+/// A link that imports the feeds, with their URLs one per line in base64.
+pub fn share_uri(feeds: &[String]) -> String {
+    format!(
+        "{SCHEME}://import?feeds={}",
+        URL_SAFE_NO_PAD.encode(feeds.join("\n"))
+    )
+}
+
+/// A shorter link that imports the feeds, with them as a JSON array compressed
+/// with zstd, to fit in a QR code.
+pub fn compact_share_uri(feeds: &[String]) -> Result<String, Box<dyn Error>> {
+    let compressed = compress_text(&serde_json::to_string(feeds)?);
+
+    Ok(format!(
+        "{SCHEME}://import?feeds={}",
+        URL_SAFE_NO_PAD.encode(compressed)
+    ))
+}
+
+/// The link as a QR code, in SVG.
+pub fn qr_code_svg(uri: &str) -> Result<String, Box<dyn Error>> {
+    Ok(QrCode::new(uri.as_bytes())?
+        .render::<svg::Color>()
+        .min_dimensions(QR_CODE_SIZE, QR_CODE_SIZE)
+        .dark_color(svg::Color("#000000"))
+        .light_color(svg::Color("#ffffff"))
+        .build())
+}
+
+pub fn is_share_uri(arg: &str) -> bool {
+    arg.starts_with(&format!("{SCHEME}:"))
+}
+
+/// The feeds in a link made by `share_uri` or `compact_share_uri`, as they
+/// were written. Any web page can open such a link, so use `feeds_to_import`
+/// for the ones that are safe to offer.
+pub fn parse_share_uri(uri: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let url = Url::parse(uri)?;
+    if url.scheme() != SCHEME || url.host_str() != Some("import") {
+        return Err(format!("{uri} is not a {SCHEME}://import link").into());
+    }
+    let (_, encoded) = url
+        .query_pairs()
+        .find(|(name, _)| name == "feeds")
+        .ok_or("the link has no feeds")?;
+    let feeds = decompress_untrusted_text(
+        &URL_SAFE_NO_PAD.decode(encoded.as_bytes())?,
+        MAX_FEEDS_BYTES,
+    )
+    .map_err(|e| format!("the feeds in the link could not be read: {e}"))?;
+    if feeds.starts_with('[') {
+        return Ok(serde_json::from_str(&feeds)?);
+    }
+
+    Ok(feeds
+        .lines()
+        .map(str::trim)
+        .filter(|feed| !feed.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// The web feeds in the link that are not subscribed yet, for the user to
+/// confirm before they are added. Local paths and other schemes are left out,
+/// as they are in pasted text.
+pub fn feeds_to_import(uri: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let settings = read_settings()?;
+    let mut feeds: Vec<String> = Vec::new();
+    for feed in parse_share_uri(uri)? {
+        let is_new = !settings.all_feeds().any(|f| *f == feed) && !feeds.contains(&feed);
+        if is_web_url(&feed) && is_new {
+            feeds.push(feed);
+        }
+    }
+
+    Ok(feeds)
+}
@@ -0,0 +1,67 @@
+//! Expands links from URL shorteners to the address they redirect to.
+
+use crate::{state, CLIENT, SETTINGS};
+use feed_rs::model::Entry;
+use lazy_static::lazy_static;
+use std::{collections::HashMap, error::Error, sync::Mutex};
+use url::Url;
+
+const SHORT_LINKS_FILE: &str = "short_links.json";
+
+lazy_static! {
+    static ref EXPANSIONS: Mutex<HashMap<String, String>> = Mutex::new(load());
+}
+
+fn load() -> HashMap<String, String> {
+    state::load(SHORT_LINKS_FILE)
+}
+
+pub fn save() -> Result<(), Box<dyn Error>> {
+    state::save(SHORT_LINKS_FILE, &*EXPANSIONS.lock().unwrap())
+}
+
+fn is_short_link(link: &str) -> bool {
+    let Some(host) = Url::parse(link)
+        .ok()
+        .and_then(|url| url.host_str().map(String::from))
+    else {
+        return false;
+    };
+
+    SETTINGS
+        .short_link_domains
+        .iter()
+        .any(|domain| host.eq_ignore_ascii_case(domain))
+}
+
+async fn expand(link: &str) -> Option<String> {
+    if let Some(expanded) = EXPANSIONS.lock().unwrap().get(link) {
+        return Some(expanded.clone());
+    }
+
+    let response = match CLIENT.head(link).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::warn!("could not expand {link}: {e}");
+            return None;
+        }
+    };
+    let expanded = response.url().to_string();
+    EXPANSIONS
+        .lock()
+        .unwrap()
+        .insert(link.to_owned(), expanded.clone());
+
+    Some(expanded)
+}
+
+/// Replaces shortened entry links with their expanded URL.
+pub async fn expand_links(entries: &mut [Entry]) {
+    for link in entries.iter_mut().flat_map(|e| e.links.iter_mut()) {
+        if is_short_link(&link.href) {
+            if let Some(expanded) = expand(&link.href).await {
+                link.href = expanded;
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Reads and writes the JSON files the app keeps its state in, in the active
+//! profile.
+
+use crate::profile;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    error::Error,
+    fs::{self, read_to_string},
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+/// The state kept in `file`, or the default when there is none yet. A file
+/// that cannot be read or parsed is logged and taken as empty, so a damaged
+/// file does not keep the app from starting.
+pub fn load<T: DeserializeOwned + Default>(file: &str) -> T {
+    match read_to_string(profile::path(file)) {
+        Ok(data) => parse(file, &data),
+        Err(e) if e.kind() == ErrorKind::NotFound => T::default(),
+        Err(e) => {
+            tracing::warn!("could not read {file}: {e}");
+            T::default()
+        }
+    }
+}
+
+/// Parses the contents of `file`, logging why when they are not valid.
+pub(crate) fn parse<T: DeserializeOwned + Default>(file: &str, data: &str) -> T {
+    serde_json::from_str(data).unwrap_or_else(|e| {
+        tracing::warn!("could not parse {file}, starting it over: {e}");
+        T::default()
+    })
+}
+
+pub fn save<T: Serialize + ?Sized>(file: &str, state: &T) -> Result<(), Box<dyn Error>> {
+    write(&profile::path(file), serde_json::to_string(state)?)?;
+
+    Ok(())
+}
+
+/// Writes `data` to a file next to `path` and then renames it over `path`, so
+/// a crash while writing leaves the previous contents in place.
+pub(crate) fn write(path: &Path, data: impl AsRef<[u8]>) -> io::Result<()> {
+    let mut saving = path.as_os_str().to_owned();
+    saving.push(".tmp");
+    fs::write(&saving, data)?;
+    fs::rename(saving, path)
+}
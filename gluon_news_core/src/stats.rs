@@ -0,0 +1,124 @@
+//! Figures for the statistics dashboard.
+
+use crate::{compat::EntryExt, focus_time::format_minutes, SETTINGS};
+use chrono::{Datelike, NaiveDate, Timelike};
+use chrono_tz::Tz;
+use feed_rs::model::Entry;
+use std::str::FromStr;
+
+const CELL_SIZE: u32 = 18;
+const LABEL_WIDTH: u32 = 36;
+const LABEL_HEIGHT: u32 = 16;
+const DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const BAR_WIDTH: u32 = 36;
+const CHART_HEIGHT: u32 = 100;
+/// Dark blue, the colour of the busiest cell.
+const MAX_COLOUR: (u8, u8, u8) = (0, 0, 139);
+
+/// How many entries were published at each hour of each day of the week, in
+/// the configured time zone, with Monday first. Undated entries are skipped.
+pub fn publication_heatmap(entries: &[(String, Entry)]) -> [[u32; 24]; 7] {
+    let time_zone = Tz::from_str(&SETTINGS.time_zone).unwrap_or(Tz::UTC);
+    let mut heatmap = [[0; 24]; 7];
+
+    for date in entries.iter().filter_map(|(_, e)| e.best_date()) {
+        let date = date.with_timezone(&time_zone);
+        heatmap[date.weekday().num_days_from_monday() as usize][date.hour() as usize] += 1;
+    }
+
+    heatmap
+}
+
+fn colour(count: u32, max: u32) -> String {
+    let ratio = if max == 0 {
+        0.0
+    } else {
+        count as f64 / max as f64
+    };
+    let channel = |target: u8| (255.0 - (255.0 - target as f64) * ratio).round() as u8;
+
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(MAX_COLOUR.0),
+        channel(MAX_COLOUR.1),
+        channel(MAX_COLOUR.2)
+    )
+}
+
+/// Renders the heatmap as an SVG grid going from white for no entries to dark
+/// blue for the busiest day and hour.
+pub fn heatmap_svg(heatmap: &[[u32; 24]; 7]) -> String {
+    let max = heatmap.iter().flatten().copied().max().unwrap_or(0);
+    let width = LABEL_WIDTH + CELL_SIZE * 24;
+    let height = LABEL_HEIGHT + CELL_SIZE * 7;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" font-size=\"10\">"
+    );
+
+    for hour in (0..24).step_by(3) {
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\">{hour:02}</text>",
+            LABEL_WIDTH + CELL_SIZE * hour,
+            LABEL_HEIGHT - 4
+        ));
+    }
+    for (day, hours) in heatmap.iter().enumerate() {
+        let y = LABEL_HEIGHT + CELL_SIZE * day as u32;
+        svg.push_str(&format!(
+            "<text x=\"0\" y=\"{}\">{}</text>",
+            y + CELL_SIZE - 5,
+            DAYS[day]
+        ));
+        for (hour, count) in hours.iter().enumerate() {
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{y}\" width=\"{CELL_SIZE}\" height=\"{CELL_SIZE}\" fill=\"{}\" stroke=\"#ddd\"><title>{} {hour:02}:00: {count}</title></rect>",
+                LABEL_WIDTH + CELL_SIZE * hour as u32,
+                colour(*count, max),
+                DAYS[day]
+            ));
+        }
+    }
+
+    svg.push_str("</svg>");
+
+    svg
+}
+
+/// Renders the minutes read on each day as an SVG bar chart, with the bars
+/// scaled to the busiest day.
+pub fn week_chart_svg(week: &[(NaiveDate, u64)]) -> String {
+    let max = week
+        .iter()
+        .map(|(_, minutes)| *minutes)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let width = BAR_WIDTH * week.len() as u32;
+    let height = CHART_HEIGHT + LABEL_HEIGHT;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" font-size=\"10\">"
+    );
+
+    for (i, (day, minutes)) in week.iter().enumerate() {
+        let x = BAR_WIDTH * i as u32;
+        let bar_height = (CHART_HEIGHT as u64 * minutes / max) as u32;
+        let day_name = DAYS[day.weekday().num_days_from_monday() as usize];
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{bar_height}\" fill=\"{}\"><title>{day_name}: {}</title></rect>",
+            x + 4,
+            CHART_HEIGHT - bar_height,
+            BAR_WIDTH - 8,
+            colour(*minutes as u32, max as u32),
+            format_minutes(*minutes)
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\">{day_name}</text>",
+            x + 8,
+            height - 4
+        ));
+    }
+
+    svg.push_str("</svg>");
+
+    svg
+}
@@ -0,0 +1,85 @@
+//! The days the user read something on, for the read streak.
+
+use crate::{feed_cache, state, SETTINGS};
+use chrono::{Days, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, error::Error, str::FromStr};
+
+const READ_DAYS_FILE: &str = "read_days.json";
+/// Streak lengths, in days, worth a congratulation.
+pub const MILESTONES: [u32; 3] = [7, 30, 100];
+
+/// Today in the configured time zone.
+pub fn today() -> NaiveDate {
+    let time_zone = Tz::from_str(&SETTINGS.time_zone).unwrap_or(Tz::UTC);
+
+    Utc::now().with_timezone(&time_zone).date_naive()
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct ReadDays {
+    days: BTreeSet<NaiveDate>,
+}
+
+impl ReadDays {
+    pub fn load() -> Self {
+        feed_cache::load(|cache| cache.read_days.clone())
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        feed_cache::update(|cache| cache.read_days = self.clone())
+    }
+
+    /// The file the days read on were kept in before the feed cache.
+    pub(crate) fn load_legacy() -> Self {
+        state::load(READ_DAYS_FILE)
+    }
+
+    /// Records that something was read on `day`. Returns false when the day
+    /// was already recorded.
+    pub fn record(&mut self, day: NaiveDate) -> bool {
+        self.days.insert(day)
+    }
+
+    /// The runs of consecutive days read, as their first and last day, oldest
+    /// first.
+    pub fn streaks(&self) -> Vec<(NaiveDate, NaiveDate)> {
+        let mut streaks: Vec<(NaiveDate, NaiveDate)> = Vec::new();
+
+        for &day in &self.days {
+            match streaks.last_mut() {
+                Some((_, last)) if last.checked_add_days(Days::new(1)) == Some(day) => *last = day,
+                _ => streaks.push((day, day)),
+            }
+        }
+
+        streaks
+    }
+
+    /// The days in the streak that ends today, or yesterday when nothing has
+    /// been read yet today.
+    pub fn current_streak(&self, today: NaiveDate) -> u32 {
+        let yesterday = today.checked_sub_days(Days::new(1));
+
+        match self.streaks().last() {
+            Some(&(first, last)) if last == today || Some(last) == yesterday => {
+                streak_len(first, last)
+            }
+            _ => 0,
+        }
+    }
+
+    pub fn longest_streak(&self) -> u32 {
+        self.streaks()
+            .into_iter()
+            .map(|(first, last)| streak_len(first, last))
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+pub fn streak_len(first: NaiveDate, last: NaiveDate) -> u32 {
+    (last - first).num_days() as u32 + 1
+}
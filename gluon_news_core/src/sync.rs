@@ -0,0 +1,171 @@
+//! Two-way sync of subscriptions and read/starred state with a Nextcloud News
+//! server, through its REST API v1.3.
+
+use crate::{compat::EntryExt, config::NextcloudConfig, local_feeds, webfinger, CLIENT};
+use feed_rs::model::Entry;
+use reqwest::{Method, RequestBuilder};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+
+const API_PATH: &str = "index.php/apps/news/api/v1-3";
+
+#[derive(Deserialize)]
+struct Feeds {
+    feeds: Vec<Feed>,
+}
+
+#[derive(Deserialize)]
+struct Feed {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct Items {
+    items: Vec<Item>,
+}
+
+#[derive(Deserialize)]
+struct Item {
+    id: i64,
+    guid: String,
+    url: Option<String>,
+    unread: bool,
+    starred: bool,
+}
+
+/// What changed locally: feeds subscribed to on the server only, and the
+/// entries that the server has read or starred but the app had not.
+#[derive(Default)]
+pub struct SyncReport {
+    pub feeds: Vec<String>,
+    pub read: HashSet<String>,
+    pub starred: HashSet<String>,
+}
+
+fn request(config: &NextcloudConfig, method: Method, path: &str) -> RequestBuilder {
+    CLIENT
+        .request(
+            method,
+            format!("{}/{API_PATH}/{path}", config.url.trim_end_matches('/')),
+        )
+        .header("User-Agent", "gluon_news")
+        .basic_auth(&config.username, Some(&config.password))
+}
+
+fn same_feed(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/') == b.trim_end_matches('/')
+}
+
+async fn sync_feeds(
+    config: &NextcloudConfig,
+    feeds: &[String],
+) -> Result<Vec<String>, reqwest::Error> {
+    let remote: Feeds = request(config, Method::GET, "feeds")
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    for feed in feeds
+        .iter()
+        .filter(|f| !webfinger::is_acct(f) && !local_feeds::is_local(f))
+    {
+        if !remote.feeds.iter().any(|r| same_feed(&r.url, feed)) {
+            request(config, Method::POST, "feeds")
+                .json(&json!({ "url": feed, "folderId": null }))
+                .send()
+                .await?
+                .error_for_status()?;
+        }
+    }
+
+    Ok(remote
+        .feeds
+        .into_iter()
+        .map(|r| r.url)
+        .filter(|url| !feeds.iter().any(|f| same_feed(f, url)))
+        .collect())
+}
+
+async fn put_items(
+    config: &NextcloudConfig,
+    path: &str,
+    ids: &[i64],
+) -> Result<(), reqwest::Error> {
+    if !ids.is_empty() {
+        request(config, Method::PUT, path)
+            .json(&json!({ "itemIds": ids }))
+            .send()
+            .await?
+            .error_for_status()?;
+    }
+
+    Ok(())
+}
+
+/// Pushes local feeds the server lacks and merges read and starred state both
+/// ways. An entry read or starred on either side ends up read or starred on
+/// both, so a conflict always resolves in favour of "read".
+pub async fn sync(
+    config: &NextcloudConfig,
+    feeds: &[String],
+    entries: &[(String, Entry)],
+    read: &HashSet<String>,
+    starred: &HashSet<String>,
+) -> Result<SyncReport, reqwest::Error> {
+    let mut report = SyncReport {
+        feeds: sync_feeds(config, feeds).await?,
+        ..SyncReport::default()
+    };
+    let remote: Items = request(config, Method::GET, "items")
+        .query(&[
+            ("type", "3"),
+            ("id", "0"),
+            ("getRead", "true"),
+            ("batchSize", "-1"),
+        ])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let by_guid: HashMap<&str, &Item> = remote.items.iter().map(|i| (i.guid.as_str(), i)).collect();
+    let by_url: HashMap<&str, &Item> = remote
+        .items
+        .iter()
+        .filter_map(|i| Some((i.url.as_deref()?, i)))
+        .collect();
+    let mut mark_read = Vec::new();
+    let mut mark_starred = Vec::new();
+
+    for (_, entry) in entries {
+        let item = by_guid
+            .get(entry.id.as_str())
+            .or_else(|| entry.best_link().and_then(|link| by_url.get(link)));
+        let Some(item) = item else {
+            continue;
+        };
+
+        match (read.contains(&entry.id), item.unread) {
+            (true, true) => mark_read.push(item.id),
+            (false, false) => {
+                report.read.insert(entry.id.clone());
+            }
+            _ => {}
+        }
+        match (starred.contains(&entry.id), item.starred) {
+            (true, false) => mark_starred.push(item.id),
+            (false, true) => {
+                report.starred.insert(entry.id.clone());
+            }
+            _ => {}
+        }
+    }
+
+    put_items(config, "items/read/multiple", &mark_read).await?;
+    put_items(config, "items/star/multiple", &mark_starred).await?;
+
+    Ok(report)
+}
@@ -0,0 +1,30 @@
+use crate::{feed_cache, state};
+use std::{collections::HashMap, error::Error};
+
+const TAGS_FILE: &str = "tags.json";
+
+pub fn load() -> HashMap<String, Vec<String>> {
+    feed_cache::load(|cache| cache.tags.clone())
+}
+
+pub fn save(tags: &HashMap<String, Vec<String>>) -> Result<(), Box<dyn Error>> {
+    feed_cache::update(|cache| cache.tags = tags.clone())
+}
+
+/// The file the tags were kept in before the feed cache.
+pub(crate) fn load_legacy() -> HashMap<String, Vec<String>> {
+    state::load(TAGS_FILE)
+}
+
+/// Splits a comma separated list of tags, dropping empty and duplicate ones.
+pub fn parse(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+
+    for tag in input.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_owned());
+        }
+    }
+
+    tags
+}
@@ -0,0 +1,78 @@
+//! Records how much data each feed transfers for the entries it has, to spot
+//! the feeds that cost far more than they are worth.
+
+use crate::state;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, error::Error};
+
+const TELEMETRY_FILE: &str = "fetch_telemetry.json";
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+/// The cost of a single fetch of a feed. The size is that of the body after
+/// decompression.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct FetchTelemetry {
+    pub url: String,
+    pub response_bytes: u64,
+    pub entry_count: u32,
+    pub parse_time_ms: u64,
+    pub fetch_time_ms: u64,
+}
+
+impl FetchTelemetry {
+    /// The bytes transferred per entry, or the whole response for a feed
+    /// without entries.
+    pub fn bytes_per_entry(&self) -> u64 {
+        self.response_bytes / self.entry_count.max(1) as u64
+    }
+}
+
+/// The latest fetch of each feed, and the bytes fetched since the file was
+/// created.
+#[derive(Default, Deserialize, Serialize)]
+pub struct Telemetry {
+    feeds: HashMap<String, FetchTelemetry>,
+    total_bytes: u64,
+}
+
+impl Telemetry {
+    pub fn load() -> Self {
+        state::load(TELEMETRY_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        state::save(TELEMETRY_FILE, self)
+    }
+
+    pub fn record(&mut self, telemetry: FetchTelemetry) {
+        self.total_bytes += telemetry.response_bytes;
+        self.feeds.insert(telemetry.url.clone(), telemetry);
+    }
+
+    /// The latest fetch of the feed, if it has been fetched.
+    pub fn get(&self, url: &str) -> Option<&FetchTelemetry> {
+        self.feeds.get(url)
+    }
+
+    /// The latest fetch of every feed, the most bytes per entry first.
+    pub fn latest(&self) -> Vec<&FetchTelemetry> {
+        let mut latest: Vec<&FetchTelemetry> = self.feeds.values().collect();
+        latest.sort_by_key(|t| std::cmp::Reverse(t.bytes_per_entry()));
+
+        latest
+    }
+
+    pub fn total_data_fetched_mb(&self) -> f64 {
+        self.total_bytes as f64 / BYTES_PER_MB
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{bytes} B")
+    } else if (bytes as f64) < BYTES_PER_MB {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / BYTES_PER_MB)
+    }
+}
@@ -0,0 +1,152 @@
+use chrono::Duration;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+
+const XHTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+const VOID_ELEMENTS: [&str; 13] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+lazy_static! {
+    static ref TAG: Regex = Regex::new(r"(?s)<[^>]*>").unwrap();
+    static ref WHITESPACE: Regex = Regex::new(r"\s+").unwrap();
+    static ref UNSAFE_ELEMENT: Regex = Regex::new(
+        r"(?is)<script\b.*?</script\s*>|<style\b.*?</style\s*>|<iframe\b.*?</iframe\s*>|<object\b.*?</object\s*>|<(script|iframe|object|embed)\b[^>]*>"
+    )
+    .unwrap();
+    static ref EVENT_ATTRIBUTE: Regex =
+        Regex::new(r#"(?i)\s+on[a-z]+\s*=\s*("[^"]*"|'[^']*'|[^\s>]+)"#).unwrap();
+    static ref IMG_TAG: Regex = Regex::new(r"(?is)<img\b[^>]*>").unwrap();
+    static ref IMG_SRC: Regex = Regex::new(r"(?i)\bsrc\s*=").unwrap();
+    static ref XMLNS_ATTRIBUTE: Regex =
+        Regex::new(r#"\s+xmlns(:[\w.-]+)?\s*=\s*("[^"]*"|'[^']*')"#).unwrap();
+    static ref PREFIXED_TAG: Regex = Regex::new(r"<(/?)[\w.-]+:([\w.-]+)").unwrap();
+    static ref SELF_CLOSING_TAG: Regex = Regex::new(r"<([A-Za-z][\w-]*)([^<>]*?)\s*/>").unwrap();
+    static ref JAVASCRIPT_URL: Regex =
+        Regex::new(r#"(?i)\b(href|src)\s*=\s*(["']?)\s*javascript:[^"'\s>]*"#).unwrap();
+}
+
+pub fn strip_html(html: &str) -> String {
+    let text = TAG.replace_all(html, " ");
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+
+    WHITESPACE.replace_all(text.trim(), " ").into_owned()
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Removes scripts, embedded objects, event handler attributes and `javascript:` URLs.
+pub fn sanitize_html(html: &str) -> String {
+    let html = UNSAFE_ELEMENT.replace_all(html, "");
+    let html = EVENT_ATTRIBUTE.replace_all(&html, "");
+
+    JAVASCRIPT_URL.replace_all(&html, "$1=$2#").into_owned()
+}
+
+/// Whether the content is Atom XHTML, which always sits in a `div` in the
+/// XHTML namespace. feed_rs reports it as plain HTML.
+pub fn is_xhtml(html: &str) -> bool {
+    html.contains(XHTML_NAMESPACE)
+}
+
+/// Turns XHTML into HTML a browser parses the same way: namespace
+/// declarations and prefixes are dropped, and self-closing tags other than
+/// void elements get a closing tag, since `<div/>` would otherwise stay open.
+pub fn xhtml_to_html(xhtml: &str) -> String {
+    let html = XMLNS_ATTRIBUTE.replace_all(xhtml, "");
+    let html = PREFIXED_TAG.replace_all(&html, "<$1$2");
+
+    SELF_CLOSING_TAG
+        .replace_all(&html, |caps: &Captures| {
+            let (name, attributes) = (&caps[1], &caps[2]);
+            if VOID_ELEMENTS.contains(&name.to_ascii_lowercase().as_str()) {
+                format!("<{name}{attributes}>")
+            } else {
+                format!("<{name}{attributes}></{name}>")
+            }
+        })
+        .into_owned()
+}
+
+/// Defers loading of images: `src` becomes `data-src`, which the page script
+/// turns back into `src` lazily, and `loading="lazy"` is added.
+pub fn lazy_images(html: &str) -> String {
+    IMG_TAG
+        .replace_all(html, |caps: &Captures| {
+            let tag = &caps[0];
+            let lower = tag.to_ascii_lowercase();
+            if lower.contains("data-src") || !IMG_SRC.is_match(tag) {
+                return tag.to_owned();
+            }
+            let tag = IMG_SRC.replace(tag, "data-src=");
+            if lower.contains("loading=") {
+                tag.into_owned()
+            } else {
+                format!("{} loading=\"lazy\"{}", &tag[..4], &tag[4..])
+            }
+        })
+        .into_owned()
+}
+
+/// Shortens the text to at most `max_chars` characters, the last of them an
+/// ellipsis when any were cut.
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().nth(max_chars).is_none() {
+        return text.to_owned();
+    }
+    let Some(kept) = max_chars.checked_sub(1) else {
+        return String::new();
+    };
+    let end = text.char_indices().nth(kept).map_or(text.len(), |(i, _)| i);
+
+    format!("{}…", &text[..end])
+}
+
+/// Formats a polling interval in its largest whole unit, such as "45m" or "2h".
+pub fn format_interval(d: std::time::Duration) -> String {
+    let seconds = d.as_secs();
+
+    if seconds < 3600 {
+        format!("{}m", (seconds / 60).max(1))
+    } else if seconds < 86400 {
+        format!("{}h", seconds / 3600)
+    } else {
+        format!("{}d", seconds / 86400)
+    }
+}
+
+pub fn humanize_duration(d: Duration) -> String {
+    let plural = |n: i64, unit: &str| {
+        if n == 1 {
+            format!("1 {unit} ago")
+        } else {
+            format!("{n} {unit}s ago")
+        }
+    };
+
+    if d.num_minutes() < 1 {
+        String::from("just now")
+    } else if d.num_hours() < 1 {
+        format!("{}m ago", d.num_minutes())
+    } else if d.num_days() < 1 {
+        format!("{}h ago", d.num_hours())
+    } else if d.num_days() < 30 {
+        plural(d.num_days(), "day")
+    } else if d.num_days() < 365 {
+        plural(d.num_days() / 30, "month")
+    } else {
+        plural(d.num_days() / 365, "year")
+    }
+}
@@ -0,0 +1,39 @@
+//! Translates entries through a LibreTranslate instance.
+
+use crate::{config::TranslateConfig, CLIENT};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize)]
+struct Translation {
+    #[serde(rename = "translatedText")]
+    translated_text: Vec<String>,
+}
+
+/// Translates each HTML text into the configured language, detecting the
+/// source language. The results are in the same order as `texts`.
+pub async fn translate(
+    config: &TranslateConfig,
+    texts: &[String],
+) -> Result<Vec<String>, reqwest::Error> {
+    let translation: Translation = CLIENT
+        .post(format!(
+            "{}/translate",
+            config.endpoint.trim_end_matches('/')
+        ))
+        .header("User-Agent", "gluon_news")
+        .json(&json!({
+            "q": texts,
+            "source": "auto",
+            "target": config.target_language,
+            "format": "html",
+            "api_key": config.api_key,
+        }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(translation.translated_text)
+}
@@ -0,0 +1,43 @@
+//! The latest destructive actions, kept so they can be reversed.
+
+use std::collections::VecDeque;
+
+const UNDO_LIMIT: usize = 10;
+
+/// An action as it needs to be known to reverse it.
+pub enum UndoAction {
+    /// The entries marked read, leaving out the ones already read before.
+    MarkAllRead(Vec<String>),
+    MuteDomain(String),
+    /// The entry and where it was in the queue.
+    RemoveFromQueue(String, usize),
+}
+
+impl UndoAction {
+    pub fn description(&self) -> String {
+        match self {
+            UndoAction::MarkAllRead(ids) => format!("Marked {} entries read", ids.len()),
+            UndoAction::MuteDomain(domain) => format!("Muted {domain}"),
+            UndoAction::RemoveFromQueue(_, _) => String::from("Removed from queue"),
+        }
+    }
+}
+
+/// The latest 10 actions, newest last.
+#[derive(Default)]
+pub struct UndoStack {
+    actions: VecDeque<UndoAction>,
+}
+
+impl UndoStack {
+    pub fn push(&mut self, action: UndoAction) {
+        if self.actions.len() == UNDO_LIMIT {
+            self.actions.pop_front();
+        }
+        self.actions.push_back(action);
+    }
+
+    pub fn pop(&mut self) -> Option<UndoAction> {
+        self.actions.pop_back()
+    }
+}
@@ -0,0 +1,77 @@
+//! Checks GitHub for a newer release of Gluon News. The app and this crate
+//! are released together, so this crate's version is the app's.
+
+use crate::CLIENT;
+use serde::Deserialize;
+
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/vascocosta/gluon_news/releases/latest";
+
+#[derive(Clone)]
+pub struct ReleaseInfo {
+    pub version: String,
+    pub release_url: String,
+    pub changelog: String,
+}
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+/// The major, minor and patch numbers of a version such as "v1.2.3",
+/// ignoring any suffix like "-beta". Missing parts are zero, so "1.2" is the
+/// same version as "1.2.0".
+fn version_parts(version: &str) -> [u64; 3] {
+    let mut parts = [0; 3];
+    for (part, number) in parts.iter_mut().zip(
+        version
+            .trim_start_matches('v')
+            .split(['.', '-', '+'])
+            .map_while(|part| part.parse().ok()),
+    ) {
+        *part = number;
+    }
+
+    parts
+}
+
+/// Whether `version` is a later release than `current`, comparing each part
+/// as a number.
+pub fn is_newer(version: &str, current: &str) -> bool {
+    version_parts(version) > version_parts(current)
+}
+
+/// The latest release when it is newer than the running version. Failed
+/// checks are logged and treated as no update.
+pub async fn check_for_update() -> Option<ReleaseInfo> {
+    let response = CLIENT
+        .get(LATEST_RELEASE_URL)
+        .header("User-Agent", "gluon_news")
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+    let release: Release = match response {
+        Ok(response) => match response.json().await {
+            Ok(release) => release,
+            Err(e) => {
+                tracing::warn!("could not read the latest release: {e}");
+                return None;
+            }
+        },
+        Err(e) => {
+            tracing::warn!("could not check for updates: {e}");
+            return None;
+        }
+    };
+
+    is_newer(&release.tag_name, env!("CARGO_PKG_VERSION")).then(|| ReleaseInfo {
+        version: release.tag_name.trim_start_matches('v').to_owned(),
+        release_url: release.html_url,
+        changelog: release.body.unwrap_or_default(),
+    })
+}
@@ -0,0 +1,61 @@
+use crate::{error::WebFingerError, CLIENT};
+use serde::Deserialize;
+
+const ATOM_TYPE: &str = "application/atom+xml";
+
+#[derive(Deserialize)]
+struct Resource {
+    #[serde(default)]
+    links: Vec<Link>,
+}
+
+#[derive(Deserialize)]
+struct Link {
+    #[serde(rename = "type")]
+    media_type: Option<String>,
+    href: Option<String>,
+}
+
+/// Splits an `acct:user@domain` address into its user and domain parts.
+fn parse_acct(acct: &str) -> Option<(&str, &str)> {
+    let (user, domain) = acct.strip_prefix("acct:")?.split_once('@')?;
+
+    if user.is_empty() || domain.is_empty() || domain.contains(['/', '@', '?', '#']) {
+        return None;
+    }
+
+    Some((user, domain))
+}
+
+pub fn is_acct(feed: &str) -> bool {
+    feed.starts_with("acct:")
+}
+
+pub fn is_valid_acct(feed: &str) -> bool {
+    parse_acct(feed).is_some()
+}
+
+/// Looks up the account on its WebFinger endpoint and returns its Atom feed URL.
+pub async fn resolve_webfinger(acct: &str) -> Result<String, WebFingerError> {
+    let (_, domain) =
+        parse_acct(acct).ok_or_else(|| WebFingerError::InvalidAccount(acct.to_owned()))?;
+    let text = CLIENT
+        .get(format!("https://{domain}/.well-known/webfinger"))
+        .query(&[("resource", acct)])
+        .header("User-Agent", "gluon_news")
+        .header("Accept", "application/jrd+json")
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let resource: Resource =
+        serde_json::from_str(&text).map_err(|e| WebFingerError::ParseError(e.to_string()))?;
+
+    resource
+        .links
+        .into_iter()
+        .find(|link| link.media_type.as_deref() == Some(ATOM_TYPE))
+        .and_then(|link| link.href)
+        .ok_or(WebFingerError::NoFeedLink)
+}
@@ -0,0 +1,49 @@
+//! Pushes new entries to a WebSocket endpoint, for tools such as Node-RED or
+//! external dashboards to act on.
+
+use crate::{compat::EntryExt, config::WsConfig, text, SETTINGS};
+use chrono::{DateTime, Utc};
+use feed_rs::model::Entry;
+use futures::SinkExt;
+use serde::Serialize;
+use std::error::Error;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+#[derive(Serialize)]
+struct PushedEntry {
+    feed: String,
+    title: String,
+    link: Option<String>,
+    published: Option<DateTime<Utc>>,
+    summary: String,
+}
+
+/// Sends the entries as a single JSON array, connecting for this push only.
+/// The connection is made with the system resolver and without any proxy, so
+/// nothing is pushed while either is set up.
+pub async fn push_entries(
+    config: &WsConfig,
+    entries: &[(String, Entry)],
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    if let Some(setting) = SETTINGS.routing_setting() {
+        return Err(format!("not pushing, {setting} is set and would be bypassed").into());
+    }
+    let pushed: Vec<PushedEntry> = entries
+        .iter()
+        .map(|(feed, entry)| PushedEntry {
+            feed: feed.clone(),
+            title: entry.best_title(),
+            link: entry.best_link().map(str::to_owned),
+            published: entry.best_date(),
+            summary: text::strip_html(&entry.best_summary()),
+        })
+        .collect();
+    let (mut socket, _) = connect_async(config.url.as_str()).await?;
+
+    socket
+        .send(Message::Text(serde_json::to_string(&pushed)?))
+        .await?;
+    socket.close(None).await?;
+
+    Ok(())
+}
@@ -0,0 +1,243 @@
+//! Test utilities shared by the integration tests: a small mock HTTP server
+//! that serves the feeds in `tests/fixtures`, plus per process setup.
+#![allow(dead_code)]
+
+use std::{
+    collections::HashMap,
+    env, fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    process,
+    sync::{Arc, Mutex, Once},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::MutexGuard,
+};
+
+static SETUP: Once = Once::new();
+static FETCH_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Moves the test process into a scratch directory, so state files such as
+/// rate_limits.json and the image cache never touch the working tree, and
+/// serializes the tests that go through that shared state.
+pub async fn setup() -> MutexGuard<'static, ()> {
+    SETUP.call_once(|| {
+        let dir = env::temp_dir().join(format!("gluon_news_tests_{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        env::set_current_dir(&dir).unwrap();
+        env::set_var("XDG_CACHE_HOME", dir.join("cache"));
+    });
+
+    FETCH_LOCK.lock().await
+}
+
+pub fn fixture(name: &str) -> Vec<u8> {
+    fs::read(fixtures_dir().join(name)).unwrap()
+}
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+}
+
+#[derive(Clone)]
+pub struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl MockResponse {
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    fn rss() -> Self {
+        Self::new(200)
+            .header("Content-Type", "application/rss+xml")
+            .body(fixture("rss.xml"))
+    }
+
+    fn atom() -> Self {
+        Self::new(200)
+            .header("Content-Type", "application/atom+xml")
+            .body(fixture("atom.xml"))
+    }
+}
+
+#[derive(Default)]
+struct Route {
+    responses: Vec<MockResponse>,
+    hits: usize,
+}
+
+type Routes = Arc<Mutex<HashMap<String, Route>>>;
+
+/// A mock feed server listening on a random local port.
+///
+/// `new` mounts these routes:
+///
+/// - `/rss.xml` and `/atom.xml` serve the fixtures.
+/// - `/private` answers 401.
+/// - `/limited` answers 429 with a Retry-After of 120 seconds.
+/// - `/flaky` answers 503 twice, then serves the RSS fixture.
+/// - `/broken` answers 200 with a body that is not a feed.
+/// - `/image.png` serves a few bytes of image data.
+pub struct MockFeedServer {
+    address: SocketAddr,
+    routes: Routes,
+}
+
+impl MockFeedServer {
+    pub async fn new() -> Self {
+        let server = Self::empty().await;
+
+        server.mount("/rss.xml", vec![MockResponse::rss()]);
+        server.mount("/atom.xml", vec![MockResponse::atom()]);
+        server.mount("/private", vec![MockResponse::new(401)]);
+        server.mount(
+            "/limited",
+            vec![MockResponse::new(429).header("Retry-After", "120")],
+        );
+        server.mount(
+            "/flaky",
+            vec![
+                MockResponse::new(503),
+                MockResponse::new(503),
+                MockResponse::rss(),
+            ],
+        );
+        server.mount(
+            "/broken",
+            vec![MockResponse::new(200).body("<html>not a feed</html>")],
+        );
+        server.mount(
+            "/image.png",
+            vec![MockResponse::new(200)
+                .header("Content-Type", "image/png")
+                .body(b"\x89PNG\r\n\x1a\n".to_vec())],
+        );
+
+        server
+    }
+
+    /// Starts a server without any routes, every request answers 404.
+    pub async fn empty() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let routes = Routes::default();
+        let accept_routes = Arc::clone(&routes);
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, Arc::clone(&accept_routes)));
+            }
+        });
+
+        Self { address, routes }
+    }
+
+    /// Serves `responses` in order for `path`, repeating the last one once
+    /// they run out.
+    pub fn mount(&self, path: &str, responses: Vec<MockResponse>) {
+        self.routes
+            .lock()
+            .unwrap()
+            .insert(path.to_owned(), Route { responses, hits: 0 });
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{path}", self.address)
+    }
+
+    /// How many requests reached `path` so far.
+    pub fn hits(&self, path: &str) -> usize {
+        self.routes
+            .lock()
+            .unwrap()
+            .get(path)
+            .map_or(0, |route| route.hits)
+    }
+}
+
+fn respond(routes: &Routes, path: &str) -> MockResponse {
+    let mut routes = routes.lock().unwrap();
+    let Some(route) = routes.get_mut(path) else {
+        return MockResponse::new(404);
+    };
+    let index = route.hits.min(route.responses.len().saturating_sub(1));
+    route.hits += 1;
+
+    route
+        .responses
+        .get(index)
+        .cloned()
+        .unwrap_or_else(|| MockResponse::new(404))
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+async fn serve(stream: TcpStream, routes: Routes) {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+
+    if reader.read_line(&mut request_line).await.is_err() {
+        return;
+    }
+    loop {
+        let mut line = String::new();
+        match reader.read_line(&mut line).await {
+            Ok(0) | Err(_) => return,
+            Ok(_) if line.trim().is_empty() => break,
+            Ok(_) => {}
+        }
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let path = path.split('?').next().unwrap_or(path);
+    let response = respond(&routes, path);
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        reason(response.status),
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+
+    let mut stream = reader.into_inner();
+    if stream.write_all(head.as_bytes()).await.is_ok() {
+        stream.write_all(&response.body).await.ok();
+    }
+    stream.shutdown().await.ok();
+}
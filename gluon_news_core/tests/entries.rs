@@ -0,0 +1,204 @@
+//! Tests of the sorting and deduplication of the entries of a refresh.
+
+use chrono::{TimeZone, Utc};
+use feed_rs::model::{Link, Text};
+use gluon_news_core::{diff::deduplicate, sort_newest_first, Entry};
+
+/// Builds entries field by field, starting from `Entry::default()`, which
+/// has no ID, links, title or dates.
+trait EntryBuilder {
+    fn id(self, id: &str) -> Self;
+    fn link(self, href: &str) -> Self;
+    fn title(self, title: &str) -> Self;
+    fn published(self, seconds: i64) -> Self;
+    fn updated(self, seconds: i64) -> Self;
+}
+
+impl EntryBuilder for Entry {
+    fn id(mut self, id: &str) -> Self {
+        self.id = id.to_owned();
+        self
+    }
+
+    fn link(mut self, href: &str) -> Self {
+        self.links.push(Link {
+            href: href.to_owned(),
+            rel: None,
+            media_type: None,
+            href_lang: None,
+            title: None,
+            length: None,
+        });
+        self
+    }
+
+    fn title(mut self, title: &str) -> Self {
+        self.title = Some(Text {
+            content_type: mime::TEXT_PLAIN,
+            src: None,
+            content: title.to_owned(),
+        });
+        self
+    }
+
+    fn published(mut self, seconds: i64) -> Self {
+        self.published = Utc.timestamp_opt(seconds, 0).single();
+        self
+    }
+
+    fn updated(mut self, seconds: i64) -> Self {
+        self.updated = Utc.timestamp_opt(seconds, 0).single();
+        self
+    }
+}
+
+fn in_feed(entries: Vec<Entry>) -> Vec<(String, Entry)> {
+    entries
+        .into_iter()
+        .map(|entry| (String::from("feed"), entry))
+        .collect()
+}
+
+fn ids(entries: &[(String, Entry)]) -> Vec<&str> {
+    entries.iter().map(|e| e.1.id.as_str()).collect()
+}
+
+#[test]
+fn sorting_keeps_the_order_of_entries_with_the_same_date() {
+    let mut entries = in_feed(vec![
+        Entry::default().id("a").published(100),
+        Entry::default().id("b").published(200),
+        Entry::default().id("c").published(100),
+        Entry::default().id("d").published(200),
+    ]);
+
+    sort_newest_first(&mut entries);
+
+    assert_eq!(ids(&entries), ["b", "d", "a", "c"]);
+}
+
+#[test]
+fn sorting_falls_back_to_the_update_date() {
+    let mut entries = in_feed(vec![
+        Entry::default().id("published").published(200),
+        Entry::default().id("undated"),
+        Entry::default().id("updated").updated(300),
+        Entry::default().id("both").published(100).updated(400),
+    ]);
+
+    sort_newest_first(&mut entries);
+
+    assert_eq!(ids(&entries), ["updated", "published", "both", "undated"]);
+}
+
+#[test]
+fn deduplicates_entries_of_the_same_feed_with_the_same_id() {
+    let entries = in_feed(vec![
+        Entry::default()
+            .id("same")
+            .title("One")
+            .link("https://a.example/1"),
+        Entry::default()
+            .id("same")
+            .title("Two")
+            .link("https://a.example/2"),
+    ]);
+
+    let (kept, also_in) = deduplicate(entries, None);
+
+    assert_eq!(ids(&kept), ["same"]);
+    assert_eq!(kept[0].1.title.as_ref().unwrap().content, "One");
+    assert!(also_in.is_empty());
+}
+
+#[test]
+fn keeps_entries_of_different_feeds_with_the_same_id_apart() {
+    let entries = vec![
+        (
+            String::from("first"),
+            Entry::default()
+                .id("1")
+                .title("One")
+                .link("https://a.example/1"),
+        ),
+        (
+            String::from("second"),
+            Entry::default()
+                .id("1")
+                .title("Two")
+                .link("https://b.example/1"),
+        ),
+    ];
+
+    let (kept, also_in) = deduplicate(entries, None);
+
+    assert_eq!(kept.len(), 2);
+    assert!(also_in.is_empty());
+}
+
+#[test]
+fn deduplicates_entries_with_the_same_link() {
+    let entries = in_feed(vec![
+        Entry::default()
+            .id("a")
+            .title("One")
+            .link("https://www.example.com/post/?utm_source=feed")
+            .published(200),
+        Entry::default()
+            .id("b")
+            .title("Two")
+            .link("https://example.com/post#comments")
+            .published(100),
+    ]);
+
+    let (kept, _) = deduplicate(entries, None);
+
+    assert_eq!(ids(&kept), ["a"]);
+    // The copy keeps the earliest publication date of the two.
+    assert_eq!(kept[0].1.published, Utc.timestamp_opt(100, 0).single());
+}
+
+#[test]
+fn keeps_entries_without_id_or_link_apart() {
+    let entries = in_feed(vec![
+        Entry::default().title("One"),
+        Entry::default().title("Two"),
+        Entry::default(),
+        Entry::default(),
+    ]);
+
+    let (kept, _) = deduplicate(entries, None);
+
+    assert_eq!(kept.len(), 4);
+}
+
+#[test]
+fn deduplicates_entries_of_different_feeds_by_title() {
+    let entries = vec![
+        (
+            String::from("a"),
+            Entry::default().title("Breaking: the news!"),
+        ),
+        (
+            String::from("b"),
+            Entry::default().title("breaking the news"),
+        ),
+    ];
+
+    let (kept, also_in) = deduplicate(entries, None);
+
+    assert_eq!(kept.len(), 1);
+    assert_eq!(also_in[&kept[0].1.id], ["b"]);
+}
+
+#[test]
+fn keeps_entries_of_the_same_feed_with_the_same_title() {
+    let entries = in_feed(vec![
+        Entry::default().id("a").title("Update README.md"),
+        Entry::default().id("b").title("Update README.md"),
+    ]);
+
+    let (kept, _) = deduplicate(entries, None);
+
+    assert_eq!(ids(&kept), ["a", "b"]);
+}
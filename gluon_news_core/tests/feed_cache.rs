@@ -0,0 +1,55 @@
+//! Tests of the move of the reading state into the feed cache.
+
+mod common;
+
+use gluon_news_core::{bookmarks, feed_cache::FeedCache, read_state, tags};
+use std::{collections::HashSet, fs};
+
+#[tokio::test]
+async fn migrates_the_files_of_each_feature_on_first_open() {
+    let _guard = common::setup().await;
+    fs::remove_file("feed_cache.json").ok();
+    fs::write("read_state.json", r#"["a", "b"]"#).unwrap();
+    fs::write("bookmarks.json", r#"["b"]"#).unwrap();
+    fs::write("tags.json", r#"{"a": ["rust"]}"#).unwrap();
+
+    let cache = FeedCache::open_default().unwrap();
+
+    assert_eq!(
+        *cache.read(),
+        HashSet::from([String::from("a"), String::from("b")])
+    );
+    assert_eq!(*cache.bookmarks(), HashSet::from([String::from("b")]));
+    assert_eq!(cache.tags()["a"], ["rust"]);
+
+    // The old files are only read once: changes go to the cache.
+    let mut read = read_state::load();
+    read.insert(String::from("c"));
+    read_state::save(&read).unwrap();
+    assert_eq!(
+        fs::read_to_string("read_state.json").unwrap(),
+        r#"["a", "b"]"#
+    );
+    assert!(FeedCache::open_default().unwrap().read().contains("c"));
+    assert_eq!(bookmarks::load(), HashSet::from([String::from("b")]));
+    assert_eq!(tags::load()["a"], ["rust"]);
+}
+
+#[tokio::test]
+async fn reads_the_cache_again_once_changed_on_disk() {
+    let _guard = common::setup().await;
+    read_state::save(&HashSet::from([String::from("a")])).unwrap();
+    assert!(read_state::load().contains("a"));
+
+    fs::write("feed_cache.json", r#"{"read": ["b"]}"#).unwrap();
+    // Set apart from the time of the save, which a quick write can share.
+    fs::File::options()
+        .append(true)
+        .open("feed_cache.json")
+        .unwrap()
+        .set_modified(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap();
+
+    assert_eq!(read_state::load(), HashSet::from([String::from("b")]));
+    assert!(!std::path::Path::new("feed_cache.json.tmp").exists());
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::MockFeedServer;
+use gluon_news_core::{error::FeedError, fetch_news, FetchResult};
+use std::time::Duration;
+
+#[tokio::test]
+async fn fetches_rss_and_atom_feeds() {
+    let _guard = common::setup().await;
+    let server = MockFeedServer::new().await;
+    let rss = server.url("/rss.xml");
+    let atom = server.url("/atom.xml");
+
+    let results = fetch_news(&[&rss, &atom]).await.unwrap();
+
+    match &results[..] {
+        [FetchResult::Success(rss_title, rss_entries, hint, _), FetchResult::Success(atom_title, atom_entries, _, _)] =>
+        {
+            assert_eq!(rss_title, "Mock RSS");
+            assert_eq!(rss_entries.len(), 2);
+            assert_eq!(*hint, Some(Duration::from_secs(1800)));
+            assert_eq!(atom_title, "Mock Atom");
+            assert_eq!(atom_entries.len(), 1);
+        }
+        _ => panic!("expected two successful fetches"),
+    }
+}
+
+#[tokio::test]
+async fn reports_auth_errors() {
+    let _guard = common::setup().await;
+    let server = MockFeedServer::new().await;
+    let url = server.url("/private");
+
+    let results = fetch_news(&[&url]).await.unwrap();
+
+    assert!(matches!(
+        &results[..],
+        [FetchResult::Failure(failed, FeedError::AuthError)] if *failed == url
+    ));
+    assert_eq!(server.hits("/private"), 1);
+}
+
+#[tokio::test]
+async fn reports_parse_errors() {
+    let _guard = common::setup().await;
+    let server = MockFeedServer::new().await;
+    let url = server.url("/broken");
+
+    let results = fetch_news(&[&url]).await.unwrap();
+
+    assert!(matches!(
+        &results[..],
+        [FetchResult::Failure(_, FeedError::ParseError(_))]
+    ));
+}
+
+#[tokio::test]
+async fn retries_server_errors() {
+    let _guard = common::setup().await;
+    let server = MockFeedServer::new().await;
+    let url = server.url("/flaky");
+
+    let results = fetch_news(&[&url]).await.unwrap();
+
+    assert!(matches!(&results[..], [FetchResult::Success(..)]));
+    assert_eq!(server.hits("/flaky"), 3);
+}
+
+#[tokio::test]
+async fn remembers_rate_limits() {
+    let _guard = common::setup().await;
+    let server = MockFeedServer::new().await;
+    let url = server.url("/limited");
+
+    let first = fetch_news(&[&url]).await.unwrap();
+    let second = fetch_news(&[&url]).await.unwrap();
+
+    assert!(matches!(
+        &first[..],
+        [FetchResult::Failure(_, FeedError::RateLimited(120))]
+    ));
+    assert!(matches!(
+        &second[..],
+        [FetchResult::Failure(_, FeedError::RateLimited(seconds))] if *seconds <= 120
+    ));
+    assert_eq!(server.hits("/limited"), 1);
+}
+
+#[tokio::test]
+async fn rejects_empty_feed_lists() {
+    assert!(fetch_news(&[]).await.is_err());
+}
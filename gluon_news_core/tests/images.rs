@@ -0,0 +1,79 @@
+mod common;
+
+use common::MockFeedServer;
+use gluon_news_core::{images, sanitizer, CLIENT};
+use url::Url;
+
+/// The local files the images in the HTML point to.
+fn image_files(html: &str) -> Vec<std::path::PathBuf> {
+    images::image_sources(html)
+        .iter()
+        .filter_map(|src| Url::parse(src).ok()?.to_file_path().ok())
+        .collect()
+}
+
+#[tokio::test]
+async fn caches_downloaded_images() {
+    let _guard = common::setup().await;
+    let server = MockFeedServer::new().await;
+    let html = format!(r#"<p><img src="{}"></p>"#, server.url("/image.png"));
+
+    let first = images::download_images(&CLIENT, &html, images::DEFAULT_MAX_CACHE_SIZE_MB).await;
+    let second = images::download_images(&CLIENT, &html, images::DEFAULT_MAX_CACHE_SIZE_MB).await;
+
+    assert_ne!(first, html);
+    assert_eq!(first, second);
+    let files = image_files(&first);
+    assert_eq!(files.len(), 1);
+    assert!(files[0].starts_with(images::cache_dir()));
+    assert!(files[0].is_file());
+    assert_eq!(server.hits("/image.png"), 1);
+}
+
+#[tokio::test]
+async fn never_evicts_the_images_just_returned() {
+    let _guard = common::setup().await;
+    let server = MockFeedServer::new().await;
+    let html = format!(r#"<img src="{}">"#, server.url("/image.png"));
+
+    // Nothing fits in a cache of 0 MB, but the images in use are kept.
+    let result = images::download_images(&CLIENT, &html, 0).await;
+
+    let files = image_files(&result);
+    assert_eq!(files.len(), 1);
+    assert!(files[0].is_file());
+}
+
+#[tokio::test]
+async fn keeps_the_cached_sources_of_sanitized_html() {
+    let _guard = common::setup().await;
+    let server = MockFeedServer::new().await;
+    let html = format!(
+        r#"<p onclick="alert(1)"><img src="{}"></p>"#,
+        server.url("/image.png")
+    );
+
+    // The content pane sanitizes before downloading, as the sanitizer only
+    // keeps web URLs.
+    let result = images::download_images(
+        &CLIENT,
+        &sanitizer::sanitize(&html),
+        images::DEFAULT_MAX_CACHE_SIZE_MB,
+    )
+    .await;
+
+    let files = image_files(&result);
+    assert_eq!(files.len(), 1);
+    assert!(files[0].is_file());
+}
+
+#[tokio::test]
+async fn keeps_images_that_fail_to_download() {
+    let _guard = common::setup().await;
+    let server = MockFeedServer::empty().await;
+    let html = format!(r#"<img src="{}">"#, server.url("/missing.png"));
+
+    let result = images::download_images(&CLIENT, &html, images::DEFAULT_MAX_CACHE_SIZE_MB).await;
+
+    assert_eq!(result, html);
+}
@@ -0,0 +1,35 @@
+//! Tests of the upgrades of settings.json to the current schema.
+
+use gluon_news_core::migration::{self, CURRENT_VERSION};
+use serde_json::Value;
+
+#[test]
+fn stamps_unversioned_settings_keeping_the_order_of_their_keys() {
+    let settings: Value =
+        serde_json::from_str(r#"{"time_zone": "UTC", "feeds": ["https://a.example/feed"]}"#)
+            .unwrap();
+
+    let migrated = migration::migrate_settings(settings.clone(), migration::version(&settings));
+
+    assert_eq!(migration::version(&settings), 0);
+    assert_eq!(migration::version(&migrated), CURRENT_VERSION);
+    assert_eq!(
+        serde_json::to_string(&migrated).unwrap(),
+        format!(
+            r#"{{"time_zone":"UTC","feeds":["https://a.example/feed"],"version":{CURRENT_VERSION}}}"#
+        )
+    );
+}
+
+#[test]
+fn leaves_current_settings_as_they_are() {
+    let settings: Value = serde_json::from_str(&format!(
+        r#"{{"version": {CURRENT_VERSION}, "feeds": [], "theme": "dark"}}"#
+    ))
+    .unwrap();
+
+    assert_eq!(
+        migration::migrate_settings(settings.clone(), CURRENT_VERSION),
+        settings
+    );
+}
@@ -0,0 +1,155 @@
+//! Property-based tests of the sorting, deduplication and text helpers.
+
+use chrono::{TimeZone, Utc};
+use feed_rs::model::Link;
+use gluon_news_core::{
+    diff::{deduplicate, diff_feeds},
+    reading_speed::estimate_reading_time,
+    sort_newest_first,
+    text::{escape_html, format_interval, humanize_duration, strip_html, truncate},
+    Entry,
+};
+use proptest::prelude::*;
+use std::time::Duration;
+
+fn entry(id: String, published: Option<i64>, link: Option<String>) -> Entry {
+    Entry {
+        id,
+        published: published.and_then(|seconds| Utc.timestamp_opt(seconds, 0).single()),
+        links: link
+            .into_iter()
+            .map(|href| Link {
+                href,
+                rel: None,
+                media_type: None,
+                href_lang: None,
+                title: None,
+                length: None,
+            })
+            .collect(),
+        ..Entry::default()
+    }
+}
+
+/// Entries from a few feeds, sharing IDs and links now and then so that some
+/// of them are duplicates.
+fn entries() -> impl Strategy<Value = Vec<(String, Entry)>> {
+    prop::collection::vec(
+        (
+            "feed [a-c]",
+            "[a-e]{1,2}",
+            prop::option::of(0..2_000_000_000i64),
+            prop::option::of("https?://(www\\.)?example\\.(com|org)/[a-c]{0,2}/?"),
+        ),
+        0..20,
+    )
+    .prop_map(|specs| {
+        specs
+            .into_iter()
+            .map(|(feed, id, published, link)| (feed, entry(id, published, link)))
+            .collect()
+    })
+}
+
+fn ids(entries: &[(String, Entry)]) -> Vec<String> {
+    entries.iter().map(|e| e.1.id.clone()).collect()
+}
+
+proptest! {
+    #[test]
+    fn sorting_is_idempotent(mut entries in entries()) {
+        sort_newest_first(&mut entries);
+        let once = ids(&entries);
+        sort_newest_first(&mut entries);
+
+        prop_assert_eq!(ids(&entries), once);
+    }
+
+    #[test]
+    fn sorting_puts_newest_first_and_keeps_every_entry(entries in entries()) {
+        let mut sorted = entries.clone();
+        sort_newest_first(&mut sorted);
+
+        for pair in sorted.windows(2) {
+            prop_assert!(pair[0].1.published.unwrap_or_default() >= pair[1].1.published.unwrap_or_default());
+        }
+        let (mut before, mut after) = (ids(&entries), ids(&sorted));
+        before.sort();
+        after.sort();
+        prop_assert_eq!(before, after);
+    }
+
+    #[test]
+    fn deduplication_never_increases_entry_count(entries in entries()) {
+        let (kept, also_in) = deduplicate(entries.clone(), None);
+
+        prop_assert!(kept.len() <= entries.len());
+        for (feed_title, entry) in &kept {
+            prop_assert!(entries.iter().any(|e| &e.0 == feed_title && e.1.id == entry.id));
+        }
+        prop_assert!(also_in.values().all(|sources| !sources.is_empty()));
+    }
+
+    #[test]
+    fn diffing_accounts_for_every_entry(a in entries(), b in entries()) {
+        let diff = diff_feeds(&a, &b);
+
+        prop_assert_eq!(diff.only_a.len() + diff.both.len(), a.len());
+        prop_assert!(diff.only_b.len() <= b.len());
+        prop_assert!(diff.only_a.len() + diff.both.len() + diff.only_b.len() <= a.len() + b.len());
+    }
+
+    #[test]
+    fn truncation_never_exceeds_the_limit(text in "\\PC*", max_chars in 0usize..50) {
+        let truncated = truncate(&text, max_chars);
+
+        prop_assert!(truncated.chars().count() <= max_chars);
+        if text.chars().count() <= max_chars {
+            prop_assert_eq!(truncated, text);
+        } else {
+            // The ellipsis counts towards the limit.
+            prop_assert!(max_chars == 0 || truncated.ends_with('…'));
+            prop_assert!(text.starts_with(truncated.trim_end_matches('…')));
+        }
+    }
+
+    #[test]
+    fn reading_time_is_at_least_one_minute(words in any::<usize>(), wpm in any::<u32>()) {
+        prop_assert!(estimate_reading_time(words, wpm) >= 1);
+    }
+
+    #[test]
+    fn reading_time_grows_with_word_count(words in 0usize..1_000_000, more in 0usize..1_000_000, wpm in 1u32..2000) {
+        prop_assert!(estimate_reading_time(words + more, wpm) >= estimate_reading_time(words, wpm));
+    }
+
+    #[test]
+    fn stripping_escaped_text_gives_the_text_back(text in "[ a-zA-Z0-9<>&\"';]*") {
+        let expected = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        prop_assert_eq!(strip_html(&escape_html(&text)), expected);
+    }
+
+    #[test]
+    fn escaped_text_has_no_markup(text in "\\PC*") {
+        let escaped = escape_html(&text);
+
+        prop_assert!(!escaped.contains('<') && !escaped.contains('>') && !escaped.contains('"'));
+    }
+
+    #[test]
+    fn intervals_are_formatted_in_a_single_unit(seconds in any::<u32>()) {
+        let formatted = format_interval(Duration::from_secs(seconds as u64));
+        let (number, unit) = formatted.split_at(formatted.len() - 1);
+
+        prop_assert!(["m", "h", "d"].contains(&unit));
+        prop_assert!(number.parse::<u64>().is_ok_and(|n| n >= 1 || unit != "m"));
+    }
+
+    #[test]
+    fn durations_are_humanized_as_the_past(seconds in 0i64..10_000_000_000) {
+        let humanized = humanize_duration(chrono::Duration::seconds(seconds));
+
+        prop_assert!(humanized == "just now" || humanized.ends_with(" ago"));
+    }
+}
@@ -0,0 +1,24 @@
+//! Tests of the JSON files the app state is kept in.
+
+mod common;
+
+use gluon_news_core::state;
+use std::{collections::HashMap, fs, path::Path};
+
+const FILE: &str = "state_test.json";
+
+#[tokio::test]
+async fn starts_a_damaged_file_over_and_replaces_it_whole() {
+    let _guard = common::setup().await;
+    fs::write(FILE, "{\"truncated\": [").unwrap();
+
+    let mut counts: HashMap<String, u32> = state::load(FILE);
+    assert!(counts.is_empty());
+
+    counts.insert("read".to_owned(), 3);
+    state::save(FILE, &counts).unwrap();
+
+    assert_eq!(state::load::<HashMap<String, u32>>(FILE), counts);
+    assert!(!Path::new(&format!("{FILE}.tmp")).exists());
+    fs::remove_file(FILE).unwrap();
+}
@@ -0,0 +1,14 @@
+//! Tests of the comparison of release versions.
+
+use gluon_news_core::update_checker::is_newer;
+
+#[test]
+fn compares_versions_part_by_part_as_numbers() {
+    assert!(is_newer("v0.10.0", "0.9.1"));
+    assert!(is_newer("1.2.10", "1.2.9"));
+    assert!(is_newer("v2", "1.9.9"));
+    assert!(!is_newer("1.2", "1.2.0"));
+    assert!(!is_newer("v1.2.0", "1.2"));
+    assert!(!is_newer("1.3.0-beta", "1.3.0"));
+    assert!(!is_newer("1.2.3", "1.10.0"));
+}
@@ -0,0 +1,34 @@
+// Loads images marked with data-src by the app: natively with loading="lazy"
+// where the webview supports it, otherwise once they scroll into view.
+(function () {
+    var native = "loading" in HTMLImageElement.prototype;
+    var observer = native ? null : new IntersectionObserver(function (entries) {
+        entries.forEach(function (entry) {
+            if (entry.isIntersecting) {
+                observer.unobserve(entry.target);
+                show(entry.target);
+            }
+        });
+    }, { rootMargin: "200px" });
+
+    function show(img) {
+        img.src = img.dataset.src;
+        img.removeAttribute("data-src");
+    }
+
+    function prepare(img) {
+        img.dataset.lazy = "pending";
+        img.addEventListener("load", function () {
+            img.classList.add("loaded");
+        }, { once: true });
+        if (native) {
+            show(img);
+        } else {
+            observer.observe(img);
+        }
+    }
+
+    new MutationObserver(function () {
+        document.querySelectorAll("img[data-src]:not([data-lazy])").forEach(prepare);
+    }).observe(document.documentElement, { childList: true, subtree: true });
+})();
@@ -0,0 +1,143 @@
+//! Shows the unread count on the taskbar or dock icon, using whatever the platform offers.
+
+use dioxus_desktop::DesktopContext;
+
+pub async fn set_unread_count(window: &DesktopContext, count: usize) {
+    platform::set_unread_count(window, count).await;
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use dioxus_desktop::{
+        wry::application::{platform::windows::WindowExtWindows, window::Icon},
+        DesktopContext,
+    };
+
+    const SIZE: usize = 32;
+    const SCALE: usize = 3;
+    const DIGITS: [[u8; 5]; 10] = [
+        [0b111, 0b101, 0b101, 0b101, 0b111],
+        [0b010, 0b110, 0b010, 0b010, 0b111],
+        [0b111, 0b001, 0b111, 0b100, 0b111],
+        [0b111, 0b001, 0b111, 0b001, 0b111],
+        [0b101, 0b101, 0b111, 0b001, 0b001],
+        [0b111, 0b100, 0b111, 0b001, 0b111],
+        [0b111, 0b100, 0b111, 0b101, 0b111],
+        [0b111, 0b001, 0b010, 0b010, 0b010],
+        [0b111, 0b101, 0b111, 0b101, 0b111],
+        [0b111, 0b101, 0b111, 0b001, 0b111],
+    ];
+
+    /// Draws the count, capped at 99, as white digits on a red circle.
+    fn badge_icon(count: usize) -> Option<Icon> {
+        let text = count.min(99).to_string();
+        let width = text.len() * 4 * SCALE - SCALE;
+        let left = (SIZE - width) / 2;
+        let top = (SIZE - 5 * SCALE) / 2;
+        let mut rgba = vec![0u8; SIZE * SIZE * 4];
+
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let dx = x as f32 - SIZE as f32 / 2.0 + 0.5;
+                let dy = y as f32 - SIZE as f32 / 2.0 + 0.5;
+                if dx * dx + dy * dy <= (SIZE * SIZE / 4) as f32 {
+                    rgba[(y * SIZE + x) * 4..][..4].copy_from_slice(&[220, 40, 40, 255]);
+                }
+            }
+        }
+        for (i, digit) in text.bytes().map(|b| (b - b'0') as usize).enumerate() {
+            for (row, bits) in DIGITS[digit].iter().enumerate() {
+                for column in 0..3 {
+                    if bits & (0b100 >> column) == 0 {
+                        continue;
+                    }
+                    for y in 0..SCALE {
+                        for x in 0..SCALE {
+                            let px = left + (i * 4 + column) * SCALE + x;
+                            let py = top + row * SCALE + y;
+                            rgba[(py * SIZE + px) * 4..][..4].copy_from_slice(&[255; 4]);
+                        }
+                    }
+                }
+            }
+        }
+
+        Icon::from_rgba(rgba, SIZE as u32, SIZE as u32).ok()
+    }
+
+    /// tao has no overlay icon API, so the taskbar icon itself is replaced while
+    /// there are unread entries.
+    pub async fn set_unread_count(window: &DesktopContext, count: usize) {
+        let icon = if count == 0 { None } else { badge_icon(count) };
+
+        window.set_taskbar_icon(icon);
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use dioxus_desktop::DesktopContext;
+    use objc::{class, msg_send, runtime::Object, sel, sel_impl};
+
+    const NS_UTF8_STRING_ENCODING: usize = 4;
+
+    pub async fn set_unread_count(_window: &DesktopContext, count: usize) {
+        let label = if count == 0 {
+            String::new()
+        } else {
+            count.to_string()
+        };
+
+        unsafe {
+            let ns_string: *mut Object = msg_send![class!(NSString), alloc];
+            let ns_string: *mut Object = msg_send![ns_string,
+                initWithBytes: label.as_ptr()
+                length: label.len()
+                encoding: NS_UTF8_STRING_ENCODING];
+            let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+            let dock_tile: *mut Object = msg_send![app, dockTile];
+            let _: () = msg_send![dock_tile, setBadgeLabel: ns_string];
+            let _: () = msg_send![ns_string, release];
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use dioxus_desktop::DesktopContext;
+    use tokio::process::Command;
+
+    const DESKTOP_ID: &str = "application://gluon_news.desktop";
+    const OBJECT_PATH: &str = "/com/canonical/unity/launcherentry/gluon_news";
+
+    /// Emits the Unity LauncherEntry signal that GNOME docks, KDE and Unity
+    /// listen to, using gdbus so no D-Bus bindings are needed.
+    pub async fn set_unread_count(_window: &DesktopContext, count: usize) {
+        let properties = format!(
+            "{{'count': <int64 {count}>, 'count-visible': <{}>}}",
+            count > 0
+        );
+        let result = Command::new("gdbus")
+            .args(["emit", "--session", "--object-path", OBJECT_PATH])
+            .args(["--signal", "com.canonical.Unity.LauncherEntry.Update"])
+            .arg(DESKTOP_ID)
+            .arg(properties)
+            .status()
+            .await;
+
+        match result {
+            Ok(status) if !status.success() => {
+                tracing::warn!("could not update launcher badge: gdbus exited with {status}")
+            }
+            Err(e) => tracing::warn!("could not update launcher badge: {e}"),
+            Ok(_) => {}
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod platform {
+    use dioxus_desktop::DesktopContext;
+
+    pub async fn set_unread_count(_window: &DesktopContext, _count: usize) {}
+}
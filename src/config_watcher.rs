@@ -0,0 +1,35 @@
+//! Watches settings.json for changes made while the app runs, such as in a
+//! text editor.
+
+use gluon_news_core::config;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Sends on `changed` whenever settings.json is written. The directory is
+/// watched rather than the file, as many editors save by replacing the file.
+/// Watching stops when the watcher is dropped.
+pub fn watch(changed: UnboundedSender<()>) -> notify::Result<RecommendedWatcher> {
+    let path = config::settings_path();
+    let file_name = path.file_name().map(|name| name.to_owned());
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if (event.kind.is_create() || event.kind.is_modify())
+            && event
+                .paths
+                .iter()
+                .any(|p| p.file_name() == file_name.as_deref())
+        {
+            changed.send(()).ok();
+        }
+    })?;
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+    Ok(watcher)
+}
@@ -0,0 +1,192 @@
+//! The keyboard shortcuts of the main window. The key handler and the keyboard
+//! help both read them from here, so the help always shows the keys in use,
+//! including those changed in `keybindings` in settings.json.
+
+use dioxus::html::input_data::keyboard_types::{Key, Modifiers};
+use gluon_news_core::SETTINGS;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Context {
+    Navigation,
+    EntryActions,
+    ViewModes,
+    Global,
+}
+
+impl Context {
+    pub const ALL: [Context; 4] = [
+        Context::Navigation,
+        Context::EntryActions,
+        Context::ViewModes,
+        Context::Global,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Context::Navigation => "Navigation",
+            Context::EntryActions => "Entry actions",
+            Context::ViewModes => "View modes",
+            Context::Global => "Global",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+    NextEntry,
+    PreviousEntry,
+    NextUnread,
+    ToggleStar,
+    MuteEntry,
+    NotInterested,
+    EditTags,
+    ToggleUnreadOnly,
+    NewWindow,
+    KeyboardHelp,
+}
+
+impl Action {
+    pub const ALL: [Action; 10] = [
+        Action::NextEntry,
+        Action::PreviousEntry,
+        Action::NextUnread,
+        Action::ToggleStar,
+        Action::MuteEntry,
+        Action::NotInterested,
+        Action::EditTags,
+        Action::ToggleUnreadOnly,
+        Action::NewWindow,
+        Action::KeyboardHelp,
+    ];
+
+    /// The name of the action in `keybindings`.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Action::NextEntry => "next_entry",
+            Action::PreviousEntry => "previous_entry",
+            Action::NextUnread => "next_unread",
+            Action::ToggleStar => "toggle_star",
+            Action::MuteEntry => "mute_entry",
+            Action::NotInterested => "not_interested",
+            Action::EditTags => "edit_tags",
+            Action::ToggleUnreadOnly => "toggle_unread_only",
+            Action::NewWindow => "new_window",
+            Action::KeyboardHelp => "keyboard_help",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::NextEntry => "Focus the next entry",
+            Action::PreviousEntry => "Focus the previous entry",
+            Action::NextUnread => "Open the next unread entry",
+            Action::ToggleStar => "Star or unstar the focused entry",
+            Action::MuteEntry => "Mute the focused entry",
+            Action::NotInterested => "Hide the focused entry, as not interested",
+            Action::EditTags => "Edit the tags of the focused entry",
+            Action::ToggleUnreadOnly => "Show only unread entries, or all of them",
+            Action::NewWindow => "Open a new window",
+            Action::KeyboardHelp => "Show the keyboard shortcuts",
+        }
+    }
+
+    pub fn context(&self) -> Context {
+        match self {
+            Action::NextEntry | Action::PreviousEntry | Action::NextUnread => Context::Navigation,
+            Action::ToggleStar | Action::MuteEntry | Action::NotInterested | Action::EditTags => {
+                Context::EntryActions
+            }
+            Action::ToggleUnreadOnly => Context::ViewModes,
+            Action::NewWindow | Action::KeyboardHelp => Context::Global,
+        }
+    }
+
+    /// The key, named as `Key` displays it, when not set in settings.json.
+    fn default_key(&self) -> &'static str {
+        match self {
+            Action::NextEntry => "ArrowDown",
+            Action::PreviousEntry => "ArrowUp",
+            Action::NextUnread => "n",
+            Action::ToggleStar => "s",
+            Action::MuteEntry => "m",
+            Action::NotInterested => "x",
+            Action::EditTags => "t",
+            Action::ToggleUnreadOnly => "u",
+            Action::NewWindow => "t",
+            Action::KeyboardHelp => "?",
+        }
+    }
+}
+
+pub struct KeyBinding {
+    pub action: Action,
+    pub key: String,
+    /// Whether the key is pressed along with Ctrl and Shift.
+    pub ctrl_shift: bool,
+}
+
+impl KeyBinding {
+    /// The keys to press, as shown in the keyboard help.
+    pub fn label(&self) -> String {
+        let key = match self.key.as_str() {
+            "ArrowDown" => "↓",
+            "ArrowUp" => "↑",
+            key => key,
+        };
+
+        if self.ctrl_shift {
+            format!("Ctrl+Shift+{}", key.to_uppercase())
+        } else {
+            key.to_owned()
+        }
+    }
+
+    // Shift is left out of the match for plain keys, as it is how some
+    // characters such as "?" are typed.
+    fn matches(&self, key: &Key, modifiers: Modifiers) -> bool {
+        let pressed = key.to_string();
+
+        if self.ctrl_shift {
+            modifiers.contains(Modifiers::CONTROL | Modifiers::SHIFT)
+                && pressed.eq_ignore_ascii_case(&self.key)
+        } else {
+            !modifiers.intersects(Modifiers::CONTROL | Modifiers::ALT | Modifiers::META)
+                && pressed == self.key
+        }
+    }
+}
+
+fn binding(action: Action, key: Option<&String>) -> KeyBinding {
+    KeyBinding {
+        action,
+        key: key
+            .cloned()
+            .unwrap_or_else(|| action.default_key().to_owned()),
+        ctrl_shift: action == Action::NewWindow,
+    }
+}
+
+/// Every shortcut, with the keys set in settings.json in place of the default
+/// ones.
+pub fn bindings() -> Vec<KeyBinding> {
+    Action::ALL
+        .iter()
+        .map(|&action| binding(action, SETTINGS.keybindings.get(action.id())))
+        .collect()
+}
+
+/// Every shortcut with its default key, for before settings.json exists.
+pub fn default_bindings() -> Vec<KeyBinding> {
+    Action::ALL
+        .iter()
+        .map(|&action| binding(action, None))
+        .collect()
+}
+
+/// The action of the key pressed, if it has one.
+pub fn action(key: &Key, modifiers: Modifiers) -> Option<Action> {
+    bindings()
+        .into_iter()
+        .find(|binding| binding.matches(key, modifiers))
+        .map(|binding| binding.action)
+}
@@ -1,199 +1,4560 @@
 #![allow(non_snake_case)]
 
+mod badge;
+mod config_watcher;
+mod keybindings;
+mod notify;
+mod profile_picker;
+mod tts;
+
+use crate::notify::NotificationBatcher;
 use chrono::{DateTime, Utc};
 use chrono_tz::Tz;
-use dioxus::prelude::*;
-use dioxus_desktop::{Config, WindowBuilder};
-use feed_rs::{
-    model::{Entry, Feed},
-    parser,
+use dioxus::{html::input_data::keyboard_types::Key, prelude::*};
+use dioxus_desktop::{
+    tao::event::{Event, WindowEvent},
+    use_window, use_wry_event_handler, Config, WindowBuilder,
+};
+use gluon_news_core::{
+    archive, bookmarks, browser, bundles, check_proxy_reachable, client_error,
+    collections::{self, ExportFormat},
+    commits,
+    compat::{link_label, EntryExt},
+    config::{
+        self, save_mute_domains, LinkTarget, LogLevel, MergedFeed, NextcloudConfig, Settings,
+        ThemeMode, ToolbarItem,
+    },
+    diff::{diff_feeds, diff_snapshots, merge_feeds, word_diff, SnapshotDiff, WordChange},
+    digest, discovery,
+    dry_run::FetchPlan,
+    error::{FeedBatchError, FeedError, OAuthError},
+    export, feedly, fetch_news_with_progress,
+    fetch_times::{estimate_fetch_duration, FeedStats, FetchTimes},
+    filters::{self, entry_matches, DateFilter},
+    focus_time::{self, format_minutes, FocusTime},
+    force_fetch_feed, full_content,
+    health::{self, HealthReport},
+    images,
+    media::{self, MediaItem, PlaybackProgress},
+    newsletter, oauth, pdf,
+    position::ListPosition,
+    preferences::{MuteHistory, MuteRule, MutedEntry},
+    profile,
+    read_later::ReadLaterQueue,
+    read_state,
+    reading_goal::ReadCounts,
+    reading_hours::{self, ReadingHours},
+    reading_speed::{estimate_reading_time, word_count, ReadingProfile},
+    reminders::{self, Reminder, Reminders, Snooze},
+    reputation::{self, Credibility},
+    sanitizer, search_feeds, search_history, share, sort_newest_first, stats,
+    streak::{self, ReadDays},
+    sync, tags,
+    telemetry::{format_bytes, Telemetry},
+    text::{self, humanize_duration},
+    translate,
+    undo::{UndoAction, UndoStack},
+    update_checker::{check_for_update, ReleaseInfo},
+    websocket, Entry, FetchProgress, News, CLIENT, DEFAULT_POLL_INTERVAL, SETTINGS,
 };
-use futures::future::join_all;
-use lazy_static::lazy_static;
-use reqwest::{Client, Response};
-use serde::Deserialize;
+use keybindings::{Action, Context};
 use std::str::FromStr;
-use std::{error::Error, fs::read_to_string, sync::Arc};
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, HashMap, HashSet},
+    env,
+    hash::{Hash, Hasher},
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    process::{self, Child},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::{broadcast, watch};
+use tracing::Level;
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{Builder, Rotation},
+};
+use tracing_subscriber::{
+    filter::LevelFilter,
+    fmt::{self, format::FmtSpan},
+    prelude::*,
+};
+
+const STALE_AFTER_DAYS: i64 = 90;
+const LOG_DIR: &str = "logs";
+const LOG_FILE_DAYS: usize = 7;
+const DEFAULT_TTS_RATE: f32 = 1.0;
+const NOTICE_DURATION: Duration = Duration::from_secs(2);
+const UNDO_TOAST_DURATION: Duration = Duration::from_secs(5);
+const DEFAULT_NOTIFICATION_BUFFER_HOURS: u32 = 1;
+/// How long to wait for further writes before reloading settings.json, as
+/// editors often save in several steps.
+const SETTINGS_RELOAD_DELAY: Duration = Duration::from_millis(300);
+const DEFAULT_RSVP_WPM: u32 = 300;
+const RSVP_SKIP_WORDS: usize = 10;
+const DEFAULT_CATCH_UP_DAYS: u32 = 7;
+const DEFAULT_READING_WIDTH_PX: u32 = 720;
+const DEFAULT_READING_LINE_HEIGHT: f32 = 1.6;
+const DEFAULT_READING_PARAGRAPH_SPACING_EM: f32 = 1.0;
+const REMINDER_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const REMINDER_TIME_FORMAT: &str = "%a %d %b %H:%M";
+const STREAK_HISTORY_LEN: usize = 10;
+const STREAK_DATE_FORMAT: &str = "%d %b %Y";
+const DEFAULT_SKELETON_COUNT: usize = 10;
+/// How many entries fetched feeds can send ahead of the list showing them.
+const STREAMED_ENTRIES_BUFFER: usize = 256;
+/// The last position of the reading time slider, which stands for no limit.
+const MAX_READING_TIME_FILTER: u32 = 30;
+const PREVIEW_DELAY: Duration = Duration::from_millis(400);
+const PREVIEW_MAX_CHARS: usize = 300;
+const PREVIEW_TIME_FORMAT: &str = "%A %d %B %Y, %H:%M";
+/// How often the entry at the top of the list is noted, to be saved when the
+/// app closes.
+const POSITION_SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+const TOP_ENTRY_SCRIPT: &str = "return [...document.querySelectorAll('li[data-entry-id]')]
+    .find(li => li.getBoundingClientRect().bottom > 0)?.dataset.entryId ?? null;";
+/// The entry at the top of the list when a refresh starts, where it was on
+/// screen, and the entries listed above it.
+const REFRESH_ANCHOR_SCRIPT: &str =
+    "const items = [...document.querySelectorAll('li[data-entry-id]')];
+const top = items.findIndex(li => li.getBoundingClientRect().bottom > 0);
+return top < 0 ? null : {
+    id: items[top].dataset.entryId,
+    offset: items[top].getBoundingClientRect().top,
+    above: items.slice(0, top).map(li => li.dataset.entryId),
+};";
+/// How many more entries are listed each time the end of the list is reached.
+const RIVER_BATCH: usize = 50;
+/// Sends a message whenever the marker at the end of the list comes into view.
+/// The marker is observed again whenever the list changes, so that a marker
+/// still in view after more entries are listed asks for more again.
+const RIVER_SCRIPT: &str = "const observer = new IntersectionObserver(items => {
+    if (items.some(item => item.isIntersecting)) dioxus.send(true);
+});
+new MutationObserver(() => {
+    observer.disconnect();
+    const end = document.querySelector('.river-end');
+    if (end) observer.observe(end);
+}).observe(document.body, { childList: true, subtree: true });";
+/// How often the time spent reading is counted.
+const FOCUS_TIME_INTERVAL: Duration = Duration::from_secs(15);
+/// Notes the time of the latest input in the window.
+const INPUT_TRACKING_SCRIPT: &str = "window.lastInput = Date.now();
+for (const type of ['keydown', 'mousemove', 'mousedown', 'wheel', 'scroll', 'touchstart']) {
+    document.addEventListener(type, () => window.lastInput = Date.now(), { capture: true, passive: true });
+}";
+/// Sends the web links that would open in a new window instead of opening
+/// them, leaving alone the clicks the app handles itself.
+const BROWSER_LINKS_SCRIPT: &str = "document.addEventListener('click', event => {
+    const link = event.target.closest('a[href]');
+    if (!link || event.defaultPrevented || link.target !== '_blank') return;
+    if (!['http:', 'https:'].includes(link.protocol)) return;
+    event.preventDefault();
+    dioxus.send(link.href);
+});";
+
+/// Set once the first window is open. That window fetches the news and runs
+/// what each refresh sets off, such as exports, pushes and notifications, so
+/// they happen once however many windows there are.
+static PRIMARY_WINDOW_OPEN: AtomicBool = AtomicBool::new(false);
+
+/// The new feeds of the `gluon-news://` link the app was opened with, which
+/// the first window offers to add.
+static SHARED_FEEDS: OnceLock<Vec<String>> = OnceLock::new();
 
-lazy_static! {
-    static ref SETTINGS: Arc<Settings> = Arc::new(read_settings().unwrap_or_default());
+/// What the first window got from a refresh, for the other windows to list.
+#[derive(Clone)]
+struct Refresh {
+    news: Result<Arc<News>, Arc<FeedBatchError>>,
+    unsafe_links: HashSet<String>,
 }
 
-#[derive(Deserialize)]
-struct Settings {
-    feeds: Vec<String>,
-    maximized: bool,
-    time_zone: String,
+fn refreshes() -> &'static watch::Sender<Option<Refresh>> {
+    static REFRESHES: OnceLock<watch::Sender<Option<Refresh>>> = OnceLock::new();
+
+    REFRESHES.get_or_init(|| watch::channel(None).0)
+}
+
+/// What another window asks the first one to fetch again with.
+#[derive(Clone)]
+enum WindowRequest {
+    Refresh,
+    AddFeeds(Vec<String>),
+    Merge(MergedFeed),
+    /// The title of a merged feed split again.
+    Split(String),
+}
+
+fn window_requests() -> &'static broadcast::Sender<WindowRequest> {
+    static REQUESTS: OnceLock<broadcast::Sender<WindowRequest>> = OnceLock::new();
+
+    REQUESTS.get_or_init(|| broadcast::channel(16).0)
+}
+
+/// The scripts the first window runs to apply reloaded settings, for the other
+/// windows to run as well.
+fn settings_scripts() -> &'static broadcast::Sender<String> {
+    static SCRIPTS: OnceLock<broadcast::Sender<String>> = OnceLock::new();
+
+    SCRIPTS.get_or_init(|| broadcast::channel(16).0)
+}
+
+#[derive(Clone, PartialEq)]
+enum View {
+    All,
+    Feed(String),
+    Collection(String),
+    Compare(String, String),
+    Merged(Vec<String>),
+    /// A bundle by name, with the titles of the feeds in it.
+    Bundle(String, Vec<String>),
+    /// A media feed's entries as cards with a player, like a podcast app.
+    MediaTimeline(String),
+    Queue,
+    Reminders,
+    Stats,
 }
 
-impl Default for Settings {
-    fn default() -> Self {
-        Self {
-            feeds: vec!["https://github.com/vascocosta/gluon_news/commits.atom".to_owned()],
-            maximized: true,
-            time_zone: "Europe/London".to_owned(),
+impl View {
+    fn key(&self) -> String {
+        match self {
+            View::All => String::from("all"),
+            View::Feed(title) => format!("feed:{title}"),
+            View::Collection(name) => format!("collection:{name}"),
+            View::Compare(a, b) => format!("compare:{a}:{b}"),
+            View::Merged(titles) => format!("merged:{}", titles.join(":")),
+            View::Bundle(name, _) => format!("bundle:{name}"),
+            View::MediaTimeline(title) => format!("media:{title}"),
+            View::Queue => String::from("queue"),
+            View::Reminders => String::from("reminders"),
+            View::Stats => String::from("stats"),
+        }
+    }
+
+    /// Entries folded from several feeds show in the views of each of them,
+    /// `also_in` being the feeds other than the one the entry is listed under.
+    fn shows(
+        &self,
+        feed_title: &str,
+        also_in: &[String],
+        entry_id: &str,
+        collections: &HashMap<String, Vec<String>>,
+    ) -> bool {
+        let from = |title: &String| title == feed_title || also_in.contains(title);
+
+        match self {
+            View::All => true,
+            View::Feed(title) | View::MediaTimeline(title) => from(title),
+            View::Merged(titles) | View::Bundle(_, titles) => titles.iter().any(from),
+            View::Collection(name) => collections
+                .get(name)
+                .is_some_and(|ids| ids.iter().any(|id| id == entry_id)),
+            // The queue view lists the queued entries itself, in queue order.
+            View::Queue => true,
+            View::Compare(_, _) | View::Reminders | View::Stats => false,
         }
     }
 }
 
-#[derive(PartialEq, Props)]
-struct EntryProps {
+#[derive(Props)]
+struct EntryProps<'a> {
+    entry_id: String,
     title: String,
     summary: String,
     link: String,
     category: String,
+    /// The other feeds duplicates of the entry were folded from.
+    also_in: Vec<String>,
+    #[props(!optional)]
+    feed_color: Option<String>,
+    #[props(!optional)]
+    author: Option<String>,
+    #[props(!optional)]
+    avatar: Option<String>,
+    #[props(!optional)]
+    domain: Option<String>,
+    /// "Updated 3h ago" when the entry was updated well after publication.
+    #[props(!optional)]
+    update_badge: Option<String>,
+    credibility: Credibility,
+    published: DateTime<Tz>,
+    reading_time: u32,
+    link_target: LinkTarget,
+    starred: bool,
+    recommended: bool,
+    tags: Vec<String>,
+    /// The entry's categories, with how many entries share each one.
+    categories: Vec<(String, usize)>,
+    #[props(!optional)]
+    archived: Option<String>,
+    /// The links and labels of the entry's Atom `via` source and `related`
+    /// articles.
+    #[props(!optional)]
+    via: Option<(String, String)>,
+    related: Vec<(String, String)>,
+    on_open: EventHandler<'a>,
+    on_category: EventHandler<'a, String>,
+}
+
+#[derive(Props, PartialEq)]
+struct EntryCredibilityProps {
+    credibility: Credibility,
+}
+
+#[derive(Props, PartialEq)]
+struct SkeletonListProps {
+    count: usize,
+}
+
+#[derive(Props, PartialEq)]
+struct RefreshProgressProps {
+    progress: FetchProgress,
+}
+
+#[derive(Props)]
+struct CommitEntryProps<'a> {
+    title: String,
+    message: String,
+    link: String,
+    #[props(!optional)]
+    sha: Option<String>,
+    #[props(!optional)]
+    committer: Option<String>,
+    #[props(!optional)]
+    line_changes: Option<(u32, u32)>,
     published: DateTime<Tz>,
+    link_target: LinkTarget,
+    starred: bool,
+    tags: Vec<String>,
+    #[props(!optional)]
+    archived: Option<String>,
+    on_open: EventHandler<'a>,
+}
+
+#[derive(Props)]
+struct ContentPaneProps<'a> {
+    title: String,
+    content: String,
+    link: String,
+    extracted: bool,
+    on_close: EventHandler<'a>,
+}
+
+#[derive(Props, PartialEq)]
+struct QuickPreviewProps {
+    feed_title: String,
+    summary: String,
+    #[props(!optional)]
+    published: Option<String>,
+}
+
+#[derive(Props)]
+struct MediaCardProps<'a> {
+    entry_id: String,
+    title: String,
+    media: MediaItem,
+    progress: UseRef<PlaybackProgress>,
+    on_open: EventHandler<'a>,
+}
+
+#[derive(Props)]
+struct ImageGalleryProps<'a> {
+    images: Vec<String>,
+    on_open: EventHandler<'a, usize>,
+}
+
+#[derive(Props)]
+struct LightboxProps<'a> {
+    images: Vec<String>,
+    index: usize,
+    on_navigate: EventHandler<'a, usize>,
+    on_close: EventHandler<'a>,
+}
+
+#[derive(Props)]
+struct MuteSuggestionProps<'a> {
+    rule: MuteRule,
+    on_accept: EventHandler<'a, MuteRule>,
+    on_dismiss: EventHandler<'a, MuteRule>,
+}
+
+#[derive(Props)]
+struct CatchUpProps<'a> {
+    days: u32,
+    groups: Vec<(String, Vec<Entry>)>,
+    link_target: LinkTarget,
+    on_mark_read: EventHandler<'a, Vec<String>>,
+    on_close: EventHandler<'a>,
+}
+
+#[derive(Props)]
+struct BulkAddFeedsProps<'a> {
+    /// The feeds already configured, left out of the URLs found.
+    existing: Vec<String>,
+    /// Feeds listed ahead of any pasted, such as those of a shared link.
+    #[props(default)]
+    suggested: Vec<String>,
+    on_add: EventHandler<'a, Vec<String>>,
+    on_close: EventHandler<'a>,
+}
+
+#[derive(Props)]
+struct RsvpReaderProps<'a> {
+    words: Vec<String>,
+    wpm: u32,
+    on_close: EventHandler<'a>,
+}
+
+#[derive(Props)]
+struct SearchDropdownProps<'a> {
+    history: Vec<String>,
+    on_select: EventHandler<'a, String>,
+    on_clear: EventHandler<'a>,
+}
+
+#[derive(Props)]
+struct ToolbarProps<'a> {
+    items: Vec<ToolbarItem>,
+    refresh: Element<'a>,
+    search: Element<'a>,
+    date_filters: Element<'a>,
+    reading_speed: Element<'a>,
+    export: Element<'a>,
+    queue_depth: Element<'a>,
+    read_streak: Element<'a>,
+    data_fetched: Element<'a>,
+    reading_time_filter: Element<'a>,
+    newsletter: Element<'a>,
+    reading_goal: Element<'a>,
+    trackers_blocked: Element<'a>,
+    focus_time: Element<'a>,
+}
+
+#[derive(Props)]
+struct ReminderMenuProps<'a> {
+    on_pick: EventHandler<'a, DateTime<Utc>>,
+    on_cancel: EventHandler<'a>,
+}
+
+#[derive(Props)]
+struct ReminderItemProps<'a> {
+    reminder: Reminder,
+    rescheduling: bool,
+    on_reschedule: EventHandler<'a, String>,
+    on_cancel: EventHandler<'a, String>,
+    on_pick: EventHandler<'a, Reminder>,
+    on_close_menu: EventHandler<'a>,
+}
+
+#[derive(Props)]
+struct KeyboardHelpModalProps<'a> {
+    on_close: EventHandler<'a>,
+}
+
+#[derive(Props)]
+struct ShareQrCodeProps<'a> {
+    feeds: Vec<String>,
+    on_close: EventHandler<'a>,
+}
+
+#[derive(Props)]
+struct FirstRunWizardProps<'a> {
+    on_done: EventHandler<'a>,
+}
+
+#[derive(Props)]
+struct FoldProps<'a> {
+    /// How many entries are below the fold.
+    folded: usize,
+    on_drag_start: EventHandler<'a>,
+}
+
+#[derive(Props)]
+struct SnapshotDiffViewProps<'a> {
+    feed_title: String,
+    diff: SnapshotDiff,
+    on_close: EventHandler<'a>,
+}
+
+#[derive(Props)]
+struct FeedMenuProps<'a> {
+    /// The titles of the feeds this one can be merged with.
+    others: Vec<String>,
+    merged: bool,
+    /// The feeds a force refresh applies to, more than one when this feed is
+    /// selected along with others.
+    refreshing: usize,
+    on_merge: EventHandler<'a, String>,
+    on_split: EventHandler<'a>,
+    on_force_refresh: EventHandler<'a>,
+    on_cancel: EventHandler<'a>,
+}
+
+#[derive(Props)]
+struct CollectionMenuProps<'a> {
+    names: Vec<String>,
+    on_pick: EventHandler<'a, String>,
+    on_cancel: EventHandler<'a>,
 }
 
-fn read_settings() -> Result<Settings, Box<dyn Error>> {
-    let data = read_to_string("settings.json")?;
-    let settings: Settings = serde_json::from_str(&data)?;
+fn show_notification(body: &str) {
+    if let Err(e) = notify::send(body) {
+        tracing::warn!("could not show notification: {e}");
+    }
+}
+
+/// Syncs with Nextcloud News after a refresh and applies what the server had
+/// that the app did not.
+async fn sync_nextcloud(
+    config: &NextcloudConfig,
+    feeds: &[String],
+    news: &News,
+    read: &UseRef<HashSet<String>>,
+    starred: &UseRef<HashSet<String>>,
+    synced_feeds: &UseRef<Vec<String>>,
+) {
+    let (read_ids, starred_ids) = (read.read().clone(), starred.read().clone());
+    let report = match sync::sync(config, feeds, &news.entries, &read_ids, &starred_ids).await {
+        Ok(report) => report,
+        Err(e) => {
+            tracing::warn!("could not sync with Nextcloud News: {e}");
+            return;
+        }
+    };
 
-    Ok(settings)
+    if !report.read.is_empty() {
+        read.write().extend(report.read);
+        if let Err(e) = read_state::save(&read.read()) {
+            tracing::warn!("could not save read state: {e}");
+        }
+    }
+    if !report.starred.is_empty() {
+        starred.write().extend(report.starred);
+        if let Err(e) = bookmarks::save(&starred.read()) {
+            tracing::warn!("could not save bookmarks: {e}");
+        }
+    }
+    if !report.feeds.is_empty() {
+        if let Err(e) = config::add_feeds(&report.feeds) {
+            tracing::warn!("could not save feeds from Nextcloud News: {e}");
+        }
+        synced_feeds.write_silent().extend(report.feeds);
+    }
 }
 
-async fn fetch_news(urls: &[&str]) -> Option<Vec<(String, Entry)>> {
-    let client = Client::new();
-    let mut tasks = Vec::new();
-    for url in urls {
-        tasks.push(tokio::task::spawn(
-            client.get(*url).header("User-Agent", "gluon_news").send(),
+/// Sets the theme and the reading pane typography, loads the configured web
+/// font and sets the font stack used for entry bodies.
+fn custom_head(settings: &Settings) -> String {
+    let mut head = format!(
+        "<style>:root {{ --reading-width: {}px; --reading-line-height: {}; --reading-paragraph-spacing: {}em; }}</style>",
+        settings.reading_pane_max_width_px.unwrap_or(DEFAULT_READING_WIDTH_PX),
+        settings.reading_line_height.unwrap_or(DEFAULT_READING_LINE_HEIGHT),
+        settings
+            .reading_paragraph_spacing_em
+            .unwrap_or(DEFAULT_READING_PARAGRAPH_SPACING_EM)
+    );
+
+    head.push_str(&format!(
+        "<script>window.themeMode = {:?};{}</script>",
+        settings.theme.id(),
+        include_str!("../theme.js")
+    ));
+    if let Some(url) = &settings.content_font_url {
+        head.push_str(&format!(
+            r#"<link rel="stylesheet" href="{}">"#,
+            text::escape_html(url)
         ));
     }
-    let mut outputs = Vec::new();
-    for task in tasks {
-        outputs.push(task.await.unwrap());
+    if let Some(family) = &settings.content_font_family {
+        head.push_str(&format!(
+            "<style>:root {{ --content-font: {}; }}</style>",
+            family.replace('<', "")
+        ));
+    }
+    if settings.lazy_images {
+        head.push_str(&format!(
+            "<script>{}</script>",
+            include_str!("../lazy_images.js")
+        ));
     }
-    let responses: Vec<Response> = outputs.into_iter().filter_map(|r| r.ok()).collect();
-    let texts: Vec<String> = join_all(responses.into_iter().map(|r| r.text()))
-        .await
-        .into_iter()
-        .filter_map(|r| r.ok())
-        .collect();
-    let feeds: Vec<Feed> = texts
-        .into_iter()
-        .filter_map(|t| parser::parse(t.as_bytes()).ok())
-        .collect();
-    let mut entries: Vec<(String, Entry)> = Vec::new();
 
-    for feed in feeds {
-        for entry in feed.entries {
-            let feed_title = match feed.title.clone() {
-                Some(feed_title) => feed_title.content,
-                None => String::from("N/A"),
-            };
-            entries.push((feed_title, entry));
-        }
+    head
+}
+
+/// Entry HTML sanitized, with images proxied when image_proxy is set.
+fn sanitized_html(html: &str) -> String {
+    let html = if text::is_xhtml(html) {
+        sanitizer::sanitize(&text::xhtml_to_html(html))
+    } else {
+        sanitizer::sanitize(html)
+    };
+
+    match &SETTINGS.image_proxy {
+        Some(proxy) => images::proxy_images(&html, proxy),
+        None => html,
     }
+}
 
-    if entries.is_empty() {
-        return None;
+/// Sanitized HTML with its images deferred when lazy_images is set.
+fn deferred_images(html: String) -> String {
+    if SETTINGS.lazy_images {
+        text::lazy_images(&html)
+    } else {
+        html
     }
+}
 
-    entries.sort_by(|a, b| {
-        b.1.published
-            .unwrap_or_default()
-            .cmp(&a.1.published.unwrap_or_default())
+/// Entry HTML as it is put on the page, sanitized, with images proxied when
+/// image_proxy is set and deferred when lazy_images is set.
+fn display_html(html: &str) -> String {
+    deferred_images(sanitized_html(html))
+}
+
+/// Sends the entries to every export target, logging the ones that fail.
+/// Returns how many targets failed.
+async fn export_entries(entries: Vec<(String, Entry)>) -> usize {
+    let results = export::export_all(&SETTINGS.export_targets, &entries).await;
+
+    results
+        .iter()
+        .filter_map(|(target, result)| result.as_ref().err().map(|e| (target, e)))
+        .inspect(|(target, e)| tracing::warn!("could not export to {target}: {e}"))
+        .count()
+}
+
+/// Renders an entry, or a placeholder when rendering it panics, so one
+/// malformed entry cannot take the whole app down.
+fn render_or_placeholder<'a>(
+    cx: &'a ScopeState,
+    title: &str,
+    render: impl FnOnce() -> Element<'a>,
+) -> Element<'a> {
+    match panic::catch_unwind(AssertUnwindSafe(render)) {
+        Ok(element) => element,
+        Err(payload) => {
+            let reason = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            tracing::error!("could not render entry {title:?}: {reason}");
+
+            cx.render(rsx! {
+                div { class: "feed-error", "Failed to render entry {title}" }
+            })
+        }
+    }
+}
+
+/// Worked out from the email on each render rather than cached: hashing an
+/// address costs less than looking its URL up would, and the app keeps no
+/// SQLite database to look it up in.
+fn gravatar_url(email: &str) -> String {
+    let hash = md5::compute(email.trim().to_lowercase());
+
+    format!("https://www.gravatar.com/avatar/{hash:x}?s=32&d=retro")
+}
+
+fn Entry<'a>(cx: Scope<'a, EntryProps<'a>>) -> Element<'a> {
+    let span = tracing::span!(
+        Level::DEBUG,
+        "render_entry",
+        entry_id = cx.props.entry_id.as_str()
+    );
+    let _entered = span.enter();
+    let render_start = Instant::now();
+    // The badge can only show how long the render before this one took.
+    let render_time = use_ref(cx, || None::<Duration>);
+    let render_badge = (SETTINGS.debug && SETTINGS.show_render_timings)
+        .then(|| *render_time.read())
+        .flatten()
+        .map(|elapsed| format!("{:.1}ms", elapsed.as_secs_f64() * 1000.0));
+    let prevent_default = if cx.props.link_target == LinkTarget::ContentPane {
+        "onclick"
+    } else {
+        ""
+    };
+    let feed_style = match &cx.props.feed_color {
+        Some(color) => format!("border-left: 4px solid {color}; padding-left: 5px;"),
+        None => String::new(),
+    };
+    let translation = use_state(cx, || None::<(String, String)>);
+    let show_original = use_state(cx, || false);
+    let translate_status = use_state(cx, || None::<String>);
+    let translate = move || {
+        let Some(config) = &SETTINGS.translate_config else {
+            return;
+        };
+        let texts = vec![cx.props.title.clone(), cx.props.summary.clone()];
+        let translation = translation.clone();
+        let translate_status = translate_status.clone();
+        translate_status.set(Some(String::from("Translating…")));
+        cx.spawn(async move {
+            match translate::translate(config, &texts).await {
+                Ok(translated) if translated.len() == 2 => {
+                    translation.set(Some((
+                        text::strip_html(&translated[0]),
+                        sanitizer::sanitize(&translated[1]),
+                    )));
+                    translate_status.set(None);
+                }
+                Ok(_) => translate_status.set(Some(String::from("Could not translate entry"))),
+                Err(e) => translate_status.set(Some(format!("Could not translate entry: {e}"))),
+            }
+        });
+    };
+    let (title, summary) = match translation.get() {
+        Some((title, summary)) if !*show_original.get() => (title, summary),
+        _ => (&cx.props.title, &cx.props.summary),
+    };
+
+    let element = render_or_placeholder(cx, &cx.props.title, || {
+        cx.render(rsx! {
+            div {
+                a {
+                    href: "{cx.props.link}",
+                    target: cx.props.link_target.target(),
+                    prevent_default: "{prevent_default}",
+                    onclick: move |_| cx.props.on_open.call(()),
+                    if cx.props.starred { "★ " } else { "" }
+                    "{title}",
+                }
+                if let Some(badge) = &render_badge {
+                    rsx! { span { class: "badge", title: "How long the entry took to render last time", "{badge}" } }
+                }
+                if let Some(domain) = &cx.props.domain {
+                    rsx! { span { class: "badge domain", "{domain}" } }
+                }
+                if cx.props.recommended {
+                    rsx! { span { class: "badge recommended", "Recommended" } }
+                }
+                if let Some(badge) = &cx.props.update_badge {
+                    rsx! { span { class: "badge updated", "{badge}" } }
+                }
+            }
+            if let Some((href, source)) = &cx.props.via {
+                rsx! {
+                    div {
+                        class: "via",
+                        "via "
+                        a { href: "{href}", target: cx.props.link_target.target(), "{source}" }
+                    }
+                }
+            }
+            hr {}
+            div {
+                class: "summary",
+                dangerous_inner_html: "{summary}",
+            }
+            if SETTINGS.translate_config.is_some() {
+                rsx! {
+                    div {
+                        if translation.get().is_some() {
+                            rsx! {
+                                button {
+                                    onclick: move |_| show_original.modify(|s| !s),
+                                    if *show_original.get() { "Show translation" } else { "Show original" }
+                                }
+                            }
+                        } else {
+                            rsx! { button { onclick: move |_| translate(), "Translate" } }
+                        }
+                        if let Some(status) = translate_status.get() {
+                            rsx! { span { class: "freshness", " {status}" } }
+                        }
+                    }
+                }
+            }
+            hr {}
+            div {
+                style: "{feed_style}",
+                "{cx.props.category}",
+                EntryCredibility { credibility: cx.props.credibility }
+            }
+            if !cx.props.also_in.is_empty() {
+                let sources = cx.props.also_in.join(", ");
+                rsx! { div { class: "freshness", "Also in {sources}" } }
+            }
+            if let Some(author) = &cx.props.author {
+                rsx! {
+                    div {
+                        class: "author",
+                        if let Some(avatar) = &cx.props.avatar {
+                            rsx! { img { class: "avatar", src: "{avatar}", width: "32", height: "32" } }
+                        }
+                        "{author}"
+                    }
+                }
+            }
+            div {
+                "{cx.props.published}",
+            }
+            div {
+                "{cx.props.reading_time} min read",
+            }
+            if let Some(archived) = &cx.props.archived {
+                rsx! {
+                    div {
+                        a { href: "file://{archived}", target: "_blank", "Open archived version" }
+                    }
+                }
+            }
+            if !cx.props.tags.is_empty() {
+                rsx! {
+                    div {
+                        class: "tags",
+                        for tag in &cx.props.tags {
+                            span { class: "tag", "{tag}" }
+                        }
+                    }
+                }
+            }
+            if !cx.props.categories.is_empty() {
+                rsx! {
+                    div {
+                        for (category, count) in &cx.props.categories {
+                            button {
+                                class: "chip",
+                                onclick: move |_| cx.props.on_category.call(category.clone()),
+                                "{category} ({count})"
+                            }
+                        }
+                    }
+                }
+            }
+            if !cx.props.related.is_empty() {
+                rsx! {
+                    div {
+                        class: "related",
+                        "Related: "
+                        for (href, label) in &cx.props.related {
+                            a { class: "chip", href: "{href}", target: cx.props.link_target.target(), "{label}" }
+                        }
+                    }
+                }
+            }
+        })
     });
+    let elapsed = render_start.elapsed();
+    tracing::debug!(
+        elapsed_ms = elapsed.as_secs_f64() * 1000.0,
+        "rendered entry"
+    );
+    *render_time.write_silent() = Some(elapsed);
+
+    element
+}
 
-    Some(entries)
+/// A shield for entries linking to a trusted domain and a warning for those
+/// linking to a suspicious one, or flagged by Safe Browsing.
+fn EntryCredibility(cx: Scope<EntryCredibilityProps>) -> Element {
+    match cx.props.credibility {
+        Credibility::Trusted => cx.render(rsx! {
+            span { class: "credibility trusted", title: "Trusted source", " 🛡" }
+        }),
+        Credibility::Suspicious => cx.render(rsx! {
+            span { class: "credibility suspicious", title: "Suspicious source", " ⚠" }
+        }),
+        Credibility::Unknown => None,
+    }
 }
 
-fn Entry(cx: Scope<EntryProps>) -> Element {
+/// Placeholders shaped like entries, shown while the feeds load.
+fn SkeletonList(cx: Scope<SkeletonListProps>) -> Element {
     cx.render(rsx! {
-        div {
-            a {
-                href: "{cx.props.link}",
-                target: "_blank",
-                "{cx.props.title}",
+        ul {
+            class: "skeleton-list",
+            for i in 0..cx.props.count {
+                li {
+                    key: "{i}",
+                    class: "skeleton",
+                    div { class: "skeleton-block skeleton-title" }
+                    div { class: "skeleton-block skeleton-line" }
+                    div { class: "skeleton-block skeleton-line skeleton-short" }
+                }
             }
         }
-        hr {}
-        div {
-            class: "summary",
-            dangerous_inner_html: "{cx.props.summary}",
+    })
+}
+
+/// How many feeds of the refresh are done, and which one was done last.
+fn RefreshProgress(cx: Scope<RefreshProgressProps>) -> Element {
+    let FetchProgress {
+        completed,
+        total,
+        latest_feed,
+    } = &cx.props.progress;
+
+    cx.render(rsx! {
+        span {
+            class: "refresh-progress",
+            progress { max: "{total}", value: "{completed}" }
+            span { class: "freshness", " {completed}/{total} feeds, last {latest_feed}" }
         }
-        hr {}
-        div {
-            "{cx.props.category}",
+    })
+}
+
+fn CommitEntry<'a>(cx: Scope<'a, CommitEntryProps<'a>>) -> Element<'a> {
+    let prevent_default = if cx.props.link_target == LinkTarget::ContentPane {
+        "onclick"
+    } else {
+        ""
+    };
+    let label = match &cx.props.sha {
+        Some(sha) => sha.chars().take(7).collect(),
+        None => cx.props.title.clone(),
+    };
+
+    render_or_placeholder(cx, &cx.props.title, || {
+        cx.render(rsx! {
+            div {
+                a {
+                    class: "commit-sha",
+                    href: "{cx.props.link}",
+                    target: cx.props.link_target.target(),
+                    prevent_default: "{prevent_default}",
+                    onclick: move |_| cx.props.on_open.call(()),
+                    if cx.props.starred { "★ " } else { "" }
+                    "{label}",
+                }
+                if let Some((added, removed)) = cx.props.line_changes {
+                    rsx! {
+                        span { class: "badge added", "+{added}" }
+                        span { class: "badge removed", "−{removed}" }
+                    }
+                }
+            }
+            if let Some(committer) = &cx.props.committer {
+                rsx! { div { class: "author", "{committer}" } }
+            }
+            hr {}
+            div {
+                class: "summary commit-message",
+                dangerous_inner_html: "{cx.props.message}",
+            }
+            hr {}
+            div {
+                "{cx.props.published}",
+            }
+            if let Some(archived) = &cx.props.archived {
+                rsx! {
+                    div {
+                        a { href: "file://{archived}", target: "_blank", "Open archived version" }
+                    }
+                }
+            }
+            if !cx.props.tags.is_empty() {
+                rsx! {
+                    div {
+                        class: "tags",
+                        for tag in &cx.props.tags {
+                            span { class: "tag", "{tag}" }
+                        }
+                    }
+                }
+            }
+        })
+    })
+}
+
+fn ContentPane<'a>(cx: Scope<'a, ContentPaneProps<'a>>) -> Element<'a> {
+    let local_content = use_state(cx, || None::<String>);
+    let status = use_state(cx, || None::<String>);
+    let window = use_window(cx);
+    let speech = use_ref(cx, || None::<Child>);
+    let speed_reading = use_state(cx, || false);
+    let lightbox = use_state(cx, || None::<usize>);
+    // The link last found gone, so the answer for the entry shown before is
+    // never shown for this one.
+    let missing = use_state(cx, || None::<String>);
+    let content = local_content.get().as_ref().unwrap_or(&cx.props.content);
+    // The gallery shows the images left by the sanitizer and the tracking
+    // pixel filter, as the pane does.
+    let sanitized = match local_content.get() {
+        Some(local) => local.clone(),
+        None => sanitized_html(&cx.props.content),
+    };
+    let images = images::image_sources(&sanitized);
+    let html = deferred_images(sanitized);
+
+    use_effect(cx, (&cx.props.link,), |(link,)| {
+        let missing = missing.clone();
+        async move {
+            if !link.is_empty() && archive::is_missing(&link).await {
+                missing.set(Some(link));
+            }
+        }
+    });
+    use_on_unmount(cx, {
+        let speech = speech.clone();
+        move || {
+            if let Some(mut child) = speech.write_silent().take() {
+                tts::stop(&mut child);
+            }
         }
+    });
+
+    cx.render(rsx! {
         div {
-            "{cx.props.published}",
+            class: "content-pane",
+            button { onclick: move |_| cx.props.on_close.call(()), "Close" }
+            button {
+                onclick: move |_| {
+                    let content = cx.props.content.clone();
+                    let local_content = local_content.clone();
+                    cx.spawn(async move {
+                        let max_cache_size_mb = SETTINGS.max_cache_size_mb.unwrap_or(images::DEFAULT_MAX_CACHE_SIZE_MB);
+                        // Sanitized first, as the sanitizer drops the file URLs of
+                        // the cached images.
+                        local_content.set(Some(images::download_images(&CLIENT, &sanitized_html(&content), max_cache_size_mb).await));
+                    });
+                },
+                "Download images"
+            }
+            button {
+                onclick: move |_| {
+                    let title = cx.props.title.clone();
+                    let content = cx.props.content.clone();
+                    let status = status.clone();
+                    let window = window.clone();
+                    cx.spawn(async move {
+                        match pdf::save_as_pdf(&title, &content).await {
+                            Ok(path) => status.set(Some(format!("Saved to {}", path.display()))),
+                            Err(e) => {
+                                tracing::warn!("could not save PDF with weasyprint: {e}");
+                                window.print();
+                            }
+                        }
+                    });
+                },
+                "Save as PDF"
+            }
+            button { onclick: move |_| window.print(), "Print" }
+            if speech.read().is_some() {
+                rsx! {
+                    button {
+                        onclick: move |_| {
+                            if let Some(mut child) = speech.write().take() {
+                                tts::stop(&mut child);
+                            }
+                        },
+                        "⏹ Stop"
+                    }
+                }
+            } else {
+                rsx! {
+                    button {
+                        onclick: move |_| {
+                            let text = text::strip_html(content);
+                            let rate = SETTINGS.tts_rate.unwrap_or(DEFAULT_TTS_RATE);
+                            match tts::speak(&text, rate, SETTINGS.tts_voice.as_deref()) {
+                                Ok(child) => speech.set(Some(child)),
+                                Err(e) => status.set(Some(format!("Could not start text-to-speech: {e}"))),
+                            }
+                        },
+                        "🔊 Read aloud"
+                    }
+                }
+            }
+            button { onclick: move |_| speed_reading.set(true), "Speed read" }
+            if *speed_reading.get() {
+                rsx! {
+                    RsvpReader {
+                        words: text::strip_html(content).split_whitespace().map(String::from).collect(),
+                        wpm: SETTINGS.rsvp_wpm.filter(|wpm| *wpm > 0).unwrap_or(DEFAULT_RSVP_WPM),
+                        on_close: move |_| speed_reading.set(false),
+                    }
+                }
+            }
+            if let Some(status) = status.get() {
+                rsx! { div { class: "pane-status", "{status}" } }
+            }
+            h2 {
+                a {
+                    href: "{cx.props.link}",
+                    target: "_blank",
+                    "{cx.props.title}",
+                }
+            }
+            if cx.props.extracted {
+                rsx! { span { class: "badge extracted", "Extracted content" } }
+            }
+            if missing.get().as_ref() == Some(&cx.props.link) {
+                rsx! {
+                    div {
+                        class: "pane-status",
+                        "The page is gone from its site. "
+                        a { href: "{archive::archive_url(&cx.props.link)}", target: "_blank", "Try Archive.org" }
+                    }
+                }
+            }
+            if images.len() > 1 {
+                let images = images.clone();
+                rsx! {
+                    ImageGallery {
+                        images: images,
+                        on_open: move |index| lightbox.set(Some(index)),
+                    }
+                }
+            }
+            if let Some(index) = *lightbox.get() {
+                let images = images.clone();
+                rsx! {
+                    Lightbox {
+                        images: images,
+                        index: index,
+                        on_navigate: move |index| lightbox.set(Some(index)),
+                        on_close: move |_| lightbox.set(None),
+                    }
+                }
+            }
+            hr {}
+            div {
+                class: "summary",
+                dangerous_inner_html: "{html}",
+            }
         }
     })
 }
 
-fn App(cx: Scope) -> Element {
-    let mut count = use_state(cx, || 0);
-    let future = use_future(cx, (count,), |_| async move {
-        let feeds: Vec<&str> = SETTINGS.feeds.iter().map(|f| f.as_str()).collect();
+/// The DOM ID of the player of an entry, as entry IDs are often URLs.
+fn player_id(entry_id: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    entry_id.hash(&mut hasher);
 
-        fetch_news(&feeds).await
-    });
+    format!("player-{:016x}", hasher.finish())
+}
 
-    cx.render(match future.value() {
-        Some(response) => rsx! {
-            style { include_str!("../style.css") }
+fn MediaCard<'a>(cx: Scope<'a, MediaCardProps<'a>>) -> Element<'a> {
+    let playing = use_state(cx, || false);
+    let eval = use_eval(cx);
+    let id = cx.bump().alloc(player_id(&cx.props.entry_id)).as_str();
+    let position = cx.props.progress.read().get(&cx.props.entry_id);
+    let duration = cx.props.media.duration.map(|d| d.as_secs_f64());
+    let played_percent = duration
+        .filter(|d| *d > 0.0)
+        .map_or(0.0, |d| (position / d * 100.0).min(100.0));
+    // Reads where the player is, so playback resumes there next time.
+    let save_position = move || {
+        let Ok(script) = eval(&format!(
+            "return document.getElementById('{id}')?.currentTime ?? 0;"
+        )) else {
+            return;
+        };
+        let progress = cx.props.progress.clone();
+        let entry_id = cx.props.entry_id.clone();
+        cx.spawn(async move {
+            if let Some(seconds) = script.join().await.ok().and_then(|s| s.as_f64()) {
+                progress.write().set(&entry_id, seconds);
+                if let Err(e) = progress.read().save() {
+                    tracing::warn!("could not save playback progress: {e}");
+                }
+            }
+        });
+    };
 
-            match response {
-                Some(entries) => rsx! {
-                    ul {
-                        li {
-                            button {onclick: move |_| {count += 1}, "Refresh"}
-                        }
-                        for e in entries {
-                            li {
-                                Entry {
-                                    title: match e.1.title.clone() {
-                                        Some(title) => title.content,
-                                        None => String::from("N/A"),
-                                    },
-                                    summary: match e.1.summary.clone() {
-                                        Some(summary) => summary.content,
-                                        None => String::from("N/A"),
-                                    },
-                                    link: match e.1.links.get(0) {
-                                        Some(link) => link.href.clone(),
-                                        None => String::from("N/A"),
-                                    },
-                                    category: e.0.chars().take(100).collect::<String>(),
-                                    published: match e.1.published {
-                                        Some(published) => {
-                                            let time_zone = Tz::from_str(&SETTINGS.time_zone).unwrap_or(Tz::UTC);
-                                            published.with_timezone(&time_zone)
-                                        },
-                                        None => Utc::now().with_timezone(&Tz::UTC),
-                                    },
-                                }
+    cx.render(rsx! {
+        div {
+            class: "media-card",
+            if let Some(thumbnail) = &cx.props.media.thumbnail {
+                rsx! { img { class: "media-thumbnail", src: "{thumbnail}" } }
+            } else {
+                rsx! { div { class: "media-thumbnail" } }
+            }
+            div {
+                class: "media-details",
+                a { href: "#", prevent_default: "onclick", onclick: move |_| cx.props.on_open.call(()), "{cx.props.title}" }
+                div {
+                    class: "freshness",
+                    match duration {
+                        Some(duration) => rsx! { "{media::format_duration(position)} / {media::format_duration(duration)}" },
+                        None if position > 0.0 => rsx! { "Played {media::format_duration(position)}" },
+                        None => rsx! { "" },
+                    }
+                }
+                div {
+                    class: "media-progress",
+                    div { class: "media-progress-played", style: "width: {played_percent}%;" }
+                }
+                if *playing.get() {
+                    if cx.props.media.is_video {
+                        rsx! {
+                            video {
+                                id: "{id}",
+                                class: "media-player",
+                                src: "{cx.props.media.url}",
+                                controls: true,
+                                autoplay: true,
+                                onloadedmetadata: move |_| {
+                                    eval(&format!("document.getElementById('{id}').currentTime = {position};")).ok();
+                                },
+                                onpause: move |_| save_position(),
+                                onended: move |_| save_position(),
+                            }
+                        }
+                    } else {
+                        rsx! {
+                            audio {
+                                id: "{id}",
+                                class: "media-player",
+                                src: "{cx.props.media.url}",
+                                controls: true,
+                                autoplay: true,
+                                onloadedmetadata: move |_| {
+                                    eval(&format!("document.getElementById('{id}').currentTime = {position};")).ok();
+                                },
+                                onpause: move |_| save_position(),
+                                onended: move |_| save_position(),
                             }
                         }
                     }
-                },
-                None => rsx! {
-                    ul {
-                        li {
-                            div {"Could not fetch any news. Make sure you have a valid settings.json file."}
+                } else {
+                    rsx! {
+                        button {
+                            class: "media-play",
+                            onclick: move |_| playing.set(true),
+                            if position > 0.0 { "▶ Resume" } else { "▶ Play" }
                         }
                     }
-                },
+                }
             }
-        },
-        None => rsx! { div { "Loading..." } },
+        }
     })
 }
 
-#[tokio::main]
-async fn main() {
-    dioxus_desktop::launch_cfg(
-        App,
-        Config::default().with_window(
-            WindowBuilder::new()
-                .with_title("Gluon News")
-                .with_maximized(SETTINGS.maximized)
-                .with_resizable(true)
-                .with_inner_size(dioxus_desktop::wry::application::dpi::LogicalSize::new(
-                    1000.0, 800.0,
-                )),
-        ),
-    );
+fn ImageGallery<'a>(cx: Scope<'a, ImageGalleryProps<'a>>) -> Element<'a> {
+    cx.render(rsx! {
+        div {
+            class: "gallery",
+            for (i, src) in cx.props.images.iter().enumerate() {
+                img {
+                    key: "{src}",
+                    class: "gallery-thumbnail",
+                    src: "{src}",
+                    onclick: move |_| cx.props.on_open.call(i),
+                }
+            }
+        }
+    })
+}
+
+/// Shows one of the images full size over the app, with arrows to the others.
+fn Lightbox<'a>(cx: Scope<'a, LightboxProps<'a>>) -> Element<'a> {
+    let len = cx.props.images.len();
+    let index = cx.props.index.min(len.saturating_sub(1));
+    let previous = move || cx.props.on_navigate.call((index + len - 1) % len);
+    let next = move || cx.props.on_navigate.call((index + 1) % len);
+    let src = cx.props.images.get(index).map_or("", String::as_str);
+
+    cx.render(rsx! {
+        div {
+            class: "lightbox",
+            tabindex: "0",
+            onmounted: move |evt| {
+                let element = evt.inner().clone();
+                cx.spawn(async move {
+                    element.set_focus(true).await.ok();
+                });
+            },
+            onkeydown: move |evt| {
+                evt.stop_propagation();
+                match evt.key() {
+                    Key::ArrowLeft => previous(),
+                    Key::ArrowRight => next(),
+                    Key::Escape => cx.props.on_close.call(()),
+                    _ => {}
+                }
+            },
+            button { class: "lightbox-close", onclick: move |_| cx.props.on_close.call(()), "✕" }
+            button { class: "lightbox-arrow", onclick: move |_| previous(), "‹" }
+            img { class: "lightbox-image", src: "{src}" }
+            button { class: "lightbox-arrow", onclick: move |_| next(), "›" }
+            div { class: "lightbox-position", "{index + 1} / {len}" }
+        }
+    })
+}
+
+/// Offers a mute rule learned from the entries muted so far.
+fn MuteSuggestion<'a>(cx: Scope<'a, MuteSuggestionProps<'a>>) -> Element<'a> {
+    let rule = &cx.props.rule;
+
+    cx.render(rsx! {
+        span {
+            class: "mute-suggestion",
+            " You might want to mute: {rule.label()} "
+            button { onclick: move |_| cx.props.on_accept.call(rule.clone()), "Mute" }
+            button { onclick: move |_| cx.props.on_dismiss.call(rule.clone()), "Dismiss" }
+        }
+    })
+}
+
+/// A card with the start of an entry, shown over the compact list.
+fn QuickPreview(cx: Scope<QuickPreviewProps>) -> Element {
+    cx.render(rsx! {
+        div {
+            class: "quick-preview",
+            div { class: "freshness", "{cx.props.feed_title}" }
+            if let Some(published) = &cx.props.published {
+                rsx! { div { class: "freshness", "{published}" } }
+            }
+            p { "{cx.props.summary}" }
+        }
+    })
+}
+
+/// Always lists entries compactly, one line each, to fit as much as possible.
+fn CatchUp<'a>(cx: Scope<'a, CatchUpProps<'a>>) -> Element<'a> {
+    let total: usize = cx
+        .props
+        .groups
+        .iter()
+        .map(|(_, entries)| entries.len())
+        .sum();
+    let now = Utc::now();
+    let time_zone = Tz::from_str(&SETTINGS.time_zone).unwrap_or(Tz::UTC);
+    let preview = use_state(cx, || None::<String>);
+    // The preview waiting for the delay to pass, cancelled when the mouse leaves.
+    let pending_preview = use_state(cx, || None::<TaskId>);
+    let cancel_preview = move || {
+        if let Some(task) = *pending_preview.get() {
+            cx.remove_future(task);
+            pending_preview.set(None);
+        }
+    };
+    let schedule_preview = move |entry_id: String| {
+        cancel_preview();
+        let preview = preview.clone();
+        let task = cx.push_future(async move {
+            tokio::time::sleep(PREVIEW_DELAY).await;
+            preview.set(Some(entry_id));
+        });
+        pending_preview.set(Some(task));
+    };
+
+    cx.render(rsx! {
+        div {
+            class: "catch-up",
+            div {
+                class: "catch-up-header",
+                h2 { "{total} unread entries from the last {cx.props.days} days" }
+                button { onclick: move |_| cx.props.on_close.call(()), "Close" }
+            }
+            if cx.props.groups.is_empty() {
+                rsx! { div { class: "search-empty", "You are all caught up" } }
+            }
+            for (feed_title, entries) in &cx.props.groups {
+                div {
+                    class: "catch-up-group",
+                    h3 {
+                        "{feed_title} ({entries.len()})"
+                        button {
+                            onclick: move |_| cx.props.on_mark_read.call(entries.iter().map(|e| e.id.clone()).collect()),
+                            "Mark all read"
+                        }
+                    }
+                    for e in entries {
+                        div {
+                            class: "catch-up-entry",
+                            a {
+                                href: "{e.best_link().unwrap_or_default()}",
+                                target: cx.props.link_target.target(),
+                                onmouseenter: move |_| schedule_preview(e.id.clone()),
+                                onmouseleave: move |_| {
+                                    cancel_preview();
+                                    preview.set(None);
+                                },
+                                "{e.best_title()}"
+                            }
+                            if let Some(date) = e.best_date() {
+                                rsx! { span { class: "freshness", " {humanize_duration(now - date)}" } }
+                            }
+                            if preview.get().as_ref() == Some(&e.id) {
+                                rsx! {
+                                    QuickPreview {
+                                        feed_title: feed_title.clone(),
+                                        summary: text::truncate(&text::strip_html(&e.best_summary()), PREVIEW_MAX_CHARS),
+                                        published: e.best_date().map(|date| date.with_timezone(&time_zone).format(PREVIEW_TIME_FORMAT).to_string()),
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn BulkAddFeeds<'a>(cx: Scope<'a, BulkAddFeedsProps<'a>>) -> Element<'a> {
+    let pasted = use_state(cx, String::new);
+    // The URLs found in the pasted text, with whether each one is checked.
+    let found = use_state(cx, || {
+        cx.props
+            .suggested
+            .iter()
+            .filter(|url| !cx.props.existing.contains(url))
+            .map(|url| (url.clone(), true))
+            .collect::<Vec<_>>()
+    });
+    let checked: Vec<String> = found
+        .get()
+        .iter()
+        .filter(|(_, checked)| *checked)
+        .map(|(url, _)| url.clone())
+        .collect();
+    let checked_count = checked.len();
+
+    cx.render(rsx! {
+        div {
+            class: "catch-up",
+            div {
+                class: "catch-up-header",
+                h2 { "Add feeds" }
+                button { onclick: move |_| cx.props.on_close.call(()), "Close" }
+            }
+            textarea {
+                class: "bulk-add-text",
+                placeholder: "Paste feed URLs, one per line, or an OPML document",
+                value: "{pasted}",
+                oninput: move |evt| {
+                    found.set(
+                        config::find_feed_urls(&evt.value)
+                            .into_iter()
+                            .filter(|url| !cx.props.existing.contains(url))
+                            .map(|url| (url, true))
+                            .collect(),
+                    );
+                    pasted.set(evt.value.clone());
+                },
+                onkeydown: move |evt| evt.stop_propagation(),
+            }
+            if found.get().is_empty() && !pasted.get().trim().is_empty() {
+                rsx! { div { class: "search-empty", "No new feed URLs found" } }
+            }
+            for (i, (url, checked)) in found.get().iter().enumerate() {
+                label {
+                    class: "catch-up-entry",
+                    input {
+                        r#type: "checkbox",
+                        checked: *checked,
+                        onclick: move |_| found.make_mut()[i].1 ^= true,
+                    }
+                    " {url}"
+                }
+            }
+            button {
+                disabled: checked_count == 0,
+                onclick: move |_| cx.props.on_add.call(checked.clone()),
+                "Add {checked_count} feeds"
+            }
+        }
+    })
+}
+
+fn RsvpReader<'a>(cx: Scope<'a, RsvpReaderProps<'a>>) -> Element<'a> {
+    let index = use_state(cx, || 0usize);
+    let paused = use_state(cx, || false);
+    let len = cx.props.words.len();
+    let delay = Duration::from_millis(60_000 / cx.props.wpm as u64);
+
+    use_future(cx, (index, paused), |(index, paused)| async move {
+        if !*paused.get() && *index.get() + 1 < len {
+            tokio::time::sleep(delay).await;
+            index.modify(|i| i + 1);
+        }
+    });
+
+    let back = move || index.modify(|i| i.saturating_sub(RSVP_SKIP_WORDS));
+    let forward = move || index.modify(|i| (i + RSVP_SKIP_WORDS).min(len.saturating_sub(1)));
+    let word = cx.props.words.get(*index.get()).map_or("", String::as_str);
+
+    cx.render(rsx! {
+        div {
+            class: "rsvp",
+            tabindex: "0",
+            onmounted: move |evt| {
+                let element = evt.inner().clone();
+                cx.spawn(async move {
+                    element.set_focus(true).await.ok();
+                });
+            },
+            onkeydown: move |evt| {
+                evt.stop_propagation();
+                match evt.key() {
+                    Key::Character(c) if c == " " => paused.modify(|p| !p),
+                    Key::ArrowLeft => back(),
+                    Key::ArrowRight => forward(),
+                    Key::Escape => cx.props.on_close.call(()),
+                    _ => {}
+                }
+            },
+            div { class: "rsvp-word", "{word}" }
+            div {
+                class: "rsvp-progress",
+                "{(*index.get() + 1).min(len)} / {len} at {cx.props.wpm} wpm"
+            }
+            div {
+                button { onclick: move |_| back(), "Back {RSVP_SKIP_WORDS}" }
+                button {
+                    onclick: move |_| paused.modify(|p| !p),
+                    if *paused.get() { "Resume" } else { "Pause" }
+                }
+                button { onclick: move |_| forward(), "Forward {RSVP_SKIP_WORDS}" }
+                button { onclick: move |_| cx.props.on_close.call(()), "Close" }
+            }
+        }
+    })
+}
+
+fn SearchDropdown<'a>(cx: Scope<'a, SearchDropdownProps<'a>>) -> Element<'a> {
+    cx.render(rsx! {
+        div {
+            class: "search-dropdown",
+            if cx.props.history.is_empty() {
+                rsx! { div { class: "search-empty", "No previous searches" } }
+            }
+            for query in &cx.props.history {
+                div {
+                    class: "search-item",
+                    onclick: move |_| cx.props.on_select.call(query.clone()),
+                    "{query}"
+                }
+            }
+            button { onclick: move |_| cx.props.on_clear.call(()), "Clear history" }
+        }
+    })
+}
+
+fn Toolbar<'a>(cx: Scope<'a, ToolbarProps<'a>>) -> Element<'a> {
+    let element = |item: &ToolbarItem| match item {
+        ToolbarItem::Refresh => &cx.props.refresh,
+        ToolbarItem::Search => &cx.props.search,
+        ToolbarItem::DateFilters => &cx.props.date_filters,
+        ToolbarItem::ReadingSpeed => &cx.props.reading_speed,
+        ToolbarItem::Export => &cx.props.export,
+        ToolbarItem::QueueDepth => &cx.props.queue_depth,
+        ToolbarItem::ReadStreak => &cx.props.read_streak,
+        ToolbarItem::DataFetched => &cx.props.data_fetched,
+        ToolbarItem::ReadingTimeFilter => &cx.props.reading_time_filter,
+        ToolbarItem::Newsletter => &cx.props.newsletter,
+        ToolbarItem::ReadingGoal => &cx.props.reading_goal,
+        ToolbarItem::TrackersBlocked => &cx.props.trackers_blocked,
+        ToolbarItem::FocusTime => &cx.props.focus_time,
+    };
+
+    cx.render(rsx! {
+        div {
+            class: "toolbar",
+            for item in &cx.props.items {
+                element(item)
+            }
+        }
+    })
+}
+
+fn CollectionMenu<'a>(cx: Scope<'a, CollectionMenuProps<'a>>) -> Element<'a> {
+    let new_name = use_state(cx, String::new);
+    let create = move || {
+        let name = new_name.get().trim().to_owned();
+        if !name.is_empty() {
+            cx.props.on_pick.call(name);
+        }
+    };
+
+    cx.render(rsx! {
+        div {
+            class: "collection-menu",
+            div { class: "freshness", "Add to collection" }
+            for name in &cx.props.names {
+                div {
+                    class: "search-item",
+                    onclick: move |_| cx.props.on_pick.call(name.clone()),
+                    "{name}"
+                }
+            }
+            input {
+                placeholder: "New collection",
+                value: "{new_name}",
+                oninput: move |evt| new_name.set(evt.value.clone()),
+                onkeydown: move |evt| {
+                    evt.stop_propagation();
+                    match evt.key() {
+                        Key::Enter => create(),
+                        Key::Escape => cx.props.on_cancel.call(()),
+                        _ => {}
+                    }
+                },
+            }
+            button { onclick: move |_| create(), "Add" }
+            button { onclick: move |_| cx.props.on_cancel.call(()), "Cancel" }
+        }
+    })
+}
+
+/// Every keyboard shortcut of the main window, grouped by context.
+fn KeyboardHelpModal<'a>(cx: Scope<'a, KeyboardHelpModalProps<'a>>) -> Element<'a> {
+    let bindings = keybindings::bindings();
+
+    cx.render(rsx! {
+        div {
+            class: "catch-up",
+            tabindex: "0",
+            onmounted: move |evt| {
+                let element = evt.inner().clone();
+                cx.spawn(async move {
+                    element.set_focus(true).await.ok();
+                });
+            },
+            onkeydown: move |evt| {
+                evt.stop_propagation();
+                if evt.key() == Key::Escape {
+                    cx.props.on_close.call(());
+                }
+            },
+            div {
+                class: "catch-up-header",
+                h2 { "Keyboard shortcuts" }
+                button { onclick: move |_| cx.props.on_close.call(()), "Close" }
+            }
+            for context in Context::ALL {
+                h3 { "{context.label()}" }
+                table {
+                    class: "keyboard-help",
+                    for binding in bindings.iter().filter(|b| b.action.context() == context) {
+                        tr {
+                            td { kbd { "{binding.label()}" } }
+                            td { "{binding.action.description()}" }
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// The subscriptions as a QR code of a `gluon-news://` link, for another
+/// device to scan, with the link itself to copy by hand.
+fn ShareQrCode<'a>(cx: Scope<'a, ShareQrCodeProps<'a>>) -> Element<'a> {
+    let eval = use_eval(cx);
+    let code = share::compact_share_uri(&cx.props.feeds)
+        .and_then(|uri| Ok((share::qr_code_svg(&uri)?, uri)));
+
+    cx.render(rsx! {
+        div {
+            class: "catch-up",
+            tabindex: "0",
+            onmounted: move |evt| {
+                let element = evt.inner().clone();
+                cx.spawn(async move {
+                    element.set_focus(true).await.ok();
+                });
+            },
+            onkeydown: move |evt| {
+                evt.stop_propagation();
+                if evt.key() == Key::Escape {
+                    cx.props.on_close.call(());
+                }
+            },
+            div {
+                class: "catch-up-header",
+                h2 { "Share subscriptions" }
+                button { onclick: move |_| cx.props.on_close.call(()), "Close" }
+            }
+            match code {
+                Ok((svg, uri)) => rsx! {
+                    p { "Scan this code on another device running Gluon News to import your {cx.props.feeds.len()} feeds." }
+                    div { class: "qr-code", dangerous_inner_html: "{svg}" }
+                    input { class: "qr-code-link", readonly: true, value: "{uri}" }
+                    button {
+                        onclick: move |_| {
+                            eval(&format!("navigator.clipboard.writeText({uri:?});")).ok();
+                        },
+                        "Copy link"
+                    }
+                },
+                Err(e) => rsx! { p { class: "notice", "Could not make a QR code of your feeds: {e}" } },
+            }
+        }
+    })
+}
+
+/// The divider between the entries above the fold, shown in full, and those
+/// below it, shown compactly. Dragging it onto another entry moves the fold
+/// there.
+fn Fold<'a>(cx: Scope<'a, FoldProps<'a>>) -> Element<'a> {
+    cx.render(rsx! {
+        li {
+            class: "fold",
+            prevent_default: "onmousedown",
+            onmousedown: move |_| cx.props.on_drag_start.call(()),
+            title: "Drag to move the fold",
+            "{cx.props.folded} more below the fold"
+        }
+    })
+}
+
+/// The word-level changes between two texts, as CSS classes and the words
+/// they apply to.
+fn word_spans(old: &str, new: &str) -> Vec<(&'static str, String)> {
+    word_diff(old, new)
+        .into_iter()
+        .map(|change| match change {
+            WordChange::Same(words) => ("", words),
+            WordChange::Removed(words) => ("word-removed", words),
+            WordChange::Added(words) => ("word-added", words),
+        })
+        .collect()
+}
+
+/// What changed in a feed since the previous refresh, with the changed titles
+/// and summaries diffed word by word.
+fn SnapshotDiffView<'a>(cx: Scope<'a, SnapshotDiffViewProps<'a>>) -> Element<'a> {
+    let SnapshotDiff {
+        added,
+        changed,
+        removed,
+    } = &cx.props.diff;
+
+    cx.render(rsx! {
+        div {
+            class: "catch-up",
+            div {
+                class: "catch-up-header",
+                h2 { "Changes in {cx.props.feed_title}" }
+                button { onclick: move |_| cx.props.on_close.call(()), "Close" }
+            }
+            if !added.is_empty() {
+                rsx! {
+                    h3 { "New" }
+                    for entry in added {
+                        div { class: "snapshot-entry word-added", "{entry.best_title()}" }
+                    }
+                }
+            }
+            if !changed.is_empty() {
+                rsx! {
+                    h3 { "Changed" }
+                    for (old, new) in changed {
+                        div {
+                            class: "snapshot-entry",
+                            h4 {
+                                for (class, words) in word_spans(&old.best_title(), &new.best_title()) {
+                                    span { class: "{class}", "{words}" }
+                                }
+                            }
+                            div {
+                                for (class, words) in word_spans(&old.best_summary(), &new.best_summary()) {
+                                    span { class: "{class}", "{words}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if !removed.is_empty() {
+                rsx! {
+                    h3 { "Removed" }
+                    for entry in removed {
+                        div { class: "snapshot-entry word-removed", "{entry.best_title()}" }
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn FeedMenu<'a>(cx: Scope<'a, FeedMenuProps<'a>>) -> Element<'a> {
+    let picking = use_state(cx, || false);
+
+    cx.render(rsx! {
+        div {
+            class: "collection-menu",
+            if *picking.get() {
+                rsx! {
+                    div { class: "freshness", "Merge with…" }
+                    for other in &cx.props.others {
+                        div {
+                            class: "search-item",
+                            onclick: move |_| cx.props.on_merge.call(other.clone()),
+                            "{other}"
+                        }
+                    }
+                }
+            } else {
+                rsx! {
+                    div {
+                        class: "search-item",
+                        onclick: move |_| picking.set(true),
+                        "Merge with…"
+                    }
+                    if cx.props.merged {
+                        rsx! {
+                            div {
+                                class: "search-item",
+                                onclick: move |_| cx.props.on_split.call(()),
+                                "Split"
+                            }
+                        }
+                    }
+                    div {
+                        class: "search-item",
+                        title: "Fetch again past any cached copy",
+                        onclick: move |_| cx.props.on_force_refresh.call(()),
+                        if cx.props.refreshing > 1 {
+                            rsx! { "Force refresh {cx.props.refreshing} selected" }
+                        } else {
+                            rsx! { "Force refresh" }
+                        }
+                    }
+                }
+            }
+            button { onclick: move |_| cx.props.on_cancel.call(()), "Cancel" }
+        }
+    })
+}
+
+fn ReminderMenu<'a>(cx: Scope<'a, ReminderMenuProps<'a>>) -> Element<'a> {
+    let custom = use_state(cx, String::new);
+    let time_zone = Tz::from_str(&SETTINGS.time_zone).unwrap_or(Tz::UTC);
+    let pick_custom = move || {
+        if let Some(at) = reminders::parse_local(custom.get(), time_zone) {
+            cx.props.on_pick.call(at);
+        }
+    };
+
+    cx.render(rsx! {
+        div {
+            class: "collection-menu",
+            div { class: "freshness", "Remind me" }
+            for snooze in Snooze::ALL {
+                div {
+                    class: "search-item",
+                    onclick: move |_| cx.props.on_pick.call(snooze.target(Utc::now(), time_zone)),
+                    "{snooze.label()}"
+                }
+            }
+            input {
+                r#type: "datetime-local",
+                value: "{custom}",
+                oninput: move |evt| custom.set(evt.value.clone()),
+                onkeydown: move |evt| {
+                    evt.stop_propagation();
+                    match evt.key() {
+                        Key::Enter => pick_custom(),
+                        Key::Escape => cx.props.on_cancel.call(()),
+                        _ => {}
+                    }
+                },
+            }
+            button { onclick: move |_| pick_custom(), "Custom" }
+            button { onclick: move |_| cx.props.on_cancel.call(()), "Cancel" }
+        }
+    })
+}
+
+fn ReminderItem<'a>(cx: Scope<'a, ReminderItemProps<'a>>) -> Element<'a> {
+    let reminder = &cx.props.reminder;
+    let time_zone = Tz::from_str(&SETTINGS.time_zone).unwrap_or(Tz::UTC);
+
+    cx.render(rsx! {
+        div {
+            class: "reminder",
+            if let Some(link) = &reminder.link {
+                rsx! { a { href: "{link}", target: "_blank", "{reminder.title}" } }
+            } else {
+                rsx! { span { "{reminder.title}" } }
+            }
+            span { class: "freshness", " {reminder.at.with_timezone(&time_zone).format(REMINDER_TIME_FORMAT)} " }
+            button {
+                onclick: move |_| cx.props.on_reschedule.call(reminder.entry_id.clone()),
+                "Reschedule"
+            }
+            button {
+                onclick: move |_| cx.props.on_cancel.call(reminder.entry_id.clone()),
+                "Cancel"
+            }
+            if cx.props.rescheduling {
+                rsx! {
+                    ReminderMenu {
+                        on_pick: move |at| cx.props.on_pick.call(Reminder { at, ..reminder.clone() }),
+                        on_cancel: move |_| cx.props.on_close_menu.call(()),
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum WizardStep {
+    Feeds,
+    Theme,
+    Window,
+    Shortcuts,
+}
+
+impl WizardStep {
+    const ALL: [WizardStep; 4] = [
+        WizardStep::Feeds,
+        WizardStep::Theme,
+        WizardStep::Window,
+        WizardStep::Shortcuts,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            WizardStep::Feeds => "Add your first feeds",
+            WizardStep::Theme => "Choose a theme",
+            WizardStep::Window => "Window and time zone",
+            WizardStep::Shortcuts => "Keyboard shortcuts",
+        }
+    }
+}
+
+/// Walks a new user through the settings that matter most, then writes
+/// settings.json with them. Nothing here may read `SETTINGS`, which is only
+/// loaded once settings.json exists.
+fn FirstRunWizard<'a>(cx: Scope<'a, FirstRunWizardProps<'a>>) -> Element<'a> {
+    let step = use_state(cx, || 0usize);
+    let feeds = use_state(cx, Vec::<String>::new);
+    let address = use_state(cx, String::new);
+    let status = use_state(cx, || None::<String>);
+    let theme = use_state(cx, ThemeMode::default);
+    let maximized = use_state(cx, || false);
+    let time_zone = use_state(cx, || String::from("UTC"));
+    let window = use_window(cx);
+    let eval = use_eval(cx);
+    let current = WizardStep::ALL[*step.get()];
+    let last = *step.get() == WizardStep::ALL.len() - 1;
+    let discover = move || {
+        let query = address.get().trim().to_owned();
+        if query.is_empty() {
+            return;
+        }
+        let (feeds, address, status) = (feeds.clone(), address.clone(), status.clone());
+        status.set(Some(format!("Looking for feeds at {query}…")));
+        cx.spawn(async move {
+            match discovery::discover_feeds(&query).await {
+                Ok(found) if found.is_empty() => {
+                    status.set(Some(format!("No feeds found at {query}")));
+                }
+                Ok(found) => {
+                    status.set(None);
+                    address.set(String::new());
+                    let mut feeds = feeds.make_mut();
+                    for url in found {
+                        if !feeds.contains(&url) {
+                            feeds.push(url);
+                        }
+                    }
+                }
+                Err(e) => status.set(Some(format!("Could not look for feeds at {query}: {e}"))),
+            }
+        });
+    };
+    let choose_theme = move |mode: ThemeMode| {
+        theme.set(mode);
+        eval(&format!(
+            "window.themeMode = {:?}; window.applyTheme();",
+            mode.id()
+        ))
+        .ok();
+    };
+    let finish = move || {
+        if Tz::from_str(time_zone.get()).is_err() {
+            status.set(Some(format!("{} is not a time zone", time_zone.get())));
+            step.set(
+                WizardStep::ALL
+                    .iter()
+                    .position(|s| *s == WizardStep::Window)
+                    .unwrap_or_default(),
+            );
+            return;
+        }
+        match config::create_settings(feeds.get(), *theme.get(), *maximized.get(), time_zone.get())
+        {
+            Ok(()) => {
+                window.set_maximized(*maximized.get());
+                cx.props.on_done.call(());
+            }
+            Err(e) => status.set(Some(format!("Could not save settings.json: {e}"))),
+        }
+    };
+
+    cx.render(rsx! {
+        style { include_str!("../style.css") }
+        div {
+            class: "catch-up",
+            div {
+                class: "catch-up-header",
+                h2 { "Welcome to Gluon News · {current.title()}" }
+                span { class: "freshness", "Step {step + 1} of {WizardStep::ALL.len()}" }
+            }
+            match current {
+                WizardStep::Feeds => rsx! {
+                    p { "Enter the address of a website or of a feed. The feeds a website links to are found for you." }
+                    input {
+                        value: "{address}",
+                        placeholder: "example.com",
+                        oninput: move |evt| address.set(evt.value.clone()),
+                        onkeydown: move |evt| if evt.key() == Key::Enter { discover() },
+                    }
+                    button { onclick: move |_| discover(), "Find feeds" }
+                    for (i, url) in feeds.get().iter().enumerate() {
+                        div {
+                            class: "catch-up-entry",
+                            "{url} "
+                            button { onclick: move |_| { feeds.make_mut().remove(i); }, "Remove" }
+                        }
+                    }
+                },
+                WizardStep::Theme => rsx! {
+                    for mode in ThemeMode::ALL {
+                        label {
+                            class: "catch-up-entry",
+                            input {
+                                r#type: "radio",
+                                name: "theme",
+                                checked: *theme.get() == mode,
+                                onclick: move |_| choose_theme(mode),
+                            }
+                            " {mode.label()}"
+                        }
+                    }
+                },
+                WizardStep::Window => rsx! {
+                    label {
+                        class: "catch-up-entry",
+                        input {
+                            r#type: "checkbox",
+                            checked: *maximized.get(),
+                            onclick: move |_| maximized.modify(|m| !m),
+                        }
+                        " Start maximized"
+                    }
+                    label {
+                        class: "catch-up-entry",
+                        "Time zone, such as Europe/Lisbon "
+                        input {
+                            value: "{time_zone}",
+                            oninput: move |evt| time_zone.set(evt.value.clone()),
+                        }
+                    }
+                },
+                WizardStep::Shortcuts => rsx! {
+                    p { "Use these keys on the entry list. Press ? at any time to see them again." }
+                    table {
+                        class: "keyboard-help",
+                        for binding in keybindings::default_bindings() {
+                            tr {
+                                td { kbd { "{binding.label()}" } }
+                                td { "{binding.action.description()}" }
+                            }
+                        }
+                    }
+                },
+            }
+            if let Some(status) = status.get() {
+                rsx! { p { class: "notice", "{status}" } }
+            }
+            div {
+                button {
+                    disabled: *step.get() == 0,
+                    onclick: move |_| step.modify(|s| s.saturating_sub(1)),
+                    "Back"
+                }
+                if last {
+                    rsx! { button { onclick: move |_| finish(), "Start reading" } }
+                } else {
+                    rsx! { button { onclick: move |_| step.modify(|s| s + 1), "Next" } }
+                }
+            }
+        }
+    })
+}
+
+/// Shows the first-run wizard while there is no settings.json, and the app
+/// once the wizard has written it.
+fn Root(cx: Scope) -> Element {
+    let first_run = use_state(cx, config::is_first_run);
+
+    cx.render(if *first_run.get() {
+        rsx! {
+            FirstRunWizard {
+                on_done: move |_| {
+                    cx.spawn(async { start_background_tasks() });
+                    first_run.set(false);
+                },
+            }
+        }
+    } else {
+        rsx! { App {} }
+    })
+}
+
+fn App(cx: Scope) -> Element {
+    let primary = *use_state(cx, || !PRIMARY_WINDOW_OPEN.swap(true, Ordering::SeqCst)).get();
+    let mut count = use_state(cx, || 0);
+    let seen = use_ref(cx, || None::<(DateTime<Utc>, HashSet<String>)>);
+    let diff = use_state(cx, || None::<(DateTime<Utc>, HashSet<String>)>);
+    let batcher = use_ref(cx, || {
+        SETTINGS
+            .notification_digest_seconds
+            .map(|seconds| NotificationBatcher::new(Duration::from_secs(seconds)))
+    });
+    let read = use_ref(cx, read_state::load);
+    let starred = use_ref(cx, bookmarks::load);
+    let reading_hours = use_ref(cx, ReadingHours::load);
+    // The entry links Safe Browsing flagged in the last refresh.
+    let unsafe_links = use_ref(cx, HashSet::<String>::new);
+    let synced_feeds = use_ref(cx, Vec::<String>::new);
+    // Feeds removed from settings.json since the app started.
+    let removed_feeds = use_ref(cx, HashSet::<String>::new);
+    let fetching = use_ref(cx, || false);
+    // Settings are read once, so merges and splits are kept here until the
+    // next start.
+    let merged_feeds = use_ref(cx, || SETTINGS.merged_feeds.clone());
+    // The entries of the feeds fetched so far, newest first, listed while the
+    // first refresh is still running.
+    let streamed = use_ref(cx, Vec::<(String, Entry)>::new);
+    // The entries of each feed from the last refresh, and what changed in
+    // them since the refresh before.
+    let snapshots = use_ref(cx, HashMap::<String, Vec<Entry>>::new);
+    let snapshot_diffs = use_ref(cx, HashMap::<String, SnapshotDiff>::new);
+    // The entry links of each feed from its last successful fetch, by URL,
+    // for vacuuming the cache without dropping what a failed fetch left out.
+    let feed_links = use_ref(cx, HashMap::<String, HashSet<String>>::new);
+    let health = use_ref(cx, || None::<HealthReport>);
+    let pending = use_ref(cx, Vec::<FeedStats>::new);
+    let reminders = use_ref(cx, Reminders::load);
+    let telemetry = use_ref(cx, Telemetry::load);
+    let update = use_state(cx, || None::<ReleaseInfo>);
+    use_future(cx, (), |_| {
+        let update = update.clone();
+        async move {
+            if SETTINGS.check_for_updates {
+                update.set(check_for_update().await);
+            }
+        }
+    });
+    let fetch_progress = use_state(cx, || None::<FetchProgress>);
+    // Whether the other windows list again because the first one refreshed,
+    // rather than because they were asked to refresh.
+    let from_primary = use_ref(cx, || false);
+    let future = use_future(cx, (count,), |_| {
+        let asked =
+            !primary && *count.get() > 0 && !std::mem::take(&mut *from_primary.write_silent());
+        let fetch_progress = fetch_progress.clone();
+        let seen = seen.clone();
+        let diff = diff.clone();
+        let batcher = batcher.clone();
+        let read = read.clone();
+        let starred = starred.clone();
+        let reading_hours = reading_hours.clone();
+        let unsafe_links = unsafe_links.clone();
+        let synced_feeds = synced_feeds.clone();
+        let removed_feeds = removed_feeds.clone();
+        let fetching = fetching.clone();
+        let merged_feeds = merged_feeds.clone();
+        let streamed = streamed.clone();
+        let snapshots = snapshots.clone();
+        let snapshot_diffs = snapshot_diffs.clone();
+        let feed_links = feed_links.clone();
+        let health = health.clone();
+        let pending = pending.clone();
+        let telemetry = telemetry.clone();
+        async move {
+            if !primary {
+                if asked {
+                    window_requests().send(WindowRequest::Refresh).ok();
+                }
+                let mut refreshes = refreshes().subscribe();
+                let refresh = loop {
+                    if let Some(refresh) = refreshes.borrow().clone() {
+                        break refresh;
+                    }
+                    refreshes.changed().await.ok();
+                };
+                read.set(read_state::load());
+                starred.set(bookmarks::load());
+                telemetry.set(Telemetry::load());
+                unsafe_links.set(refresh.unsafe_links);
+                if let Ok(news) = &refresh.news {
+                    feed_links.write_silent().extend(news.feed_links.clone());
+                }
+                return refresh.news;
+            }
+            if SETTINGS.health_check_on_startup && health.read().is_none() {
+                let report =
+                    health::check_feeds(&SETTINGS.all_feeds().cloned().collect::<Vec<_>>()).await;
+                if !report.disabled.is_empty() {
+                    show_notification(&format!(
+                        "Disabled unreachable feeds: {}",
+                        report.disabled.join(", ")
+                    ));
+                }
+                health.write_silent().replace(report);
+            }
+            let disabled: Vec<String> = health
+                .read()
+                .as_ref()
+                .map(|report| report.disabled.clone())
+                .unwrap_or_default();
+            let all_feeds: Vec<String> = SETTINGS
+                .enabled_feeds()
+                .filter(|feed| !disabled.contains(feed))
+                .chain(synced_feeds.read().iter())
+                .filter(|feed| !removed_feeds.read().contains(*feed))
+                .cloned()
+                .collect();
+            *fetching.write_silent() = true;
+            let feeds: Vec<&str> = all_feeds.iter().map(|f| f.as_str()).collect();
+            pending.set(FetchTimes::load().stats(&feeds));
+            let (progress_tx, mut progress_rx) =
+                tokio::sync::mpsc::channel::<FetchProgress>(feeds.len().max(1));
+            let track = async {
+                while let Some(progress) = progress_rx.recv().await {
+                    pending
+                        .write()
+                        .retain(|stats| stats.url != progress.latest_feed);
+                    fetch_progress.set(Some(progress));
+                }
+            };
+            let (entries_tx, mut entries_rx) =
+                tokio::sync::mpsc::channel::<(String, Entry)>(STREAMED_ENTRIES_BUFFER);
+            let stream = async {
+                while let Some(entry) = entries_rx.recv().await {
+                    // Whatever else has arrived goes in the same batch, so the
+                    // list is sorted and rendered once per batch.
+                    let mut batch = vec![entry];
+                    while let Ok(entry) = entries_rx.try_recv() {
+                        batch.push(entry);
+                    }
+                    streamed.with_mut(|streamed| {
+                        streamed.extend(batch);
+                        sort_newest_first(streamed);
+                    });
+                }
+            };
+            let (results, searches, _, _) = tokio::join!(
+                fetch_news_with_progress(&feeds, Some(progress_tx), Some(entries_tx)),
+                search_feeds::fetch_search_results(&SETTINGS.search_feeds),
+                track,
+                stream
+            );
+            let results = results.map(|mut results| {
+                results.extend(searches);
+                results
+            });
+            *fetching.write_silent() = false;
+            streamed.write_silent().clear();
+            pending.set(Vec::new());
+            fetch_progress.set(None);
+            telemetry.set(Telemetry::load());
+            let news = results.map(|results| {
+                let mut news = News::from_results(results);
+                news.apply_merges(&merged_feeds.read());
+                news
+            });
+
+            if let (Some(config), Ok(news)) = (&SETTINGS.nextcloud_sync, &news) {
+                sync_nextcloud(config, &all_feeds, news, &read, &starred, &synced_feeds).await;
+            }
+            if let (false, Ok(news)) = (SETTINGS.export_targets.is_empty(), &news) {
+                tokio::spawn(export_entries(news.entries.clone()));
+            }
+            if let (Some(api_key), Ok(news)) = (&SETTINGS.safe_browsing_api_key, &news) {
+                let links: Vec<String> = news
+                    .entries
+                    .iter()
+                    .filter_map(|e| e.1.best_link().map(String::from))
+                    .collect();
+                match reputation::unsafe_links(&links, api_key).await {
+                    Ok(flagged) => unsafe_links.set(flagged),
+                    Err(e) => tracing::warn!("could not check links with Safe Browsing: {e}"),
+                }
+            }
+
+            if let Ok(news) = &news {
+                feed_links.write_silent().extend(news.feed_links.clone());
+                let mut by_feed: HashMap<String, Vec<Entry>> = HashMap::new();
+                for (feed_title, entry) in &news.entries {
+                    by_feed
+                        .entry(feed_title.clone())
+                        .or_default()
+                        .push(entry.clone());
+                }
+                let previous = std::mem::replace(&mut *snapshots.write_silent(), by_feed);
+                let diffs = snapshots
+                    .read()
+                    .iter()
+                    .filter_map(|(feed_title, entries)| {
+                        let diff = diff_snapshots(previous.get(feed_title)?, entries);
+                        (!diff.is_empty()).then(|| (feed_title.clone(), diff))
+                    })
+                    .collect();
+                snapshot_diffs.set(diffs);
+            }
+
+            if let Ok(news) = &news {
+                let ids: HashSet<String> = news.entries.iter().map(|e| e.1.id.clone()).collect();
+                if let Some((since, previous)) =
+                    seen.write_silent().replace((Utc::now(), ids.clone()))
+                {
+                    let new_ids: HashSet<String> = ids.difference(&previous).cloned().collect();
+                    let in_notification_window = !SETTINGS.smart_notification_window
+                        || reading_hours.read().in_window(
+                            reading_hours::current_hour(),
+                            SETTINGS
+                                .notification_buffer_hours
+                                .unwrap_or(DEFAULT_NOTIFICATION_BUFFER_HOURS),
+                        );
+                    if SETTINGS.notify_new_entries && in_notification_window {
+                        let new_entries = news.entries.iter().filter(|e| new_ids.contains(&e.1.id));
+                        match batcher.write_silent().as_mut() {
+                            Some(batcher) => batcher.push(new_entries.map(|e| e.1.best_title())),
+                            None => {
+                                for (feed_title, entry) in new_entries {
+                                    show_notification(&format!(
+                                        "{feed_title}: {}",
+                                        entry.best_title()
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                    if let (Some(config), false) = (&SETTINGS.websocket_push, new_ids.is_empty()) {
+                        let new_entries: Vec<(String, Entry)> = news
+                            .entries
+                            .iter()
+                            .filter(|e| new_ids.contains(&e.1.id))
+                            .cloned()
+                            .collect();
+                        tokio::spawn(async move {
+                            if let Err(e) = websocket::push_entries(config, &new_entries).await {
+                                tracing::warn!("could not push new entries to {}: {e}", config.url);
+                            }
+                        });
+                    }
+                    if !new_ids.is_empty() {
+                        diff.set(Some((since, new_ids)));
+                    }
+                }
+            }
+
+            let news = news.map(Arc::new).map_err(Arc::new);
+            refreshes().send_replace(Some(Refresh {
+                news: news.clone(),
+                unsafe_links: unsafe_links.read().clone(),
+            }));
+            news
+        }
+    });
+    // The other windows list each refresh of the first one as it comes.
+    use_future(cx, (), |_| {
+        let count = count.clone();
+        let from_primary = from_primary.clone();
+        async move {
+            if primary {
+                return;
+            }
+            let mut refreshes = refreshes().subscribe();
+            while refreshes.changed().await.is_ok() {
+                *from_primary.write_silent() = true;
+                count.modify(|c| c + 1);
+            }
+        }
+    });
+    // The first window fetches again when another one asks, with whatever
+    // arrived in the meantime.
+    use_future(cx, (), |_| {
+        let count = count.clone();
+        let synced_feeds = synced_feeds.clone();
+        let merged_feeds = merged_feeds.clone();
+        async move {
+            if !primary {
+                return;
+            }
+            let mut requests = window_requests().subscribe();
+            loop {
+                let mut request = match requests.recv().await {
+                    Ok(request) => Some(request),
+                    Err(broadcast::error::RecvError::Lagged(_)) => None,
+                    Err(broadcast::error::RecvError::Closed) => return,
+                };
+                while let Some(next) = request {
+                    match next {
+                        WindowRequest::Refresh => {}
+                        WindowRequest::AddFeeds(feeds) => {
+                            let mut synced_feeds = synced_feeds.write_silent();
+                            for feed in feeds {
+                                if !synced_feeds.contains(&feed) {
+                                    synced_feeds.push(feed);
+                                }
+                            }
+                        }
+                        WindowRequest::Merge(merged) => {
+                            let mut merged_feeds = merged_feeds.write_silent();
+                            merged_feeds
+                                .retain(|m| !m.sources.iter().any(|s| merged.sources.contains(s)));
+                            merged_feeds.push(merged);
+                        }
+                        WindowRequest::Split(title) => {
+                            merged_feeds.write_silent().retain(|m| m.title != title)
+                        }
+                    }
+                    request = requests.try_recv().ok();
+                }
+                count.modify(|c| c + 1);
+            }
+        }
+    });
+    let estimate = {
+        let pending = pending.read();
+        let estimate = estimate_fetch_duration(&pending);
+        (!estimate.is_zero()).then(|| format!("Estimated: ~{}s", estimate.as_secs().max(1)))
+    };
+    let data_fetched = format!("{:.1} MB", telemetry.read().total_data_fetched_mb());
+    let poll_interval = future.value().map(|news| match news {
+        Ok(news) => news.poll_interval,
+        Err(_) => DEFAULT_POLL_INTERVAL,
+    });
+    use_future(
+        cx,
+        (count, &poll_interval),
+        |(count, poll_interval)| async move {
+            if let (true, Some(poll_interval)) = (primary, poll_interval) {
+                tokio::time::sleep(poll_interval).await;
+                count.modify(|c| c + 1);
+            }
+        },
+    );
+    use_future(cx, (), |_| {
+        let batcher = batcher.clone();
+        async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                let summary = batcher
+                    .write_silent()
+                    .as_mut()
+                    .filter(|batcher| batcher.is_due())
+                    .and_then(NotificationBatcher::flush);
+                if let Some(summary) = summary {
+                    show_notification(&summary);
+                }
+            }
+        }
+    });
+    use_future(cx, (), |_| {
+        let reminders = reminders.clone();
+        async move {
+            loop {
+                tokio::time::sleep(REMINDER_CHECK_INTERVAL).await;
+                // The file is read again so other windows do not fire the
+                // same reminders.
+                let mut pending = Reminders::load();
+                let due = pending.take_due(Utc::now());
+                if due.is_empty() {
+                    continue;
+                }
+                if let Err(e) = pending.save() {
+                    tracing::warn!("could not save reminders: {e}");
+                }
+                for reminder in due {
+                    show_notification(&format!("Reminder: {}", reminder.title));
+                }
+                reminders.set(pending);
+            }
+        }
+    });
+    let list_position = use_ref(cx, ListPosition::default);
+    let river_len = use_state(cx, || RIVER_BATCH);
+    let focus_time = use_ref(cx, FocusTime::load);
+    let window_focused = use_ref(cx, || true);
+    use_wry_event_handler(cx, {
+        let batcher = batcher.clone();
+        let read = read.clone();
+        let starred = starred.clone();
+        let list_position = list_position.clone();
+        let window_focused = window_focused.clone();
+        move |event, _| {
+            if let Event::WindowEvent {
+                event: WindowEvent::Focused(focused),
+                ..
+            } = event
+            {
+                *window_focused.write_silent() = *focused;
+            }
+            if let Event::WindowEvent {
+                event: WindowEvent::CloseRequested,
+                ..
+            } = event
+            {
+                if SETTINGS.restore_position_on_startup {
+                    if let Err(e) = list_position.read().save() {
+                        tracing::warn!("could not save the list position: {e}");
+                    }
+                }
+            }
+            if let Event::WindowEvent {
+                event: WindowEvent::Focused(true),
+                ..
+            } = event
+            {
+                read.set(read_state::load());
+                starred.set(bookmarks::load());
+                let summary = batcher
+                    .write_silent()
+                    .as_mut()
+                    .and_then(NotificationBatcher::flush);
+                if let Some(summary) = summary {
+                    show_notification(&summary);
+                }
+            }
+        }
+    });
+    let view = use_state(cx, || View::All);
+    let date_filter = use_state(cx, || DateFilter::AllTime);
+    let max_reading_time = use_state(cx, || None::<u32>);
+    let show_catch_up = use_state(cx, || false);
+    let shared_feeds = use_ref(cx, || {
+        primary
+            .then(|| SHARED_FEEDS.get().cloned())
+            .flatten()
+            .unwrap_or_default()
+    });
+    let show_bulk_add = use_state(cx, || !shared_feeds.read().is_empty());
+    let show_share_qr = use_state(cx, || false);
+    let unread_only = use_state(cx, || false);
+    let category_filter = use_state(cx, || None::<String>);
+    // The label of the topic shown, which outlives refreshes that keep it.
+    let topic_filter = use_state(cx, || None::<String>);
+    let opened = use_state(cx, || None::<Entry>);
+    // Where the next unread entry is looked for in the list, just past the
+    // one opened last.
+    let current_position = use_state(cx, || 0usize);
+    let window = use_window(cx);
+    // Each window has its own view, filters and scroll position. The state
+    // files are shared and reloaded whenever a window gets focus or the first
+    // window refreshes.
+    let open_window = move || {
+        window.new_window(VirtualDom::new(App), window_config(&SETTINGS));
+    };
+    let unread = match future.value() {
+        Some(Ok(news)) => Some(
+            news.entries
+                .iter()
+                .filter(|e| !read.read().contains(&e.1.id))
+                .count(),
+        ),
+        _ => None,
+    };
+
+    use_effect(cx, (&unread,), |(unread,)| {
+        let window = window.clone();
+        async move {
+            if let Some(unread) = unread {
+                badge::set_unread_count(&window, unread).await;
+            }
+        }
+    });
+    let reading_profile = use_ref(cx, ReadingProfile::load);
+    let reading_since = use_ref(cx, || None::<(String, usize, DateTime<Utc>)>);
+    let finish_reading = move || {
+        if let Some((entry_id, words, opened)) = reading_since.write_silent().take() {
+            let mut profile = reading_profile.write();
+            profile.record(entry_id, words, opened, Utc::now());
+            if let Err(e) = profile.save() {
+                tracing::warn!("could not save reading speed samples: {e}");
+            }
+        }
+    };
+    let notice = use_state(cx, || None::<String>);
+    let read_days = use_ref(cx, ReadDays::load);
+    let record_read_day = move || {
+        let today = streak::today();
+        if !read_days.write().record(today) {
+            return;
+        }
+        if let Err(e) = read_days.read().save() {
+            tracing::warn!("could not save read days: {e}");
+        }
+        let streak = read_days.read().current_streak(today);
+        if streak::MILESTONES.contains(&streak) {
+            notice.set(Some(format!("🔥 {streak} day reading streak, well done!")));
+        }
+    };
+    let read_counts = use_ref(cx, ReadCounts::load);
+    // Only entries opened count towards the goal, not those marked read in bulk.
+    let record_read_count = move || {
+        let today = streak::today();
+        read_counts.write().record(today);
+        if let Err(e) = read_counts.read().save() {
+            tracing::warn!("could not save read counts: {e}");
+        }
+        if let Some(goal) = &SETTINGS.reading_goal {
+            if read_counts.read().in_period(goal.period, today) == goal.count {
+                show_notification(&format!(
+                    "🎯 Reading goal met: {} articles {}, well done!",
+                    goal.count,
+                    goal.period.label()
+                ));
+            }
+        }
+    };
+    let record_read_hour = move || {
+        reading_hours
+            .write_silent()
+            .record(reading_hours::current_hour());
+        if let Err(e) = reading_hours.read().save() {
+            tracing::warn!("could not save reading hours: {e}");
+        }
+    };
+    let archived = use_ref(cx, HashSet::<String>::new);
+    let open_entry = move |entry: &Entry| {
+        if read.write().insert(entry.id.clone()) {
+            if let Err(e) = read_state::save(&read.read()) {
+                tracing::warn!("could not save read state: {e}");
+            }
+            record_read_count();
+            record_read_hour();
+        }
+        record_read_day();
+        if SETTINGS.archive_on_read && !archive::is_archived(&entry.id) {
+            if let Some(link) = entry.best_link() {
+                let (entry_id, link) = (entry.id.clone(), link.to_owned());
+                let archived = archived.clone();
+                cx.spawn(async move {
+                    match archive::archive(&entry_id, &link).await {
+                        Ok(_) => {
+                            archived.write().insert(entry_id);
+                        }
+                        Err(e) => tracing::warn!("could not archive {link}: {e}"),
+                    }
+                });
+            }
+        }
+        if SETTINGS.link_target == LinkTarget::ContentPane {
+            finish_reading();
+            let words = word_count(&text::strip_html(&entry.best_content()));
+            reading_since.set(Some((entry.id.clone(), words, Utc::now())));
+            opened.set(Some(entry.clone()));
+        }
+    };
+    let wpm = reading_profile.read().wpm();
+    let personalised_wpm = reading_profile.read().personalised_wpm();
+    let scroll_positions = use_ref(cx, HashMap::<String, f64>::new);
+    let eval = use_eval(cx);
+    use_future(cx, (), |_| {
+        let synced_feeds = synced_feeds.clone();
+        let removed_feeds = removed_feeds.clone();
+        let fetching = fetching.clone();
+        let count = count.clone();
+        let notice = notice.clone();
+        let eval = eval.clone();
+        async move {
+            // The first window reloads settings.json for all of them.
+            if !primary {
+                let mut scripts = settings_scripts().subscribe();
+                loop {
+                    match scripts.recv().await {
+                        Ok(script) => {
+                            eval(&script).ok();
+                        }
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => return,
+                    }
+                }
+            }
+            let (changed_tx, mut changed_rx) = tokio::sync::mpsc::unbounded_channel();
+            let _watcher = match config_watcher::watch(changed_tx) {
+                Ok(watcher) => watcher,
+                Err(e) => {
+                    tracing::warn!("could not watch settings.json: {e}");
+                    return;
+                }
+            };
+            let mut current = config::reload_settings()
+                .map(|(_, value)| value)
+                .unwrap_or_default();
+            while changed_rx.recv().await.is_some() {
+                tokio::time::sleep(SETTINGS_RELOAD_DELAY).await;
+                while changed_rx.try_recv().is_ok() {}
+                let (settings, value) = match config::reload_settings() {
+                    Ok(reloaded) => reloaded,
+                    Err(e) => {
+                        notice.set(Some(format!("Could not reload settings: {e}")));
+                        continue;
+                    }
+                };
+                let changes = config::SettingsChanges::between(&current, &value);
+                // The app already applied what it wrote itself, such as added
+                // feeds or mute lists.
+                let own_write = config::is_own_write(&value);
+                current = value;
+                if changes.is_empty() || own_write {
+                    continue;
+                }
+                for feed in &changes.added_feeds {
+                    removed_feeds.write_silent().remove(feed);
+                    if !SETTINGS.all_feeds().any(|f| f == feed) {
+                        synced_feeds.write_silent().push(feed.clone());
+                    }
+                }
+                for feed in &changes.removed_feeds {
+                    synced_feeds.write_silent().retain(|f| f != feed);
+                    removed_feeds.write_silent().insert(feed.clone());
+                }
+                let mut applied = Vec::new();
+                let mut on_restart = Vec::new();
+                let mut scripts = Vec::new();
+                for key in &changes.changed {
+                    match key.as_str() {
+                        "theme" => {
+                            scripts.push(format!(
+                                "window.themeMode = {:?}; window.applyTheme();",
+                                settings.theme.id()
+                            ));
+                            applied.push(key.as_str());
+                        }
+                        "content_font_family" => {
+                            let family = settings.content_font_family.as_deref().unwrap_or("");
+                            scripts.push(format!(
+                                "document.documentElement.style.setProperty('--content-font', {:?});",
+                                family.replace('<', "")
+                            ));
+                            applied.push(key.as_str());
+                        }
+                        _ => on_restart.push(key.as_str()),
+                    }
+                }
+                for script in scripts {
+                    eval(&script).ok();
+                    settings_scripts().send(script).ok();
+                }
+                let mut summary = vec![String::from("Settings reloaded")];
+                if !changes.added_feeds.is_empty() || !changes.removed_feeds.is_empty() {
+                    summary.push(format!(
+                        "{} feeds added, {} removed",
+                        changes.added_feeds.len(),
+                        changes.removed_feeds.len()
+                    ));
+                }
+                if !applied.is_empty() {
+                    summary.push(format!("updated {}", applied.join(", ")));
+                }
+                if !on_restart.is_empty() {
+                    summary.push(format!("{} apply on the next start", on_restart.join(", ")));
+                }
+                notice.set(Some(summary.join(" · ")));
+                // A refresh already running keeps its feeds, and the changes
+                // are picked up by the next one.
+                if !*fetching.read()
+                    && (!changes.added_feeds.is_empty() || !changes.removed_feeds.is_empty())
+                {
+                    count.modify(|c| c + 1);
+                }
+            }
+        }
+    });
+    let tags = use_ref(cx, tags::load);
+    let queue = use_ref(cx, ReadLaterQueue::load);
+    let muted = use_ref(cx, || SETTINGS.mute_domains.clone());
+    let mute_rules = use_ref(cx, || {
+        let categories = SETTINGS
+            .mute_categories
+            .iter()
+            .cloned()
+            .map(MuteRule::Category);
+        let keywords = SETTINGS
+            .mute_keywords
+            .iter()
+            .cloned()
+            .map(MuteRule::Keyword);
+        categories.chain(keywords).collect::<Vec<_>>()
+    });
+    let mute_history = use_ref(cx, MuteHistory::load);
+    let not_interested = use_ref(cx, HashSet::<String>::new);
+    let focused = use_state(cx, || None::<String>);
+    let editing_tags = use_state(cx, || None::<String>);
+    let tag_draft = use_state(cx, String::new);
+    let search = use_state(cx, String::new);
+    let search_history = use_ref(cx, search_history::load);
+    let show_search_history = use_state(cx, || false);
+    let collections = use_ref(cx, collections::load);
+    let collection_menu = use_state(cx, || None::<String>);
+    let feed_menu = use_state(cx, || None::<String>);
+    let snapshot_view = use_state(cx, || None::<String>);
+    // Set once the fold is dragged, taking the place of the configured one.
+    let fold_override = use_state(cx, || None::<usize>);
+    let show_keyboard_help = use_state(cx, || false);
+    let dragging_fold = use_state(cx, || false);
+    let reminder_menu = use_state(cx, || None::<String>);
+    let selected_feeds = use_state(cx, Vec::<String>::new);
+
+    let playback_progress = use_ref(cx, PlaybackProgress::load);
+    let undo_stack = use_ref(cx, UndoStack::default);
+    let undo_toast = use_state(cx, || None::<String>);
+
+    use_future(cx, (notice,), |(notice,)| async move {
+        if notice.get().is_some() {
+            tokio::time::sleep(NOTICE_DURATION).await;
+            notice.set(None);
+        }
+    });
+    use_future(cx, (undo_toast,), |(undo_toast,)| async move {
+        if undo_toast.get().is_some() {
+            tokio::time::sleep(UNDO_TOAST_DURATION).await;
+            undo_toast.set(None);
+        }
+    });
+
+    use_effect(cx, (focused,), |_| {
+        let eval = eval.clone();
+        async move {
+            eval("document.querySelector('li.focused')?.scrollIntoView({block: 'nearest'});").ok();
+        }
+    });
+
+    let toggle_star = move |entry: &Entry| {
+        let was_starred = {
+            let mut starred = starred.write();
+            starred.remove(&entry.id) || !starred.insert(entry.id.clone())
+        };
+        if let Err(e) = bookmarks::save(&starred.read()) {
+            tracing::warn!("could not save bookmarks: {e}");
+        }
+        notice.set(Some(String::from(if was_starred {
+            "Unstarred"
+        } else {
+            "Starred"
+        })));
+    };
+    let update_reminders = move |change: &dyn Fn(&mut Reminders)| {
+        change(&mut reminders.write());
+        if let Err(e) = reminders.read().save() {
+            tracing::warn!("could not save reminders: {e}");
+        }
+        reminder_menu.set(None);
+    };
+    let update_queue = move |change: &dyn Fn(&mut ReadLaterQueue)| {
+        change(&mut queue.write());
+        if let Err(e) = queue.read().save() {
+            tracing::warn!("could not save read later queue: {e}");
+        }
+    };
+    let push_undo = move |action: UndoAction| {
+        undo_toast.set(Some(action.description()));
+        undo_stack.write_silent().push(action);
+    };
+    let undo = move || {
+        let Some(action) = undo_stack.write_silent().pop() else {
+            return;
+        };
+        match action {
+            UndoAction::MarkAllRead(ids) => {
+                for id in &ids {
+                    read.write().remove(id);
+                }
+                if let Err(e) = read_state::save(&read.read()) {
+                    tracing::warn!("could not save read state: {e}");
+                }
+            }
+            UndoAction::MuteDomain(domain) => {
+                muted.write().retain(|d| d != &domain);
+                if let Err(e) = save_mute_domains(&muted.read()) {
+                    tracing::warn!("could not save muted domains: {e}");
+                }
+            }
+            UndoAction::RemoveFromQueue(entry_id, position) => {
+                update_queue(&|q| q.insert(position, &entry_id));
+            }
+        }
+        undo_toast.set(None);
+    };
+    let remove_from_queue = move |entry_id: &str| {
+        let Some(position) = queue.read().ids().iter().position(|id| id == entry_id) else {
+            return;
+        };
+        update_queue(&|q| q.remove(entry_id));
+        push_undo(UndoAction::RemoveFromQueue(entry_id.to_owned(), position));
+    };
+    // Every entry muted or hidden is remembered, to suggest the mute rules
+    // that would have hidden them all.
+    let record_mute = move |entry: &Entry| {
+        let entries = match future.value() {
+            Some(Ok(news)) => news.entries.as_slice(),
+            _ => &[],
+        };
+        mute_history.write().record(MutedEntry::new(entry, entries));
+        if let Err(e) = mute_history.read().save() {
+            tracing::warn!("could not save mute history: {e}");
+        }
+    };
+    let mute_entry = move |entry: &Entry| {
+        record_mute(entry);
+        let Some(domain) = entry.domain() else {
+            notice.set(Some(String::from("Entry has no domain to mute")));
+            return;
+        };
+        if muted.read().contains(&domain) {
+            notice.set(Some(format!("{domain} is already muted")));
+            return;
+        }
+        muted.write().push(domain.clone());
+        if let Err(e) = save_mute_domains(&muted.read()) {
+            tracing::warn!("could not save muted domains: {e}");
+        }
+        push_undo(UndoAction::MuteDomain(domain));
+    };
+    let hide_entry = move |entry: &Entry| {
+        record_mute(entry);
+        not_interested.write().insert(entry.id.clone());
+        if read.write().insert(entry.id.clone()) {
+            if let Err(e) = read_state::save(&read.read()) {
+                tracing::warn!("could not save read state: {e}");
+            }
+        }
+    };
+    let mute_suggestion = mute_history
+        .read()
+        .suggestions(|rule| match rule {
+            MuteRule::Domain(domain) => muted.read().contains(domain),
+            _ => mute_rules.read().contains(rule),
+        })
+        .into_iter()
+        .next();
+    let accept_mute_rule = move |rule: MuteRule| {
+        notice.set(Some(format!("Muted {}", rule.label())));
+        if let MuteRule::Domain(domain) = rule {
+            muted.write().push(domain.clone());
+            if let Err(e) = save_mute_domains(&muted.read()) {
+                tracing::warn!("could not save muted domains: {e}");
+            }
+            push_undo(UndoAction::MuteDomain(domain));
+            return;
+        }
+        mute_rules.write().push(rule);
+        let (mut categories, mut keywords) = (Vec::new(), Vec::new());
+        for rule in mute_rules.read().iter() {
+            match rule {
+                MuteRule::Category(category) => categories.push(category.clone()),
+                MuteRule::Keyword(keyword) => keywords.push(keyword.clone()),
+                MuteRule::Domain(_) => {}
+            }
+        }
+        for (key, values) in [("mute_categories", categories), ("mute_keywords", keywords)] {
+            if let Err(e) = config::save_mute_list(key, &values) {
+                tracing::warn!("could not save {key}: {e}");
+            }
+        }
+    };
+    let dismiss_mute_rule = move |rule: MuteRule| {
+        mute_history.write().dismiss(rule);
+        if let Err(e) = mute_history.read().save() {
+            tracing::warn!("could not save mute history: {e}");
+        }
+    };
+    let edit_tags = move |entry: &Entry| {
+        let current = tags.read().get(&entry.id).map(|t| t.join(", "));
+        tag_draft.set(current.unwrap_or_default());
+        editing_tags.set(Some(entry.id.clone()));
+    };
+    let close_tag_editor = move || {
+        editing_tags.set(None);
+        eval("document.querySelector('.layout')?.focus();").ok();
+    };
+    let save_tags = move |entry_id: &String| {
+        let entry_tags = tags::parse(tag_draft.get());
+        if entry_tags.is_empty() {
+            tags.write().remove(entry_id);
+        } else {
+            tags.write().insert(entry_id.clone(), entry_tags);
+        }
+        if let Err(e) = tags::save(&tags.read()) {
+            tracing::warn!("could not save tags: {e}");
+        }
+        notice.set(Some(String::from("Tags saved")));
+        close_tag_editor();
+    };
+    let submit_search = move |query: &str| {
+        search_history::record(&mut search_history.write(), query);
+        if let Err(e) = search_history::save(&search_history.read()) {
+            tracing::warn!("could not save search history: {e}");
+        }
+        show_search_history.set(false);
+    };
+    let add_to_collection = move |name: String, entry_id: &String| {
+        let added = collections::add(&mut collections.write(), &name, entry_id);
+        if let Err(e) = collections::save(&collections.read()) {
+            tracing::warn!("could not save collections: {e}");
+        }
+        notice.set(Some(if added {
+            format!("Added to {name}")
+        } else {
+            format!("Already in {name}")
+        }));
+        collection_menu.set(None);
+    };
+    let merge_with =
+        move |title: &String, other: &String, feed_titles: &HashMap<String, String>| {
+            feed_menu.set(None);
+            let mut sources: Vec<String> = feed_titles
+                .iter()
+                .filter(|(_, t)| *t == title || *t == other)
+                .map(|(url, _)| url.clone())
+                .collect();
+            sources.sort();
+            let merged = MergedFeed {
+                title: format!("{title} + {other}"),
+                sources,
+            };
+            if let Err(e) = config::merge_feeds(&merged) {
+                notice.set(Some(format!("Could not merge feeds: {e}")));
+                return;
+            }
+            notice.set(Some(format!("Merged into {}", merged.title)));
+            if !primary {
+                window_requests()
+                    .send(WindowRequest::Merge(merged.clone()))
+                    .ok();
+            }
+            merged_feeds
+                .write_silent()
+                .retain(|m| !m.sources.iter().any(|s| merged.sources.contains(s)));
+            merged_feeds.write_silent().push(merged);
+            count.modify(|c| c + 1);
+        };
+    let split_feed = move |title: &String| {
+        feed_menu.set(None);
+        if let Err(e) = config::split_feed(title) {
+            notice.set(Some(format!("Could not split {title}: {e}")));
+            return;
+        }
+        notice.set(Some(format!("Split {title}")));
+        merged_feeds.write_silent().retain(|m| &m.title != title);
+        if !primary {
+            window_requests()
+                .send(WindowRequest::Split(title.clone()))
+                .ok();
+        }
+        count.modify(|c| c + 1);
+    };
+    // The selected feeds are refreshed together when the menu is opened on
+    // one of them.
+    let force_refresh = move |title: &String, feed_titles: &HashMap<String, String>| {
+        feed_menu.set(None);
+        let titles = if selected_feeds.get().contains(title) {
+            selected_feeds.get().clone()
+        } else {
+            vec![title.clone()]
+        };
+        let urls: Vec<String> = feed_titles
+            .iter()
+            .filter(|(_, t)| titles.contains(t))
+            .map(|(url, _)| url.clone())
+            .collect();
+        let count = count.clone();
+        let notice = notice.clone();
+        cx.spawn(async move {
+            let mut failed = 0;
+            for url in &urls {
+                if let Err(e) = force_fetch_feed(url, &CLIENT).await {
+                    tracing::warn!("could not force refresh {url}: {e}");
+                    failed += 1;
+                }
+            }
+            notice.set(Some(match failed {
+                0 => format!("Force refreshed {} feeds", urls.len()),
+                _ => format!("Could not force refresh {failed} of {} feeds", urls.len()),
+            }));
+            count.modify(|c| c + 1);
+        });
+    };
+    let export_collection = move |name: &String, entries: &[(String, Entry)], format| {
+        let ids = collections.read().get(name).cloned().unwrap_or_default();
+        match collections::export(name, &ids, entries, format) {
+            Ok(path) => notice.set(Some(format!("Exported to {}", path.display()))),
+            Err(e) => notice.set(Some(format!("Could not export {name}: {e}"))),
+        }
+    };
+    let toggle_selected = move |feed_title: &String| {
+        let mut selected = selected_feeds.get().clone();
+        if let Some(i) = selected.iter().position(|t| t == feed_title) {
+            selected.remove(i);
+        } else {
+            selected.push(feed_title.clone());
+        }
+        if let View::Merged(_) = view.get() {
+            view.set(if selected.is_empty() {
+                View::All
+            } else {
+                View::Merged(selected.clone())
+            });
+        }
+        selected_feeds.set(selected);
+    };
+    let entry_class = move |entry_id: &String| {
+        let mut classes = Vec::new();
+        if read.read().contains(entry_id) {
+            classes.push("read");
+        }
+        if focused.get().as_ref() == Some(entry_id) {
+            classes.push("focused");
+        }
+        classes.join(" ")
+    };
+
+    use_effect(cx, (view,), |(view,)| {
+        let position = scroll_positions
+            .read()
+            .get(&view.key())
+            .copied()
+            .unwrap_or_default();
+        let eval = eval.clone();
+        async move {
+            eval(&format!("window.scrollTo(0, {position});")).ok();
+        }
+    });
+    // A refresh keeps the entry at the top of the list where it was, unless
+    // entries that were not listed before now come above it, in which case
+    // the list goes back to the top to show them.
+    let refresh_anchor = use_ref(cx, || None::<(String, String)>);
+    use_effect(cx, (count,), |_| {
+        let refresh_anchor = refresh_anchor.clone();
+        let view_key = view.get().key();
+        // Before the first refresh is done, only the streamed entries are
+        // listed, and the position saved on close is restored instead.
+        let listed = future.value().is_some();
+        let eval = eval.clone();
+        async move {
+            if !listed {
+                return;
+            }
+            let Ok(script) = eval(REFRESH_ANCHOR_SCRIPT) else {
+                return;
+            };
+            *refresh_anchor.write_silent() = script
+                .join()
+                .await
+                .ok()
+                .filter(|anchor| !anchor.is_null())
+                .map(|anchor| (view_key, anchor.to_string()));
+        }
+    });
+    let listed_news = match future.value() {
+        Some(Ok(news)) => Some(Arc::as_ptr(news) as usize),
+        _ => None,
+    };
+    use_effect(cx, (&listed_news,), |_| {
+        let anchor = refresh_anchor.write_silent().take();
+        let view_key = view.get().key();
+        let eval = eval.clone();
+        async move {
+            let Some((_, anchor)) = anchor.filter(|(key, _)| *key == view_key) else {
+                return;
+            };
+            eval(&format!(
+                "const anchor = {anchor};
+                const items = [...document.querySelectorAll('li[data-entry-id]')];
+                const top = items.findIndex(li => li.dataset.entryId === anchor.id);
+                if (top >= 0) {{
+                    if (items.slice(0, top).every(li => anchor.above.includes(li.dataset.entryId))) {{
+                        window.scrollBy(0, items[top].getBoundingClientRect().top - anchor.offset);
+                    }} else {{
+                        window.scrollTo(0, 0);
+                    }}
+                }}"
+            ))
+            .ok();
+        }
+    });
+    use_future(cx, (), |_| {
+        let list_position = list_position.clone();
+        let eval = eval.clone();
+        async move {
+            if !SETTINGS.restore_position_on_startup {
+                return;
+            }
+            loop {
+                tokio::time::sleep(POSITION_SAMPLE_INTERVAL).await;
+                let Ok(script) = eval(TOP_ENTRY_SCRIPT) else {
+                    continue;
+                };
+                // Nothing is listed while refreshing, which keeps the
+                // entry from before.
+                if let Some(id) = script
+                    .join()
+                    .await
+                    .ok()
+                    .and_then(|id| id.as_str().map(String::from))
+                {
+                    list_position.write_silent().top_entry_id = Some(id);
+                }
+            }
+        }
+    });
+    // The list is a river of news: it grows as the end of it is scrolled to.
+    use_future(cx, (), |_| {
+        let river_len = river_len.clone();
+        let eval = eval.clone();
+        async move {
+            let Ok(river) = eval(RIVER_SCRIPT) else {
+                return;
+            };
+            while river.recv().await.is_ok() {
+                river_len.modify(|len| len + RIVER_BATCH);
+            }
+        }
+    });
+    // Counts the time reading while the window has focus, but not once it
+    // has had no input for a while, as the user is away then.
+    use_future(cx, (), |_| {
+        let focus_time = focus_time.clone();
+        let window_focused = window_focused.clone();
+        let eval = eval.clone();
+        async move {
+            if !SETTINGS.track_reading_time {
+                return;
+            }
+            eval(INPUT_TRACKING_SCRIPT).ok();
+            loop {
+                tokio::time::sleep(FOCUS_TIME_INTERVAL).await;
+                if !*window_focused.read() {
+                    continue;
+                }
+                let Ok(script) = eval("return Date.now() - window.lastInput;") else {
+                    continue;
+                };
+                let idle = script.join().await.ok().and_then(|idle| idle.as_u64());
+                if idle.is_some_and(|idle| idle < focus_time::IDLE_THRESHOLD.as_millis() as u64) {
+                    focus_time
+                        .write()
+                        .record(streak::today(), FOCUS_TIME_INTERVAL.as_secs());
+                    if let Err(e) = focus_time.read().save() {
+                        tracing::warn!("could not save focus time: {e}");
+                    }
+                }
+            }
+        }
+    });
+    use_future(cx, (), |_| {
+        let eval = eval.clone();
+        async move {
+            let Some(browser) = SETTINGS
+                .browser
+                .as_deref()
+                .filter(|b| browser::find_program(b).is_some())
+            else {
+                return;
+            };
+            let Ok(links) = eval(BROWSER_LINKS_SCRIPT) else {
+                return;
+            };
+            while let Ok(url) = links.recv().await {
+                if let Some(url) = url.as_str() {
+                    if let Err(e) = browser::open(browser, url) {
+                        tracing::warn!("could not open {url} in {browser}: {e}");
+                    }
+                }
+            }
+        }
+    });
+    let loaded = future.value().is_some();
+    // The entry at the top of the list when the app closed, until it is
+    // scrolled back to.
+    let saved_top_entry = use_ref(cx, || {
+        SETTINGS
+            .restore_position_on_startup
+            .then(|| ListPosition::load().top_entry_id)
+            .flatten()
+    });
+    let saved_index = match future.value() {
+        Some(Ok(news)) => saved_top_entry
+            .read()
+            .as_ref()
+            .and_then(|id| news.entries.iter().position(|e| &e.1.id == id)),
+        _ => None,
+    };
+    use_effect(cx, (&loaded,), |(loaded,)| {
+        let saved_top_entry = saved_top_entry.clone();
+        let river_len = river_len.clone();
+        let eval = eval.clone();
+        async move {
+            if !loaded {
+                return;
+            }
+            let Some(id) = saved_top_entry.write_silent().take() else {
+                return;
+            };
+            // Fewer entries are listed than fetched, so listing as many as
+            // come before it in the fetched ones is enough to list the entry.
+            if let Some(index) = saved_index {
+                river_len.modify(|len| (*len).max(index + RIVER_BATCH));
+            }
+            // The entry may only be listed on the next frames, once the
+            // longer list is rendered.
+            eval(&format!(
+                "const scroll = tries => {{
+                    const li = [...document.querySelectorAll('li[data-entry-id]')]
+                        .find(li => li.dataset.entryId === {id:?});
+                    if (li) li.scrollIntoView({{block: \"start\"}});
+                    else if (tries > 0) requestAnimationFrame(() => scroll(tries - 1));
+                }};
+                scroll(60);"
+            ))
+            .ok();
+        }
+    });
+
+    let switch_view = move |new_view: View| {
+        let eval = eval.clone();
+        let view = view.clone();
+        let scroll_positions = scroll_positions.clone();
+        cx.spawn(async move {
+            if *view.current() == new_view {
+                return;
+            }
+            if let Ok(script) = eval("return window.scrollY;") {
+                if let Some(position) = script.join().await.ok().and_then(|p| p.as_f64()) {
+                    scroll_positions
+                        .write_silent()
+                        .insert(view.current().key(), position);
+                }
+            }
+            view.set(new_view);
+        });
+    };
+
+    cx.render(match future.value() {
+        Some(response) => rsx! {
+            style { include_str!("../style.css") }
+            style { media: "print", include_str!("../print.css") }
+            if let Some(release) = update.get() {
+                rsx! {
+                    div {
+                        class: "update-banner",
+                        "Gluon News {release.version} is available. "
+                        a { href: "{release.release_url}", target: "_blank", "Download" }
+                        if !release.changelog.is_empty() {
+                            rsx! {
+                                details {
+                                    summary { "What's new" }
+                                    pre { "{release.changelog}" }
+                                }
+                            }
+                        }
+                        button { onclick: move |_| update.set(None), "Dismiss" }
+                    }
+                }
+            }
+
+            match response.as_ref().map(|news| &**news) {
+                Ok(News { entries, failures, reading_times, extracted, poll_estimates, commit_feeds, feed_colors, category_counts, scores, recommended, content_scores, topics, also_in, feed_titles, .. }) => {
+                    // The latest entry date and the unread count of each feed.
+                    let mut latest: BTreeMap<&String, (Option<DateTime<Utc>>, usize)> = BTreeMap::new();
+                    for e in entries {
+                        let (date, unread) = latest.entry(&e.0).or_default();
+                        *date = (*date).max(e.1.best_date());
+                        if !read.read().contains(&e.1.id) {
+                            *unread += 1;
+                        }
+                    }
+                    let media_feeds = media::media_feeds(entries);
+                    // The titles of the feeds in each bundle, which the sidebar lists
+                    // in place of those feeds.
+                    let bundles: Vec<(&String, Vec<String>, usize)> = SETTINGS
+                        .bundles
+                        .iter()
+                        .map(|bundle| {
+                            let titles: Vec<String> = bundles::resolve_bundle(bundle, &SETTINGS.bundles)
+                                .iter()
+                                .filter_map(|url| feed_titles.get(url).cloned())
+                                .collect();
+                            let unread = titles.iter().filter_map(|t| latest.get(t)).map(|(_, unread)| unread).sum();
+                            (&bundle.name, titles, unread)
+                        })
+                        .collect();
+                    let bundled: HashSet<String> = bundles.iter().flat_map(|(_, titles, _)| titles.iter().cloned()).collect();
+                    let now = Utc::now();
+                    let reading_time = move |e: &Entry| {
+                        reading_times.get(&e.id).copied().unwrap_or_else(|| {
+                            estimate_reading_time(word_count(&text::strip_html(&e.best_content())), wpm)
+                        })
+                    };
+                    let visible = move |e: &&(String, Entry)| {
+                        view.shows(&e.0, also_in.get(&e.1.id).map_or(&[], Vec::as_slice), &e.1.id, &collections.read())
+                            && date_filter.matches(e.1.best_date())
+                            && max_reading_time.is_none_or(|max| reading_time(&e.1) <= max)
+                            && category_filter.get().as_ref().is_none_or(|c| e.1.category_names().contains(c))
+                            && topic_filter
+                                .get()
+                                .as_ref()
+                                .and_then(|label| topics.iter().find(|t| &t.label() == label))
+                                .is_none_or(|t| t.entry_ids.contains(&e.1.id))
+                            && diff.get().as_ref().is_none_or(|(_, ids)| ids.contains(&e.1.id))
+                            && !e.1.domain().is_some_and(|d| muted.read().contains(&d))
+                            && !mute_rules.read().iter().any(|rule| rule.matches(&e.1))
+                            && !not_interested.read().contains(&e.1.id)
+                            && entry_matches(&e.1, search.get())
+                            && !(*unread_only.get() && read.read().contains(&e.1.id))
+                    };
+                    let listed = move || -> Vec<&(String, Entry)> {
+                        if *view.get() == View::Queue {
+                            queue
+                                .read()
+                                .ids()
+                                .iter()
+                                .filter_map(|id| entries.iter().find(|e| &e.1.id == id))
+                                .filter(visible)
+                                .collect()
+                        } else {
+                            entries.iter().filter(visible).collect()
+                        }
+                    };
+                    let time_zone = Tz::from_str(&SETTINGS.time_zone).unwrap_or(Tz::UTC);
+                    let listed_count = listed().len();
+                    let fold = fold_override.get().or_else(|| {
+                        filters::fold_position(
+                            listed().iter().map(|e| e.1.best_date()),
+                            SETTINGS.fold_after_n,
+                            SETTINGS.fold_after_hours,
+                            now,
+                        )
+                    });
+                    // The feeds listed on their own, which the feed menu offers to merge.
+                    let mergeable: &[String] = cx.bump().alloc(
+                        latest.keys().filter(|t| !bundled.contains(**t)).map(|t| (*t).clone()).collect::<Vec<_>>(),
+                    );
+
+                    rsx! {
+                        div {
+                            class: "layout",
+                            tabindex: "0",
+                            onmounted: move |evt| {
+                                let element = evt.inner().clone();
+                                cx.spawn(async move {
+                                    element.set_focus(true).await.ok();
+                                });
+                            },
+                            onkeydown: move |evt| {
+                                if editing_tags.get().is_some() {
+                                    return;
+                                }
+                                let Some(action) = keybindings::action(&evt.key(), evt.modifiers()) else {
+                                    return;
+                                };
+                                tracing::debug!("key {:?}: {}", evt.key(), action.description());
+                                let shown = listed();
+                                let position = focused.get().as_ref().and_then(|id| shown.iter().position(|e| &e.1.id == id));
+                                let focused_entry = position.map(|p| shown[p]);
+                                match action {
+                                    Action::NextEntry => {
+                                        let next = position.map_or(0, |p| p + 1).min(shown.len().saturating_sub(1));
+                                        if let Some(e) = shown.get(next) {
+                                            focused.set(Some(e.1.id.clone()));
+                                        }
+                                        if next >= *river_len.get() {
+                                            river_len.modify(|len| len + RIVER_BATCH);
+                                        }
+                                    }
+                                    Action::PreviousEntry => {
+                                        let previous = position.map_or(0, |p| p.saturating_sub(1));
+                                        if let Some(e) = shown.get(previous) {
+                                            focused.set(Some(e.1.id.clone()));
+                                        }
+                                    }
+                                    Action::NextUnread => {
+                                        // From the focused entry when there is one, else from the
+                                        // last one opened, starting over from the top at the end.
+                                        let start = position.map_or(*current_position.get(), |p| p + 1);
+                                        let is_unread = |i: &usize| !read.read().contains(&shown[*i].1.id);
+                                        let next = (start..shown.len()).find(is_unread).or_else(|| (0..start.min(shown.len())).find(is_unread));
+                                        match next {
+                                            Some(next) => {
+                                                let e = shown[next];
+                                                focused.set(None);
+                                                // Read entries leave the list when only unread ones are listed.
+                                                current_position.set(if *unread_only.get() { next } else { next + 1 });
+                                                if next >= *river_len.get() {
+                                                    river_len.set(next + RIVER_BATCH);
+                                                }
+                                                open_entry(&e.1);
+                                                if SETTINGS.link_target != LinkTarget::ContentPane {
+                                                    opened.set(Some(e.1.clone()));
+                                                }
+                                            }
+                                            None => notice.set(Some(String::from("No unread entries left"))),
+                                        }
+                                    }
+                                    Action::ToggleStar => focused_entry.into_iter().for_each(|e| toggle_star(&e.1)),
+                                    Action::MuteEntry => focused_entry.into_iter().for_each(|e| mute_entry(&e.1)),
+                                    Action::NotInterested => focused_entry.into_iter().for_each(|e| hide_entry(&e.1)),
+                                    Action::EditTags => focused_entry.into_iter().for_each(|e| edit_tags(&e.1)),
+                                    Action::ToggleUnreadOnly => unread_only.modify(|u| !u),
+                                    Action::NewWindow => open_window(),
+                                    Action::KeyboardHelp => show_keyboard_help.set(true),
+                                }
+                            },
+                            nav {
+                                class: "sidebar",
+                                button {onclick: move |_| switch_view(View::All), "All"}
+                                button {
+                                    class: if *unread_only.get() { "active" } else { "" },
+                                    title: "u",
+                                    onclick: move |_| unread_only.modify(|u| !u),
+                                    "Unread only"
+                                }
+                                button {onclick: move |_| switch_view(View::Queue), "Queue"}
+                                button {onclick: move |_| switch_view(View::Reminders), "Reminders"}
+                                button {onclick: move |_| switch_view(View::Stats), "Statistics"}
+                                button {onclick: move |_| show_catch_up.set(true), "Catch Me Up"}
+                                button {onclick: move |_| show_bulk_add.set(true), "Add feeds"}
+                                button {onclick: move |_| open_window(), title: "Ctrl+Shift+T", "New window"}
+                                button {
+                                    onclick: move |_| {
+                                        let uri = share::share_uri(&SETTINGS.all_feeds().cloned().collect::<Vec<_>>());
+                                        eval(&format!("navigator.clipboard.writeText({uri:?});")).ok();
+                                        notice.set(Some(String::from("Subscriptions link copied")));
+                                    },
+                                    title: "Copy a gluon-news:// link that imports your feeds",
+                                    "Share subscriptions"
+                                }
+                                button {onclick: move |_| show_share_qr.set(true), "Share as QR code"}
+                                for (name, titles, unread) in bundles {
+                                    div {
+                                        class: "feed-row",
+                                        button {
+                                            onclick: move |_| switch_view(View::Bundle(name.clone(), titles.clone())),
+                                            "📦 {name}"
+                                            span { class: "freshness", " ({unread})" }
+                                        }
+                                    }
+                                }
+                                // Feeds with nothing left to read are collapsed in unread only mode.
+                                for (title, (date, unread)) in latest.into_iter().filter(|(title, (_, unread))| !bundled.contains(*title) && (!*unread_only.get() || *unread > 0)) {
+                                    div {
+                                        class: "feed-row",
+                                        style: "border-left: 4px solid {feed_colors.get(title).cloned().unwrap_or_default()};",
+                                        prevent_default: "oncontextmenu",
+                                        oncontextmenu: move |_| feed_menu.set(Some(title.clone())),
+                                        if feed_menu.get().as_ref() == Some(title) {
+                                            rsx! {
+                                                FeedMenu {
+                                                    others: mergeable.iter().filter(|t| *t != title).cloned().collect(),
+                                                    merged: merged_feeds.read().iter().any(|m| &m.title == title),
+                                                    refreshing: if selected_feeds.get().contains(title) { selected_feeds.get().len() } else { 1 },
+                                                    on_merge: move |other: String| merge_with(title, &other, feed_titles),
+                                                    on_split: move |_| split_feed(title),
+                                                    on_force_refresh: move |_| force_refresh(title, feed_titles),
+                                                    on_cancel: move |_| feed_menu.set(None),
+                                                }
+                                            }
+                                        }
+                                        input {
+                                            r#type: "checkbox",
+                                            title: "Select to compare or merge",
+                                            checked: selected_feeds.get().contains(title),
+                                            onclick: move |_| toggle_selected(title),
+                                        }
+                                        button {
+                                            onclick: move |_| switch_view(View::Feed(title.clone())),
+                                            "{title}"
+                                            span { class: "freshness", " ({unread})" }
+                                            if let Some(date) = date {
+                                                let age = now - date;
+                                                rsx! {
+                                                    span { class: "freshness", " {humanize_duration(age)}" }
+                                                    if age > chrono::Duration::days(STALE_AFTER_DAYS) {
+                                                        rsx! { span { class: "badge stale", "stale" } }
+                                                    }
+                                                }
+                                            }
+                                            if let Some(estimate) = poll_estimates.get(title) {
+                                                rsx! {
+                                                    span {
+                                                        class: "freshness",
+                                                        title: "Estimated from when new entries arrived",
+                                                        " · every {text::format_interval(*estimate)}"
+                                                    }
+                                                }
+                                            }
+                                        }
+                                        if let Some(diff) = snapshot_diffs.read().get(title) {
+                                            rsx! {
+                                                button {
+                                                    class: "snapshot-badge",
+                                                    title: "Changes since the previous refresh",
+                                                    onclick: move |_| snapshot_view.set(Some(title.clone())),
+                                                    "({diff.summary()})"
+                                                }
+                                            }
+                                        }
+                                        if media_feeds.contains(title) {
+                                            rsx! {
+                                                button {
+                                                    title: "Media timeline",
+                                                    onclick: move |_| switch_view(View::MediaTimeline(title.clone())),
+                                                    "🎧"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                                if let [a, b] = selected_feeds.get().as_slice() {
+                                    rsx! {
+                                        button {
+                                            onclick: move |_| switch_view(View::Compare(a.clone(), b.clone())),
+                                            "Compare selected feeds"
+                                        }
+                                    }
+                                }
+                                if !selected_feeds.get().is_empty() {
+                                    rsx! {
+                                        button {
+                                            onclick: move |_| switch_view(View::Merged(selected_feeds.get().clone())),
+                                            "Merge selected feeds"
+                                        }
+                                    }
+                                }
+                                if !collections.read().is_empty() {
+                                    rsx! {
+                                        h3 { "Collections" }
+                                        for (name, count) in collections::sorted(&collections.read()) {
+                                            button {
+                                                onclick: move |_| switch_view(View::Collection(name.clone())),
+                                                "{name}"
+                                                span { class: "freshness", " {count}" }
+                                            }
+                                        }
+                                    }
+                                }
+                                if let Some(proxy) = &SETTINGS.tor_socks_proxy {
+                                    rsx! {
+                                        div {
+                                            class: "freshness",
+                                            "Fetching through Tor at {proxy}. Tor must be running separately."
+                                        }
+                                    }
+                                }
+                                if let Some(report) = health.read().as_ref() {
+                                    rsx! {
+                                        for url in &report.unreachable {
+                                            div {
+                                                class: "feed-error",
+                                                title: "{url}",
+                                                if report.disabled.contains(url) {
+                                                    rsx! { "{url}: unreachable, disabled" }
+                                                } else {
+                                                    rsx! { "{url}: unreachable at startup" }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                                for (url, error) in failures {
+                                    div {
+                                        class: "feed-error",
+                                        title: "{url}",
+                                        if let FeedError::RateLimited(_) = error {
+                                            rsx! { "{url} ({error})" }
+                                        } else {
+                                            rsx! { "{url}: {error}" }
+                                        }
+                                        if let (FeedError::OAuth(OAuthError::NotAuthorized), Some(auth)) = (error, SETTINGS.feed_options(url).auth.as_ref()) {
+                                            rsx! {
+                                                button {
+                                                    onclick: move |_| {
+                                                        let url = url.clone();
+                                                        let notice = notice.clone();
+                                                        let count = count.clone();
+                                                        cx.spawn(async move {
+                                                            match oauth::authorize_feed(&url, auth).await {
+                                                                Ok(()) => count.modify(|c| c + 1),
+                                                                Err(e) => notice.set(Some(format!("Could not authorize {url}: {e}"))),
+                                                            }
+                                                        });
+                                                    },
+                                                    "Authorize"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            ul {
+                                onmouseup: move |_| dragging_fold.set(false),
+                                li {
+                                    Toolbar {
+                                        items: SETTINGS.toolbar_items(),
+                                        refresh: render! {
+                                            button {onclick: move |_| {count += 1}, "Refresh"}
+                                            if let Some(estimate) = &estimate {
+                                                rsx! { span { class: "freshness", " {estimate}" } }
+                                            }
+                                            if let Some(progress) = fetch_progress.get() {
+                                                rsx! { RefreshProgress { progress: progress.clone() } }
+                                            }
+                                        },
+                                        search: render! {
+                                            div {
+                                                class: "search",
+                                                input {
+                                                    placeholder: "Search",
+                                                    value: "{search}",
+                                                    oninput: move |evt| search.set(evt.value.clone()),
+                                                    onclick: move |_| show_search_history.set(true),
+                                                    onkeydown: move |evt| {
+                                                        evt.stop_propagation();
+                                                        match evt.key() {
+                                                            Key::ArrowDown => show_search_history.set(true),
+                                                            Key::Escape => show_search_history.set(false),
+                                                            Key::Enter => submit_search(search.get()),
+                                                            _ => {}
+                                                        }
+                                                    },
+                                                }
+                                                if *show_search_history.get() {
+                                                    rsx! {
+                                                        SearchDropdown {
+                                                            history: search_history.read().clone(),
+                                                            on_select: move |query: String| {
+                                                                submit_search(&query);
+                                                                search.set(query);
+                                                            },
+                                                            on_clear: move |_| {
+                                                                search_history.write().clear();
+                                                                if let Err(e) = search_history::save(&search_history.read()) {
+                                                                    tracing::warn!("could not save search history: {e}");
+                                                                }
+                                                                show_search_history.set(false);
+                                                            },
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        },
+                                        date_filters: render! {
+                                            div {
+                                                for filter in DateFilter::ALL {
+                                                    button {
+                                                        class: if *date_filter.get() == filter { "chip active" } else { "chip" },
+                                                        onclick: move |_| date_filter.set(filter),
+                                                        filter.label()
+                                                    }
+                                                }
+                                            }
+                                        },
+                                        reading_speed: render! {
+                                            if let Some(wpm) = personalised_wpm {
+                                                rsx! { span { " Your reading speed: {wpm} wpm" } }
+                                            }
+                                        },
+                                        export: render! {
+                                            if !SETTINGS.export_targets.is_empty() {
+                                                rsx! {
+                                                    button {
+                                                        onclick: move |_| {
+                                                            let entries = entries.clone();
+                                                            let notice = notice.clone();
+                                                            cx.spawn(async move {
+                                                                let targets = SETTINGS.export_targets.len();
+                                                                notice.set(Some(match export_entries(entries).await {
+                                                                    0 => format!("Exported to {targets} targets"),
+                                                                    failed => format!("Export failed for {failed} of {targets} targets"),
+                                                                }));
+                                                            });
+                                                        },
+                                                        "Export to targets"
+                                                    }
+                                                }
+                                            }
+                                            if let View::Collection(name) = view.get() {
+                                                rsx! {
+                                                    div {
+                                                        button {
+                                                            onclick: move |_| export_collection(name, entries, ExportFormat::Json),
+                                                            "Export as JSON"
+                                                        }
+                                                        button {
+                                                            onclick: move |_| export_collection(name, entries, ExportFormat::Opml),
+                                                            "Export as OPML"
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        },
+                                        queue_depth: render! {
+                                            span { class: "freshness", " {queue.read().len()} queued" }
+                                        },
+                                        read_streak: render! {
+                                            span {
+                                                class: "freshness",
+                                                title: "Days in a row with something read",
+                                                " 🔥 {read_days.read().current_streak(streak::today())}"
+                                            }
+                                        },
+                                        data_fetched: render! {
+                                            span {
+                                                class: "freshness",
+                                                title: "Data fetched from all feeds so far",
+                                                " {data_fetched} fetched"
+                                            }
+                                        },
+                                        trackers_blocked: render! {
+                                            if SETTINGS.block_tracking_pixels {
+                                                rsx! {
+                                                    span {
+                                                        class: "freshness",
+                                                        title: "Tracking pixels removed from entries",
+                                                        " {sanitizer::trackers_blocked()} trackers blocked"
+                                                    }
+                                                }
+                                            }
+                                        },
+                                        focus_time: render! {
+                                            if SETTINGS.track_reading_time {
+                                                rsx! {
+                                                    span {
+                                                        class: "freshness",
+                                                        " Reading time today: {format_minutes(focus_time.read().minutes(streak::today()))}"
+                                                    }
+                                                }
+                                            }
+                                        },
+                                        reading_goal: render! {
+                                            if let Some(goal) = &SETTINGS.reading_goal {
+                                                let done = read_counts.read().in_period(goal.period, streak::today());
+                                                rsx! {
+                                                    span {
+                                                        class: "reading-goal",
+                                                        progress { max: "{goal.count}", value: "{done.min(goal.count)}" }
+                                                        span { class: "freshness", " {done}/{goal.count} articles {goal.period.label()}" }
+                                                    }
+                                                }
+                                            }
+                                        },
+                                        newsletter: render! {
+                                            if let Some(config) = &SETTINGS.newsletter {
+                                                rsx! {
+                                                    button {
+                                                        onclick: move |_| notice.set(Some(match newsletter::generate(config, entries) {
+                                                            Ok(path) => format!("Newsletter saved to {}", path.display()),
+                                                            Err(e) => format!("Could not generate the newsletter: {e}"),
+                                                        })),
+                                                        "Generate newsletter"
+                                                    }
+                                                }
+                                            }
+                                        },
+                                        reading_time_filter: render! {
+                                            label {
+                                                class: "reading-time-filter",
+                                                " Max reading time "
+                                                input {
+                                                    r#type: "range",
+                                                    min: "1",
+                                                    max: "{MAX_READING_TIME_FILTER}",
+                                                    value: "{max_reading_time.unwrap_or(MAX_READING_TIME_FILTER)}",
+                                                    oninput: move |evt| {
+                                                        max_reading_time.set(evt.value.parse().ok().filter(|&max| max < MAX_READING_TIME_FILTER));
+                                                    },
+                                                }
+                                                match max_reading_time.get() {
+                                                    Some(max) => rsx! { span { class: "freshness", " {max} min" } },
+                                                    None => rsx! { span { class: "freshness", " {MAX_READING_TIME_FILTER}+ min" } },
+                                                }
+                                            }
+                                        },
+                                    }
+                                    if let Some((since, ids)) = diff.get() {
+                                        let since = since.with_timezone(&time_zone).format("%H:%M");
+                                        rsx! {
+                                            span { " Showing {ids.len()} new entries since {since} " }
+                                            button { onclick: move |_| diff.set(None), "Show all" }
+                                        }
+                                    }
+                                    if *unread_only.get() {
+                                        rsx! {
+                                            span { class: "unread-only", " Unread only " }
+                                            button { onclick: move |_| unread_only.set(false), "Show read entries" }
+                                        }
+                                    }
+                                    if let Some(category) = category_filter.get() {
+                                        rsx! {
+                                            span { " Showing entries in {category} " }
+                                            button { onclick: move |_| category_filter.set(None), "Clear category filter" }
+                                        }
+                                    }
+                                    if let Some(notice) = notice.get() {
+                                        rsx! { span { class: "notice", " {notice}" } }
+                                    }
+                                    if let Some(rule) = &mute_suggestion {
+                                        rsx! {
+                                            MuteSuggestion {
+                                                rule: rule.clone(),
+                                                on_accept: accept_mute_rule,
+                                                on_dismiss: dismiss_mute_rule,
+                                            }
+                                        }
+                                    }
+                                }
+                                if let View::Stats = view.get() {
+                                    rsx! {
+                                        li {
+                                            class: "stats",
+                                            h3 { "Publication times" }
+                                            div { dangerous_inner_html: "{stats::heatmap_svg(&stats::publication_heatmap(entries))}" }
+                                            h3 { "Read streaks" }
+                                            div { "Current streak: {read_days.read().current_streak(streak::today())} days" }
+                                            div { "Longest streak: {read_days.read().longest_streak()} days" }
+                                            if let Some(goal) = &SETTINGS.reading_goal {
+                                                let streak = read_counts.read().goal_streak(goal, streak::today());
+                                                rsx! { div { "Reading goal met: {streak} {goal.period.unit()} in a row" } }
+                                            }
+                                            for (first, last) in read_days.read().streaks().into_iter().rev().take(STREAK_HISTORY_LEN) {
+                                                div {
+                                                    class: "freshness",
+                                                    "{first.format(STREAK_DATE_FORMAT)} to {last.format(STREAK_DATE_FORMAT)}: {streak::streak_len(first, last)} days"
+                                                }
+                                            }
+                                            if SETTINGS.track_reading_time {
+                                                rsx! {
+                                                    h3 { "Reading time" }
+                                                    div { dangerous_inner_html: "{stats::week_chart_svg(&focus_time.read().week(streak::today()))}" }
+                                                }
+                                            }
+                                            h3 { "Data per feed" }
+                                            table {
+                                                class: "telemetry",
+                                                tr {
+                                                    th { "Feed" }
+                                                    th { "Size" }
+                                                    th { "Entries" }
+                                                    th { "Per entry" }
+                                                    th { "Fetch" }
+                                                    th { "Parse" }
+                                                }
+                                                for t in telemetry.read().latest() {
+                                                    tr {
+                                                        td { "{t.url}" }
+                                                        td { "{format_bytes(t.response_bytes)}" }
+                                                        td { "{t.entry_count}" }
+                                                        td { "{format_bytes(t.bytes_per_entry())}" }
+                                                        td { "{t.fetch_time_ms} ms" }
+                                                        td { "{t.parse_time_ms} ms" }
+                                                    }
+                                                }
+                                            }
+                                            h3 { "Cache" }
+                                            div { "Full articles: {format_bytes(full_content::cache_size())}" }
+                                            button {
+                                                title: "Drop the articles no longer in any feed",
+                                                onclick: move |_| {
+                                                    let disabled: Vec<String> = health.read().as_ref().map(|report| report.disabled.clone()).unwrap_or_default();
+                                                    let searches: Vec<String> = SETTINGS.search_feeds.iter().map(|search| search.url()).collect();
+                                                    let unfetched = SETTINGS
+                                                        .enabled_feeds()
+                                                        .chain(synced_feeds.read().iter())
+                                                        .filter(|feed| !disabled.contains(feed) && !removed_feeds.read().contains(*feed))
+                                                        .chain(searches.iter())
+                                                        .filter(|feed| !feed_links.read().contains_key(*feed))
+                                                        .count();
+                                                    if unfetched > 0 {
+                                                        notice.set(Some(format!("Not vacuuming until {unfetched} more feeds have been fetched, to keep their articles")));
+                                                        return;
+                                                    }
+                                                    let feed_links = feed_links.read();
+                                                    let reminders = reminders.read();
+                                                    let links: HashSet<&str> = entries
+                                                        .iter()
+                                                        .filter_map(|e| e.1.best_link())
+                                                        .chain(feed_links.values().flatten().map(String::as_str))
+                                                        .chain(reminders.pending().iter().filter_map(|r| r.link.as_deref()))
+                                                        .collect();
+                                                    notice.set(Some(match full_content::vacuum(&links) {
+                                                        Ok(()) => format!("Cache is now {}", format_bytes(full_content::cache_size())),
+                                                        Err(e) => format!("Could not vacuum the cache: {e}"),
+                                                    }));
+                                                },
+                                                if SETTINGS.compress_cache { "Vacuum & compress" } else { "Vacuum" }
+                                            }
+                                        }
+                                    }
+                                }
+                                if let View::Reminders = view.get() {
+                                    rsx! {
+                                        li {
+                                            class: "reminders",
+                                            h3 { "Reminders ({reminders.read().pending().len()})" }
+                                            for reminder in reminders.read().pending().iter().cloned() {
+                                                ReminderItem {
+                                                    key: "{reminder.entry_id}",
+                                                    rescheduling: reminder_menu.get().as_ref() == Some(&reminder.entry_id),
+                                                    reminder: reminder,
+                                                    on_reschedule: move |entry_id| reminder_menu.set(Some(entry_id)),
+                                                    on_cancel: move |entry_id: String| update_reminders(&|r| r.cancel(&entry_id)),
+                                                    on_pick: move |reminder: Reminder| update_reminders(&|r| r.schedule(reminder.clone())),
+                                                    on_close_menu: move |_| reminder_menu.set(None),
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                                if let View::Compare(a, b) = view.get() {
+                                    // Each copy folded into an entry is compared under its own feed.
+                                    let copies: Vec<(String, Entry)> = entries
+                                        .iter()
+                                        .flat_map(|(title, entry)| {
+                                            std::iter::once(title)
+                                                .chain(also_in.get(&entry.id).into_iter().flatten())
+                                                .map(|title| (title.clone(), entry.clone()))
+                                        })
+                                        .collect();
+                                    let diff = diff_feeds(&merge_feeds(&[a], &copies), &merge_feeds(&[b], &copies));
+                                    rsx! {
+                                        li {
+                                            class: "compare",
+                                            div {
+                                                class: "compare-column",
+                                                h3 { "Only in {a} ({diff.only_a.len()})" }
+                                                for e in diff.only_a.iter() {
+                                                    div { a { href: "{e.best_link().unwrap_or_default()}", target: "_blank", "{e.best_title()}" } }
+                                                }
+                                            }
+                                            div {
+                                                class: "compare-column",
+                                                h3 { "In both feeds ({diff.both.len()})" }
+                                                for (e, _) in diff.both.iter() {
+                                                    div { a { href: "{e.best_link().unwrap_or_default()}", target: "_blank", "{e.best_title()}" } }
+                                                }
+                                            }
+                                            div {
+                                                class: "compare-column",
+                                                h3 { "Only in {b} ({diff.only_b.len()})" }
+                                                for e in diff.only_b.iter() {
+                                                    div { a { href: "{e.best_link().unwrap_or_default()}", target: "_blank", "{e.best_title()}" } }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                                if topics.len() > 1 {
+                                    rsx! {
+                                        li {
+                                            class: "topics",
+                                            for topic in topics {
+                                                button {
+                                                    class: if topic_filter.get().as_ref() == Some(&topic.label()) { "chip active" } else { "chip" },
+                                                    title: "{topic.entry_ids.len()} entries",
+                                                    onclick: move |_| {
+                                                        let label = topic.label();
+                                                        topic_filter.set((topic_filter.get().as_ref() != Some(&label)).then_some(label));
+                                                    },
+                                                    "{topic.label()} ({topic.entry_ids.len()})"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                                for (i, e) in listed().into_iter().enumerate().take(*river_len.get()) {
+                                    if fold == Some(i) {
+                                        rsx! {
+                                            Fold {
+                                                folded: listed_count - i,
+                                                on_drag_start: move |_| dragging_fold.set(true),
+                                            }
+                                        }
+                                    }
+                                    if fold.is_some_and(|fold| i >= fold) {
+                                        rsx! {
+                                            li {
+                                                class: "{entry_class(&e.1.id)} folded",
+                                                "data-entry-id": "{e.1.id}",
+                                                onmouseenter: move |_| if *dragging_fold.get() { fold_override.set(Some(i)) },
+                                                div {
+                                                    class: "compact-entry",
+                                                    onclick: move |_| open_entry(&e.1),
+                                                    "{e.1.best_title()}"
+                                                    span { class: "freshness", " {e.0}" }
+                                                }
+                                            }
+                                        }
+                                    } else {
+                                        rsx! {
+                                            li {
+                                                class: "{entry_class(&e.1.id)}",
+                                                "data-entry-id": "{e.1.id}",
+                                                onmouseenter: move |_| if *dragging_fold.get() { fold_override.set(Some(i)) },
+                                                prevent_default: "oncontextmenu",
+                                                oncontextmenu: move |_| collection_menu.set(Some(e.1.id.clone())),
+                                                if collection_menu.get().as_ref() == Some(&e.1.id) {
+                                                    rsx! {
+                                                        CollectionMenu {
+                                                            names: collections::sorted(&collections.read()).into_iter().map(|(name, _)| name).collect(),
+                                                            on_pick: move |name| add_to_collection(name, &e.1.id),
+                                                            on_cancel: move |_| collection_menu.set(None),
+                                                        }
+                                                    }
+                                                }
+                                                if let (View::MediaTimeline(_), Some(media)) = (view.get(), media::media_item(&e.1)) {
+                                                    rsx! {
+                                                        MediaCard {
+                                                            entry_id: e.1.id.clone(),
+                                                            title: e.1.best_title(),
+                                                            media: media,
+                                                            progress: playback_progress.clone(),
+                                                            on_open: move |_| open_entry(&e.1),
+                                                        }
+                                                    }
+                                                } else if commit_feeds.contains(&e.0) {
+                                                    rsx! {
+                                                        CommitEntry {
+                                                            title: e.1.best_title(),
+                                                            message: display_html(&e.1.best_content()),
+                                                            link: e.1.best_link().unwrap_or("N/A").to_owned(),
+                                                            sha: e.1.best_link().and_then(commits::sha).map(String::from),
+                                                            committer: e.1.authors.first().map(|a| a.name.clone()),
+                                                            line_changes: commits::line_changes(&e.1.best_summary()),
+                                                            published: match e.1.published.or(e.1.updated) {
+                                                                Some(published) => published.with_timezone(&time_zone),
+                                                                None => Utc::now().with_timezone(&Tz::UTC),
+                                                            },
+                                                            link_target: SETTINGS.link_target,
+                                                            starred: starred.read().contains(&e.1.id),
+                                                            tags: tags.read().get(&e.1.id).cloned().unwrap_or_default(),
+                                                            archived: (archived.read().contains(&e.1.id) || archive::is_archived(&e.1.id))
+                                                                .then(|| archive::archive_path(&e.1.id).display().to_string()),
+                                                            on_open: move |_| open_entry(&e.1),
+                                                        }
+                                                    }
+                                                } else {
+                                                    rsx! {
+                                                        Entry {
+                                                            entry_id: e.1.id.clone(),
+                                                            title: e.1.best_title(),
+                                                            summary: display_html(&e.1.best_summary()),
+                                                            link: e.1.best_link().unwrap_or("N/A").to_owned(),
+                                                            category: e.0.chars().take(100).collect::<String>(),
+                                                            also_in: also_in.get(&e.1.id).cloned().unwrap_or_default(),
+                                                            feed_color: feed_colors.get(&e.0).cloned(),
+                                                            author: e.1.authors.first().map(|a| a.name.clone()),
+                                                            avatar: e.1.authors.first()
+                                                                .and_then(|a| a.email.as_deref())
+                                                                .filter(|_| SETTINGS.show_author_avatars)
+                                                                .map(gravatar_url),
+                                                            domain: e.1.domain().filter(|_| SETTINGS.expand_short_links),
+                                                            update_badge: e.1.freshness_badge(now).filter(|_| SETTINGS.show_update_badge),
+                                                            credibility: match e.1.best_link() {
+                                                                Some(link) if unsafe_links.read().contains(link) => Credibility::Suspicious,
+                                                                _ => e.1.domain().map_or(Credibility::Unknown, |d| reputation::credibility(&d)),
+                                                            },
+                                                            published: match e.1.published {
+                                                                Some(published) => published.with_timezone(&time_zone),
+                                                                None => Utc::now().with_timezone(&Tz::UTC),
+                                                            },
+                                                            reading_time: reading_time(&e.1),
+                                                            link_target: SETTINGS.link_target,
+                                                            starred: starred.read().contains(&e.1.id),
+                                                            recommended: recommended.contains(&e.1.id),
+                                                            tags: tags.read().get(&e.1.id).cloned().unwrap_or_default(),
+                                                            categories: e.1.category_names()
+                                                                .into_iter()
+                                                                .map(|c| { let count = category_counts.get(&c).copied().unwrap_or_default(); (c, count) })
+                                                                .collect(),
+                                                            archived: (archived.read().contains(&e.1.id) || archive::is_archived(&e.1.id))
+                                                                .then(|| archive::archive_path(&e.1.id).display().to_string()),
+                                                            via: e.1.links_with_rel("via").first().map(|link| (link.href.clone(), link_label(link))),
+                                                            related: e.1.links_with_rel("related")
+                                                                .into_iter()
+                                                                .map(|link| (link.href.clone(), link_label(link)))
+                                                                .collect(),
+                                                            on_open: move |_| open_entry(&e.1),
+                                                            on_category: move |category| category_filter.set(Some(category)),
+                                                        }
+                                                    }
+                                                }
+                                                if SETTINGS.debug {
+                                                    rsx! {
+                                                        div {
+                                                            class: "freshness",
+                                                            "score {scores.get(&e.1.id).copied().unwrap_or_default():.3}, content score {content_scores.get(&e.1.id).copied().unwrap_or_default():.2}"
+                                                        }
+                                                    }
+                                                }
+                                                div {
+                                                    class: "queue-actions",
+                                                    if queue.read().contains(&e.1.id) {
+                                                        rsx! {
+                                                            button { onclick: move |_| remove_from_queue(&e.1.id), "Remove from queue" }
+                                                            if *view.get() == View::Queue {
+                                                                rsx! {
+                                                                    button { onclick: move |_| update_queue(&|q| q.shift(&e.1.id, true)), "Move up" }
+                                                                    button { onclick: move |_| update_queue(&|q| q.shift(&e.1.id, false)), "Move down" }
+                                                                }
+                                                            }
+                                                        }
+                                                    } else {
+                                                        rsx! { button { onclick: move |_| update_queue(&|q| q.push(&e.1.id)), "Add to queue" } }
+                                                    }
+                                                    button {
+                                                        title: "Remind me",
+                                                        onclick: move |_| reminder_menu.set(Some(e.1.id.clone())),
+                                                        "⏰"
+                                                    }
+                                                    if let Some(reminder) = reminders.read().get(&e.1.id) {
+                                                        rsx! { span { class: "freshness", " {reminder.at.with_timezone(&time_zone).format(REMINDER_TIME_FORMAT)}" } }
+                                                    }
+                                                    if reminder_menu.get().as_ref() == Some(&e.1.id) {
+                                                        rsx! {
+                                                            ReminderMenu {
+                                                                on_pick: move |at| update_reminders(&|r| r.schedule(Reminder {
+                                                                    entry_id: e.1.id.clone(),
+                                                                    title: e.1.best_title(),
+                                                                    link: e.1.best_link().map(String::from),
+                                                                    at,
+                                                                })),
+                                                                on_cancel: move |_| reminder_menu.set(None),
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                                if editing_tags.get().as_ref() == Some(&e.1.id) {
+                                                    rsx! {
+                                                        input {
+                                                            class: "tag-editor",
+                                                            placeholder: "Tags, separated by commas",
+                                                            value: "{tag_draft}",
+                                                            oninput: move |evt| tag_draft.set(evt.value.clone()),
+                                                            onmounted: move |evt| {
+                                                                let element = evt.inner().clone();
+                                                                cx.spawn(async move {
+                                                                    element.set_focus(true).await.ok();
+                                                                });
+                                                            },
+                                                            onkeydown: move |evt| match evt.key() {
+                                                                Key::Enter => save_tags(&e.1.id),
+                                                                Key::Escape => close_tag_editor(),
+                                                                _ => {}
+                                                            },
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                                if listed_count > *river_len.get() {
+                                    rsx! { li { class: "river-end", "Loading more entries…" } }
+                                }
+                            }
+                            if let Some(entry) = opened.get() {
+                                rsx! {
+                                    ContentPane {
+                                        key: "{entry.id}",
+                                        title: entry.best_title(),
+                                        content: extracted.get(&entry.id).cloned().unwrap_or_else(|| entry.best_content()),
+                                        link: entry.best_link().unwrap_or("N/A").to_owned(),
+                                        extracted: extracted.contains_key(&entry.id),
+                                        on_close: move |_| {
+                                            finish_reading();
+                                            opened.set(None);
+                                        },
+                                    }
+                                }
+                            }
+                            if let Some(description) = undo_toast.get() {
+                                rsx! {
+                                    div {
+                                        class: "undo-toast",
+                                        "{description} "
+                                        button { onclick: move |_| undo(), "Undo" }
+                                    }
+                                }
+                            }
+                            if *show_catch_up.get() {
+                                let days = SETTINGS.catch_up_days.unwrap_or(DEFAULT_CATCH_UP_DAYS);
+                                let since = now - chrono::Duration::days(days as i64);
+                                rsx! {
+                                    CatchUp {
+                                        days: days,
+                                        groups: filters::unread_since(entries, &read.read(), since),
+                                        link_target: SETTINGS.link_target,
+                                        on_mark_read: move |ids: Vec<String>| {
+                                            let newly_read: Vec<String> = ids.into_iter().filter(|id| read.write().insert(id.clone())).collect();
+                                            if let Err(e) = read_state::save(&read.read()) {
+                                                tracing::warn!("could not save read state: {e}");
+                                            }
+                                            record_read_day();
+                                            if !newly_read.is_empty() {
+                                                push_undo(UndoAction::MarkAllRead(newly_read));
+                                            }
+                                        },
+                                        on_close: move |_| show_catch_up.set(false),
+                                    }
+                                }
+                            }
+                            if *show_keyboard_help.get() {
+                                rsx! { KeyboardHelpModal { on_close: move |_| show_keyboard_help.set(false) } }
+                            }
+                            if let Some((feed_title, diff)) = snapshot_view.get().as_ref().and_then(|t| Some((t.clone(), snapshot_diffs.read().get(t)?.clone()))) {
+                                rsx! {
+                                    SnapshotDiffView {
+                                        feed_title: feed_title,
+                                        diff: diff,
+                                        on_close: move |_| snapshot_view.set(None),
+                                    }
+                                }
+                            }
+                            if *show_share_qr.get() {
+                                rsx! {
+                                    ShareQrCode {
+                                        feeds: SETTINGS.all_feeds().cloned().collect(),
+                                        on_close: move |_| show_share_qr.set(false),
+                                    }
+                                }
+                            }
+                            if *show_bulk_add.get() {
+                                rsx! {
+                                    BulkAddFeeds {
+                                        existing: SETTINGS.all_feeds().chain(synced_feeds.read().iter()).cloned().collect(),
+                                        suggested: shared_feeds.read().clone(),
+                                        on_add: move |feeds: Vec<String>| {
+                                            shared_feeds.write_silent().clear();
+                                            if let Err(e) = config::add_feeds(&feeds) {
+                                                notice.set(Some(format!("Could not add feeds: {e}")));
+                                                return;
+                                            }
+                                            notice.set(Some(format!("Added {} feeds", feeds.len())));
+                                            // Settings are read once, so the new feeds are fetched
+                                            // along with the synced ones until the next start.
+                                            if !primary {
+                                                window_requests().send(WindowRequest::AddFeeds(feeds.clone())).ok();
+                                            }
+                                            synced_feeds.write_silent().extend(feeds);
+                                            show_bulk_add.set(false);
+                                            count.modify(|c| c + 1);
+                                        },
+                                        on_close: move |_| {
+                                            shared_feeds.write_silent().clear();
+                                            show_bulk_add.set(false);
+                                        },
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                Err(e) => rsx! {
+                    ul {
+                        li {
+                            div {"Could not fetch any news ({e}). Make sure you have a valid settings.json file."}
+                        }
+                    }
+                },
+            }
+        },
+        None => rsx! {
+            style { include_str!("../style.css") }
+            if let Some(estimate) = &estimate {
+                rsx! { div { class: "freshness", "{estimate}" } }
+            }
+            if let Some(progress) = fetch_progress.get() {
+                rsx! { RefreshProgress { progress: progress.clone() } }
+            }
+            ul {
+                class: "streamed",
+                for (feed_title, entry) in streamed.read().iter() {
+                    li {
+                        key: "{entry.id}",
+                        div {
+                            class: "compact-entry",
+                            "{entry.best_title()}"
+                            span { class: "freshness", " {feed_title}" }
+                        }
+                    }
+                }
+            }
+            SkeletonList { count: SETTINGS.max_total_entries.unwrap_or(DEFAULT_SKELETON_COUNT) }
+        },
+    })
+}
+
+fn window_config(settings: &Settings) -> Config {
+    Config::default()
+        .with_window(
+            WindowBuilder::new()
+                .with_title(match profile::active() {
+                    Some(name) => format!("Gluon News - {name}"),
+                    None => String::from("Gluon News"),
+                })
+                .with_maximized(settings.maximized)
+                .with_resizable(true)
+                .with_inner_size(dioxus_desktop::wry::application::dpi::LogicalSize::new(
+                    1000.0, 800.0,
+                )),
+        )
+        .with_custom_head(custom_head(settings))
+}
+
+/// The value of an option given as `--option <value>` or `--option=value`.
+fn arg_value(option: &str) -> Option<String> {
+    let args: Vec<String> = env::args().collect();
+    let prefix = format!("{option}=");
+
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == option {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix(&prefix).map(String::from)
+        }
+    })
+}
+
+/// The profile given with `--profile <name>`, or the one picked from a dialog
+/// when there are several. With no profiles at all, none is used.
+fn choose_profile() -> Option<String> {
+    let given = arg_value("--profile");
+
+    if given.is_some() {
+        return given;
+    }
+
+    let profiles = profile::list();
+
+    match profiles.len() {
+        0 => None,
+        1 => profiles.into_iter().next(),
+        _ => match profile_picker::pick(&profiles) {
+            Ok(Some(profile)) => Some(profile),
+            Ok(None) => process::exit(0),
+            Err(e) => {
+                eprintln!(
+                    "could not show the profile selector ({e}), opening {}",
+                    profiles[0]
+                );
+                profiles.into_iter().next()
+            }
+        },
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    if let Some(name) = &choose_profile() {
+        if let Err(e) = profile::select(name) {
+            eprintln!("could not open profile {name}: {e}");
+            process::exit(1);
+        }
+    }
+
+    if env::args().any(|arg| arg == "--check-config") {
+        process::exit(if config::check_config() { 0 } else { 1 });
+    }
+    if env::args().any(|arg| arg == "--dry-run") {
+        match config::read_settings() {
+            Ok(settings) => println!("{}", FetchPlan::new(&settings, Utc::now())),
+            Err(e) => {
+                println!("error: could not read settings.json: {e}");
+                process::exit(1);
+            }
+        }
+        process::exit(0);
+    }
+    // Links opened from a registered gluon-news:// handler arrive as arguments.
+    // Any web page can open one, so the feeds are only offered to be added.
+    if let Some(uri) = env::args().find(|arg| share::is_share_uri(arg)) {
+        match share::feeds_to_import(&uri) {
+            Ok(feeds) => {
+                SHARED_FEEDS.set(feeds).ok();
+            }
+            Err(e) => eprintln!("could not read the shared feeds: {e}"),
+        }
+    }
+
+    if let Some(path) = arg_value("--import-feedly") {
+        match feedly::import_feedly_backup(Path::new(&path)) {
+            Ok(stats) => eprintln!(
+                "imported {} new feeds, {} groups, {} titles and {} saved articles",
+                stats.feeds, stats.groups, stats.titles, stats.saved
+            ),
+            Err(e) => eprintln!("could not import the Feedly backup: {e}"),
+        }
+    }
+
+    // Logging is set up ahead of loading the settings, so the warnings given
+    // while loading them are logged.
+    let _log_guard = init_logging(config::read_log_level());
+    // On the first run the settings are only loaded once the wizard has
+    // written them.
+    let first_run = config::is_first_run();
+    let config = if first_run {
+        window_config(&Settings::default())
+    } else {
+        // Fetching without the proxy or resolver set up would expose what
+        // they are meant to hide.
+        if let Some(e) = client_error() {
+            eprintln!("error: {e}");
+            process::exit(1);
+        }
+        start_background_tasks();
+        window_config(&SETTINGS)
+    };
+
+    dioxus_desktop::launch_cfg(Root, config);
+}
+
+/// Logs to stderr, and at `debug` or `trace` to a daily log file in the
+/// profile instead, leaving stderr at `info`. Spans are logged to the file as
+/// they close, so their timings are kept. The guard flushes the file when
+/// dropped, so it must live until the app exits.
+fn init_logging(log_level: LogLevel) -> Option<WorkerGuard> {
+    let file = log_level
+        .logs_to_file()
+        .then(|| {
+            Builder::new()
+                .rotation(Rotation::DAILY)
+                .filename_prefix("gluon_news")
+                .filename_suffix("log")
+                .max_log_files(LOG_FILE_DAYS)
+                .build(profile::path(LOG_DIR))
+        })
+        .transpose();
+    let (file, error) = match file {
+        Ok(file) => (file, None),
+        Err(e) => (None, Some(e)),
+    };
+    let (writer, guard) = file.map(tracing_appender::non_blocking).unzip();
+    let stderr_level = if log_level.logs_to_file() {
+        LevelFilter::INFO
+    } else {
+        LevelFilter::from_level(log_level.level())
+    };
+
+    tracing_subscriber::registry()
+        .with(fmt::layer().with_filter(stderr_level))
+        .with(writer.map(|writer| {
+            fmt::layer()
+                .with_writer(writer)
+                .with_ansi(false)
+                .with_span_events(FmtSpan::CLOSE)
+                .with_filter(LevelFilter::from_level(log_level.level()))
+        }))
+        .init();
+    if let Some(e) = error {
+        tracing::warn!("could not open the log file: {e}");
+    }
+
+    guard
+}
+
+fn start_background_tasks() {
+    tokio::spawn(digest::run_scheduler());
+    if let Some(proxy) = &SETTINGS.tor_socks_proxy {
+        tokio::spawn(check_proxy_reachable(proxy));
+    }
 }
@@ -0,0 +1,111 @@
+//! Shows desktop notifications through the platform's notification command.
+
+use std::{
+    io,
+    process::Command,
+    thread,
+    time::{Duration, Instant},
+};
+
+const TITLE: &str = "gluon_news";
+const DIGEST_TITLES: usize = 2;
+
+#[cfg(target_os = "macos")]
+fn command(body: &str) -> Command {
+    let mut command = Command::new("osascript");
+    command
+        .args(["-e", "on run argv"])
+        .args([
+            "-e",
+            &format!("display notification (item 1 of argv) with title \"{TITLE}\""),
+        ])
+        .args(["-e", "end run"])
+        .arg(body);
+
+    command
+}
+
+#[cfg(target_os = "windows")]
+fn command(body: &str) -> Command {
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-Command"]).arg(format!(
+        "$m = [Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime];\
+         $t = $m::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02);\
+         $x = $t.GetElementsByTagName('text');\
+         $x.Item(0).AppendChild($t.CreateTextNode('{TITLE}')) > $null;\
+         $x.Item(1).AppendChild($t.CreateTextNode('{}')) > $null;\
+         $m::CreateToastNotifier('{TITLE}').Show([Windows.UI.Notifications.ToastNotification]::new($t))",
+        body.replace('\'', "''")
+    ));
+
+    command
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn command(body: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command.arg(TITLE).arg(body);
+
+    command
+}
+
+/// Shows a notification with the given body. The command is waited for on a
+/// separate thread, so it never blocks the UI.
+pub fn send(body: &str) -> io::Result<()> {
+    let mut child = command(body).spawn()?;
+
+    thread::spawn(move || child.wait());
+
+    Ok(())
+}
+
+/// Formats entry titles as "N new entries: first, second, and N-2 more".
+pub fn summary(titles: &[String]) -> String {
+    let entries = if titles.len() == 1 {
+        "entry"
+    } else {
+        "entries"
+    };
+    let shown = titles[..titles.len().min(DIGEST_TITLES)].join(", ");
+
+    match titles.len().saturating_sub(DIGEST_TITLES) {
+        0 => format!("{} new {entries}: {shown}", titles.len()),
+        more => format!("{} new {entries}: {shown}, and {more} more", titles.len()),
+    }
+}
+
+/// Collects the titles of new entries, so they can be shown as a single
+/// notification once the digest window has passed.
+pub struct NotificationBatcher {
+    window: Duration,
+    pending: Vec<String>,
+    last_flush: Instant,
+}
+
+impl NotificationBatcher {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: Vec::new(),
+            last_flush: Instant::now(),
+        }
+    }
+
+    pub fn push(&mut self, titles: impl IntoIterator<Item = String>) {
+        self.pending.extend(titles);
+    }
+
+    pub fn is_due(&self) -> bool {
+        !self.pending.is_empty() && self.last_flush.elapsed() >= self.window
+    }
+
+    /// Empties the batch and returns its summary, if there was anything in it.
+    pub fn flush(&mut self) -> Option<String> {
+        self.last_flush = Instant::now();
+        if self.pending.is_empty() {
+            return None;
+        }
+
+        Some(summary(&std::mem::take(&mut self.pending)))
+    }
+}
@@ -0,0 +1,57 @@
+//! Asks which profile to open through the platform's list dialog.
+
+use std::{io, process::Command};
+
+const PROMPT: &str = "Choose a profile";
+
+#[cfg(target_os = "macos")]
+fn command(profiles: &[String]) -> Command {
+    let mut command = Command::new("osascript");
+    command
+        .args(["-e", "on run argv"])
+        .args([
+            "-e",
+            &format!("set choice to choose from list argv with prompt \"{PROMPT}\""),
+        ])
+        .args(["-e", "if choice is false then return \"\""])
+        .args(["-e", "return item 1 of choice"])
+        .args(["-e", "end run"])
+        .args(profiles);
+
+    command
+}
+
+#[cfg(target_os = "windows")]
+fn command(profiles: &[String]) -> Command {
+    let names: Vec<String> = profiles
+        .iter()
+        .map(|p| format!("'{}'", p.replace('\'', "''")))
+        .collect();
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-Command"]).arg(format!(
+        "@({}) | Out-GridView -Title '{PROMPT}' -OutputMode Single",
+        names.join(",")
+    ));
+
+    command
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn command(profiles: &[String]) -> Command {
+    let mut command = Command::new("zenity");
+    command
+        .args(["--list", "--title=Gluon News", "--hide-header"])
+        .arg(format!("--text={PROMPT}"))
+        .arg("--column=Profile")
+        .args(profiles);
+
+    command
+}
+
+/// Returns the chosen profile, or `None` when the dialog was dismissed.
+pub fn pick(profiles: &[String]) -> io::Result<Option<String>> {
+    let output = command(profiles).output()?;
+    let choice = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+
+    Ok(profiles.contains(&choice).then_some(choice))
+}
@@ -0,0 +1,74 @@
+//! Reads text aloud through the platform's text-to-speech command.
+
+use std::{
+    io::{self, Write},
+    process::{Child, Command, Stdio},
+    thread,
+};
+
+const DEFAULT_WPM: f32 = 175.0;
+
+#[cfg(target_os = "macos")]
+fn command(rate: f32, voice: Option<&str>) -> Command {
+    let mut command = Command::new("say");
+    command
+        .arg("-r")
+        .arg(((DEFAULT_WPM * rate).round() as u32).to_string());
+    if let Some(voice) = voice {
+        command.arg("-v").arg(voice);
+    }
+
+    command
+}
+
+#[cfg(target_os = "windows")]
+fn command(rate: f32, voice: Option<&str>) -> Command {
+    // SAPI rates go from -10 to 10, with 0 as the normal speed.
+    let sapi_rate = ((rate - 1.0) * 10.0).round().clamp(-10.0, 10.0) as i32;
+    let select_voice = match voice {
+        Some(voice) => format!("$s.SelectVoice('{}');", voice.replace('\'', "''")),
+        None => String::new(),
+    };
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-Command"]).arg(format!(
+        "Add-Type -AssemblyName System.Speech;\
+         $s = New-Object System.Speech.Synthesis.SpeechSynthesizer;\
+         $s.Rate = {sapi_rate};{select_voice}\
+         $s.Speak([Console]::In.ReadToEnd())"
+    ));
+
+    command
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn command(rate: f32, voice: Option<&str>) -> Command {
+    let mut command = Command::new("espeak-ng");
+    command
+        .arg("--stdin")
+        .arg("-s")
+        .arg(((DEFAULT_WPM * rate).round() as u32).to_string());
+    if let Some(voice) = voice {
+        command.arg("-v").arg(voice);
+    }
+
+    command
+}
+
+/// Starts speaking the text and returns the TTS process, so it can be stopped.
+/// The text is written from a separate thread because the TTS command may only
+/// read it as fast as it speaks.
+pub fn speak(text: &str, rate: f32, voice: Option<&str>) -> io::Result<Child> {
+    let mut child = command(rate, voice).stdin(Stdio::piped()).spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let text = text.to_owned();
+        thread::spawn(move || stdin.write_all(text.as_bytes()));
+    }
+
+    Ok(child)
+}
+
+pub fn stop(child: &mut Child) {
+    child.kill().ok();
+    child.wait().ok();
+}
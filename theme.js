@@ -0,0 +1,19 @@
+// Sets data-theme on the root element from the configured theme mode, which
+// the app sets as window.themeMode. In system mode the theme follows the OS
+// colour scheme, also when it changes while the app runs. The app calls
+// window.applyTheme after changing window.themeMode.
+(function () {
+    var query = window.matchMedia("(prefers-color-scheme: dark)");
+
+    function apply() {
+        var mode = window.themeMode;
+        if (mode === "system") {
+            mode = query.matches ? "dark" : "light";
+        }
+        document.documentElement.dataset.theme = mode;
+    }
+
+    apply();
+    window.applyTheme = apply;
+    query.addEventListener("change", apply);
+})();